@@ -0,0 +1,36 @@
+use nes_emulator::apu::mixer::{MixerChannel, MixerConfig};
+use nes_emulator::apu::{APU, SAMPLE_RATE};
+use sdl2::audio::{AudioQueue, AudioSpecDesired};
+use sdl2::Sdl;
+
+// if we are way ahead of the sound card, we drop the queue instead of adding more delay
+const MAX_QUEUED_SECONDS: f32 = 0.25;
+
+pub fn open_audio_queue(sdl_context: &Sdl) -> AudioQueue<f32> {
+    let audio_subsystem = sdl_context.audio().unwrap();
+    let spec = AudioSpecDesired {
+        freq: Some(SAMPLE_RATE as i32),
+        channels: Some(1),
+        samples: Some(1024),
+    };
+    let queue = audio_subsystem.open_queue::<f32, _>(None, &spec).unwrap();
+    queue.resume();
+    queue
+}
+
+pub fn queued_seconds(queue: &AudioQueue<f32>) -> f32 {
+    queue.size() as f32 / (std::mem::size_of::<f32>() as f32 * SAMPLE_RATE as f32)
+}
+
+pub fn queue_samples(queue: &AudioQueue<f32>, samples: &[f32]) {
+    if queued_seconds(queue) > MAX_QUEUED_SECONDS {
+        queue.clear();
+    }
+    queue.queue_audio(samples).unwrap();
+}
+
+pub fn toggle_mute(mixer: &mut MixerConfig, apu: &mut APU, channel: MixerChannel) {
+    let muted = mixer.toggle_mute(channel);
+    apu.set_mixer(mixer);
+    println!("{channel:?} {}", if muted { "muted" } else { "unmuted" });
+}
@@ -8,7 +8,8 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
     tty::IsTty,
 };
-use nes_emulator::bus::Config;
+use nes_emulator::bus::controller::Player;
+use nes_emulator::config::{Config, ControllerBindings};
 use sdl2::keyboard::Keycode as sdl2_kc;
 
 macro_rules! write_and_flash {
@@ -41,7 +42,17 @@ enum Buttons {
     Start,
 }
 
-fn configure_key(stdout: &mut std::io::Stdout, conf: &mut Config, button: Buttons) {
+fn show_player(stdout: &mut std::io::Stdout, player: Player) {
+    write_and_flash!(
+        stdout,
+        "{}Binding keys of player {:?} - press (1) or (2) to switch player",
+        cursor::MoveTo(1, 9),
+        player
+    )
+    .unwrap();
+}
+
+fn configure_key(stdout: &mut std::io::Stdout, bindings: &mut ControllerBindings, button: Buttons) {
     execute!(
         stdout,
         cursor::MoveTo(1, 12),
@@ -73,14 +84,14 @@ fn configure_key(stdout: &mut std::io::Stdout, conf: &mut Config, button: Button
 
             if let Some(kc) = keycode {
                 match button {
-                    Buttons::Up => conf.up = kc,
-                    Buttons::Down => conf.down = kc,
-                    Buttons::Left => conf.left = kc,
-                    Buttons::Right => conf.right = kc,
-                    Buttons::A => conf.a = kc,
-                    Buttons::B => conf.b = kc,
-                    Buttons::Select => conf.select = kc,
-                    Buttons::Start => conf.start = kc,
+                    Buttons::Up => bindings.up = kc,
+                    Buttons::Down => bindings.down = kc,
+                    Buttons::Left => bindings.left = kc,
+                    Buttons::Right => bindings.right = kc,
+                    Buttons::A => bindings.a = kc,
+                    Buttons::B => bindings.b = kc,
+                    Buttons::Select => bindings.select = kc,
+                    Buttons::Start => bindings.start = kc,
                 }
 
                 execute!(
@@ -133,6 +144,9 @@ pub fn create_config(output_path: PathBuf) {
     )
     .unwrap();
 
+    let mut player = Player::One;
+    show_player(&mut stdout, player);
+
     write_and_flash!(
         stdout,
         "{}Press a key to bind: (arrows) | (a) | (b) | (p) for start | (o) for select
@@ -147,6 +161,10 @@ pub fn create_config(output_path: PathBuf) {
             code, modifiers, ..
         })) = event::read()
         {
+            let bindings = match player {
+                Player::One => &mut conf.p1,
+                Player::Two => &mut conf.p2,
+            };
             match code {
                 KeyCode::Char('q') if modifiers.contains(KeyModifiers::CONTROL) => {
                     execute!(
@@ -162,14 +180,22 @@ pub fn create_config(output_path: PathBuf) {
                     return;
                 }
                 KeyCode::Char('q') => break,
-                KeyCode::Char('a') => configure_key(&mut stdout, &mut conf, Buttons::A),
-                KeyCode::Char('b') => configure_key(&mut stdout, &mut conf, Buttons::B),
-                KeyCode::Up => configure_key(&mut stdout, &mut conf, Buttons::Up),
-                KeyCode::Down => configure_key(&mut stdout, &mut conf, Buttons::Down),
-                KeyCode::Left => configure_key(&mut stdout, &mut conf, Buttons::Left),
-                KeyCode::Right => configure_key(&mut stdout, &mut conf, Buttons::Right),
-                KeyCode::Char('o') => configure_key(&mut stdout, &mut conf, Buttons::Select),
-                KeyCode::Char('p') => configure_key(&mut stdout, &mut conf, Buttons::Start),
+                KeyCode::Char('1') => {
+                    player = Player::One;
+                    show_player(&mut stdout, player);
+                }
+                KeyCode::Char('2') => {
+                    player = Player::Two;
+                    show_player(&mut stdout, player);
+                }
+                KeyCode::Char('a') => configure_key(&mut stdout, bindings, Buttons::A),
+                KeyCode::Char('b') => configure_key(&mut stdout, bindings, Buttons::B),
+                KeyCode::Up => configure_key(&mut stdout, bindings, Buttons::Up),
+                KeyCode::Down => configure_key(&mut stdout, bindings, Buttons::Down),
+                KeyCode::Left => configure_key(&mut stdout, bindings, Buttons::Left),
+                KeyCode::Right => configure_key(&mut stdout, bindings, Buttons::Right),
+                KeyCode::Char('o') => configure_key(&mut stdout, bindings, Buttons::Select),
+                KeyCode::Char('p') => configure_key(&mut stdout, bindings, Buttons::Start),
                 KeyCode::Char('v') => {
                     for (i, l) in toml::to_string(&conf).unwrap().lines().enumerate() {
                         write_and_flash!(stdout, "{}{}", cursor::MoveTo(1, 15 + i as u16), l)
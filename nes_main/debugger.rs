@@ -0,0 +1,277 @@
+use crate::disasm::parse_address;
+use nes_emulator::breakpoints::{BreakOn, Breakpoint};
+use nes_emulator::debugger::{flags_view, registers_view, stack_view, Debugger};
+use nes_emulator::input::FrontendRequest;
+use nes_emulator::nes::Nes;
+use nes_emulator::symbols::{Labels, Symbols};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+use std::io::{stdout, Result, Stdout, Write};
+use std::time::Duration;
+
+const DISASSEMBLY_ROWS: usize = 20;
+const STACK_ROWS: usize = 12;
+const MEMORY_ROWS: usize = 8;
+const BREAKPOINT_ROWS: usize = 20;
+const DISASSEMBLY_COLUMN: u16 = 34;
+const BREAKPOINT_COLUMN: u16 = 78;
+const MEMORY_ROW: u16 = DISASSEMBLY_ROWS as u16 + 3;
+const HELP: &str = "s step  n step over  o step out  c run to cursor  r run  b break  \
+                    up/down cursor  g go to  m memory  pgup/pgdn scroll  \
+                    x toggle breakpoint  a add breakpoint  d delete breakpoint  q quit";
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Prompt {
+    GoTo,
+    Memory,
+    Breakpoint,
+    Delete,
+}
+
+// the debugger in the terminal that started the emulator. it takes the keys of the terminal, while the sdl window
+// keeps its own, and the emulation waits while the debugger is in control
+pub struct TuiDebugger {
+    debugger: Debugger,
+    stdout: Stdout,
+    prompt: Option<(Prompt, String)>,
+    message: String,
+    // the last thing that happened outside of the debugger (like a gamepad connecting)
+    notice: String,
+}
+
+impl TuiDebugger {
+    pub fn new(nes: &Nes, symbols: Symbols) -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stdout = stdout();
+        crossterm::execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let mut debugger = Debugger::new(nes);
+        debugger.symbols = symbols;
+        let mut tui = Self {
+            debugger,
+            stdout,
+            prompt: None,
+            message: String::new(),
+            notice: String::new(),
+        };
+        tui.draw(nes)?;
+        Ok(tui)
+    }
+
+    pub fn show_notice(&mut self, nes: &Nes, notice: String) {
+        // printing would mess up the screen, so it goes under the status line
+        self.notice = notice;
+        self.draw_or_complain(nes);
+    }
+
+    pub fn in_control(&self) -> bool {
+        self.debugger.in_control()
+    }
+
+    pub fn step_cycle(&mut self, nes: &mut Nes) -> bool {
+        // runs a cycle of the nes with the breakpoints, and shows where it stopped if it did
+        let frame_done = nes.step_cycle_with_hooks(&mut self.debugger);
+        if self.debugger.in_control() {
+            self.message = self
+                .debugger
+                .last_hit()
+                .map(|hit| hit.to_string())
+                .unwrap_or_default();
+            self.draw_or_complain(nes);
+        }
+        frame_done
+    }
+
+    pub fn poll(&mut self, nes: &Nes) -> Vec<FrontendRequest> {
+        // handles the keys pressed in the terminal since the last poll, without waiting for more
+        let mut requests = Vec::new();
+        while let Ok(true) = event::poll(Duration::ZERO) {
+            let Ok(Event::Key(key)) = event::read() else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if let Some(request) = self.handle_key(key, nes) {
+                requests.push(request);
+            }
+            self.draw_or_complain(nes);
+        }
+        requests
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, nes: &Nes) -> Option<FrontendRequest> {
+        if let Some((prompt, text)) = &mut self.prompt {
+            match key.code {
+                // a breakpoint can have a condition with anything in it, and the addresses can be labels
+                KeyCode::Char(c) if *prompt == Prompt::Breakpoint => text.push(c),
+                KeyCode::Char(c) if c.is_ascii_alphanumeric() || "$_@".contains(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let (prompt, text) = self.prompt.take().unwrap();
+                    self.message = self.prompt_done(prompt, &text, nes);
+                }
+                KeyCode::Esc => self.prompt = None,
+                _ => {}
+            }
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return Some(FrontendRequest::Quit),
+            // stops at the start of the next instruction
+            KeyCode::Char('b') | KeyCode::Esc if !self.in_control() => self.debugger.step(),
+            KeyCode::Char('m') => self.prompt = Some((Prompt::Memory, String::new())),
+            KeyCode::Char('a') => self.prompt = Some((Prompt::Breakpoint, String::new())),
+            KeyCode::Char('d') => self.prompt = Some((Prompt::Delete, String::new())),
+            KeyCode::PageUp => self.debugger.scroll_memory(-(MEMORY_ROWS as i32)),
+            KeyCode::PageDown => self.debugger.scroll_memory(MEMORY_ROWS as i32),
+            _ if !self.in_control() => {}
+            KeyCode::Char('s') => self.debugger.step(),
+            KeyCode::Char('n') => self.debugger.step_over(nes),
+            KeyCode::Char('o') => self.debugger.step_out(nes),
+            KeyCode::Char('c') => self.debugger.run_to_cursor(),
+            KeyCode::Char('r') => self.debugger.run(),
+            KeyCode::Char('g') => self.prompt = Some((Prompt::GoTo, String::new())),
+            KeyCode::Char('x') => {
+                let cursor = self.debugger.cursor();
+                self.debugger.breakpoints.toggle_address(cursor);
+            }
+            KeyCode::Up => self.debugger.cursor_up(nes, DISASSEMBLY_ROWS),
+            KeyCode::Down => self.debugger.cursor_down(nes, DISASSEMBLY_ROWS),
+            _ => {}
+        }
+        None
+    }
+
+    fn prompt_done(&mut self, prompt: Prompt, text: &str, nes: &Nes) -> String {
+        // returns what went wrong, if anything. the addresses can be labels too
+        match prompt {
+            Prompt::GoTo | Prompt::Memory => {
+                let label = self.debugger.labels(nes).address_of(text);
+                match label.ok_or(()).or_else(|_| parse_address(text)) {
+                    Ok(address) if prompt == Prompt::GoTo => self.debugger.go_to(address),
+                    Ok(address) => self.debugger.memory_address = address,
+                    Err(e) => return e,
+                }
+            }
+            Prompt::Breakpoint => {
+                let breakpoint = Breakpoint::parse(text, Some(&self.debugger.labels(nes)));
+                match breakpoint {
+                    Ok(breakpoint) => {
+                        let id = self.debugger.breakpoints.add(breakpoint);
+                        return format!("added breakpoint {id}");
+                    }
+                    Err(e) => return format!("{e:#}"),
+                }
+            }
+            Prompt::Delete => {
+                let removed = text
+                    .parse()
+                    .ok()
+                    .and_then(|id| self.debugger.breakpoints.remove(id));
+                if removed.is_none() {
+                    return format!("there is no breakpoint {text:?}");
+                }
+            }
+        }
+        String::new()
+    }
+
+    fn draw_or_complain(&mut self, nes: &Nes) {
+        // a broken terminal shouldn't stop the game
+        if let Err(e) = self.draw(nes) {
+            eprintln!("failed to draw the debugger: {e}");
+        }
+    }
+
+    fn draw(&mut self, nes: &Nes) -> Result<()> {
+        let out = &mut self.stdout;
+        queue!(out, Clear(ClearType::All))?;
+        let mut print_at = |column: u16, row: u16, text: &str| -> Result<()> {
+            queue!(out, cursor::MoveTo(column, row), Print(text))
+        };
+
+        print_at(0, 0, &"registers".bold().to_string())?;
+        print_at(0, 1, &registers_view(nes.cpu()))?;
+        let [names, bits] = flags_view(nes.cpu().status);
+        print_at(0, 3, &"flags".bold().to_string())?;
+        print_at(0, 4, &names)?;
+        print_at(0, 5, &bits)?;
+        print_at(0, 7, &"stack".bold().to_string())?;
+        for (i, line) in stack_view(nes, STACK_ROWS).iter().enumerate() {
+            print_at(0, 8 + i as u16, line)?;
+        }
+
+        print_at(DISASSEMBLY_COLUMN, 0, &"disassembly".bold().to_string())?;
+        let disassembly = self.debugger.disassembly_view(nes, DISASSEMBLY_ROWS);
+        for (i, line) in disassembly.iter().enumerate() {
+            let line = if line.as_bytes()[1] == b'*' {
+                line.clone().reverse().to_string()
+            } else {
+                line.clone()
+            };
+            print_at(DISASSEMBLY_COLUMN, 1 + i as u16, &line)?;
+        }
+
+        print_at(BREAKPOINT_COLUMN, 0, &"breakpoints".bold().to_string())?;
+        let labels = self.debugger.labels(nes);
+        for (i, (id, breakpoint)) in self
+            .debugger
+            .breakpoints
+            .iter()
+            .take(BREAKPOINT_ROWS)
+            .enumerate()
+        {
+            // the label of the address goes after it
+            let label = match breakpoint.on {
+                BreakOn::Address(address) => labels.label(address),
+                _ => None,
+            };
+            let line = match label {
+                Some(label) => format!("{id:>3}  {breakpoint}  {label}"),
+                None => format!("{id:>3}  {breakpoint}"),
+            };
+            print_at(BREAKPOINT_COLUMN, 1 + i as u16, &line)?;
+        }
+
+        print_at(0, MEMORY_ROW, &"memory".bold().to_string())?;
+        for (i, line) in self
+            .debugger
+            .memory_view(nes, MEMORY_ROWS)
+            .iter()
+            .enumerate()
+        {
+            print_at(0, MEMORY_ROW + 1 + i as u16, line)?;
+        }
+
+        let status_row = MEMORY_ROW + MEMORY_ROWS as u16 + 2;
+        print_at(0, status_row, &HELP.dim().to_string())?;
+        let status = match &self.prompt {
+            Some((Prompt::GoTo, text)) => format!("go to address: {text}"),
+            Some((Prompt::Memory, text)) => format!("memory address: {text}"),
+            Some((Prompt::Breakpoint, text)) => format!("breakpoint: {text}"),
+            Some((Prompt::Delete, text)) => format!("delete breakpoint: {text}"),
+            None if self.debugger.in_control() => self.message.clone(),
+            None => "running, b to break".to_string(),
+        };
+        print_at(0, status_row + 1, &status)?;
+        print_at(0, status_row + 2, &self.notice.as_str().dim().to_string())?;
+        out.flush()
+    }
+}
+
+impl Drop for TuiDebugger {
+    fn drop(&mut self) {
+        // gives the terminal back the way we found it
+        let _ = crossterm::execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
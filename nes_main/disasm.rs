@@ -0,0 +1,75 @@
+use nes_emulator::disasm::{disassemble, listing};
+use nes_emulator::symbols::Symbols;
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+const INES_HEADER_SIZE: usize = 16;
+const TRAINER_SIZE: usize = 512;
+const PRG_BANK_SIZE: usize = 0x4000;
+
+pub fn parse_address(text: &str) -> Result<u16, String> {
+    // hex, with or without $ or 0x in front
+    let digits = text
+        .strip_prefix('$')
+        .or_else(|| text.strip_prefix("0x"))
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u16::from_str_radix(digits, 16).map_err(|e| format!("bad address {text:?}: {e}"))
+}
+
+pub fn run(
+    rom_path: &Path,
+    bank: usize,
+    from: Option<u16>,
+    count: Option<usize>,
+    symbols: Option<&Symbols>,
+) -> Result<()> {
+    // disassembles a 16KB prg bank. the bank is shown where the cpu sees it: at 0xC000 for the last bank
+    // (that's where the vectors are) and at 0x8000 for the others, unless from is in the other half
+    let rom = std::fs::read(rom_path).with_context(|| format!("failed to read {rom_path:?}"))?;
+    if rom.len() < INES_HEADER_SIZE || rom[..4] != *b"NES\x1A" {
+        bail!("{rom_path:?} is not an ines file");
+    }
+    let banks = rom[4] as usize;
+    if bank >= banks {
+        bail!("there is no bank {bank}, the rom has {banks} prg banks");
+    }
+    let has_trainer = rom[6] & 0b100 != 0;
+    let start = INES_HEADER_SIZE + has_trainer as usize * TRAINER_SIZE + bank * PRG_BANK_SIZE;
+    let Some(prg_bank) = rom.get(start..start + PRG_BANK_SIZE) else {
+        bail!("{rom_path:?} is cut short");
+    };
+
+    let bank_address: u16 = match from {
+        Some(address) if address < 0x8000 => bail!("{address:#06x} is not in the cartridge rom"),
+        Some(address) => address & 0xC000,
+        None if bank == banks - 1 => 0xC000,
+        None => 0x8000,
+    };
+
+    // instructions that run past the end of the bank read zeros
+    let peek = |address: u16| {
+        prg_bank
+            .get(address.wrapping_sub(bank_address) as usize)
+            .copied()
+            .unwrap_or(0)
+    };
+    let bank_end = bank_address.wrapping_add(PRG_BANK_SIZE as u16 - 1);
+    let mut instructions = disassemble(from.unwrap_or(bank_address), bank_end, peek);
+    if let Some(count) = count {
+        instructions.truncate(count);
+    }
+    // the labels of the other banks at the same addresses don't show up
+    let labels = symbols.map(|symbols| {
+        symbols.mapped(|address| {
+            let offset = address.checked_sub(bank_address)? as usize;
+            (offset < PRG_BANK_SIZE).then_some(bank * PRG_BANK_SIZE + offset)
+        })
+    });
+    print!(
+        "{}",
+        listing(&instructions, labels.as_ref().map(|labels| labels as _))
+    );
+    Ok(())
+}
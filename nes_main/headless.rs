@@ -0,0 +1,57 @@
+use nes_emulator::bus::ram_init::RamInit;
+use nes_emulator::headless;
+use nes_emulator::input::script::InputScript;
+use nes_emulator::input::InputProvider;
+use nes_emulator::nes::Nes;
+use nes_emulator::tracer::Tracer;
+use nes_emulator::video::{write_png, write_ppm};
+
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub fn run(
+    rom_path: &Path,
+    frames: u64,
+    dump_frame: Option<&Path>,
+    input_script: Option<&Path>,
+    ram_init: RamInit,
+    tracer: Option<Tracer>,
+) -> Result<()> {
+    let rom = std::fs::read(rom_path).with_context(|| format!("failed to read {rom_path:?}"))?;
+    let mut script = input_script.map(InputScript::load).transpose()?;
+
+    let mut nes = Nes::from_rom_with_ram_init(&rom, ram_init);
+    nes.set_tracer(tracer);
+    let input = script
+        .as_mut()
+        .map(|script| script as &mut dyn InputProvider);
+    let report = headless::run_headless(&mut nes, frames, input);
+    println!("{report}");
+    if let Some(tracer) = nes.set_tracer(None) {
+        tracer.finish()?;
+    }
+
+    if let Some(path) = dump_frame {
+        dump(&nes, path).with_context(|| format!("failed to write the frame to {path:?}"))?;
+    }
+    Ok(())
+}
+
+fn dump(nes: &Nes, path: &Path) -> Result<()> {
+    // the format comes from the extension
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let write = match extension.as_deref() {
+        Some("png") => write_png::<BufWriter<File>>,
+        Some("ppm") => write_ppm::<BufWriter<File>>,
+        _ => bail!("unknown image format, expected a .png or a .ppm file"),
+    };
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(nes.frame(), &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
@@ -1,15 +1,38 @@
+mod audio;
 mod cli_config;
+mod debugger;
+mod disasm;
+mod headless;
+mod nsf_player;
+mod pause;
+mod rewind;
+mod save_states;
+mod speed;
+mod text;
+mod trace_diff;
 mod visual_config;
 
-use nes_emulator::bus::{Bus, Config};
-use nes_emulator::cpu::CPU;
+use nes_emulator::config::Config;
 use nes_emulator::generate_texture_canvas_event_pump;
-use nes_emulator::ppu::frame::Frame;
-use nes_emulator::ppu::PPU;
+use nes_emulator::input::movie::Movie;
+use nes_emulator::input::script::InputScript;
+use nes_emulator::input::sdl::SdlInput;
+use nes_emulator::input::{ControllerStates, FrontendRequest, InputProvider};
+use nes_emulator::nes::Nes;
+use nes_emulator::symbols::Symbols;
+use nes_emulator::test_roms;
+use nes_emulator::trace_diff::TraceField;
+use nes_emulator::tracer::{TraceRange, Tracer};
+use nes_emulator::video::{SdlSink, VideoSink};
 
 use clap::{Parser, Subcommand};
 use std::fs::{read_to_string, write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const AUDIO_CHUNK_SAMPLES: usize = 512;
+// while paused we only handle the input and show the last frame, about 60 times a second
+const PAUSED_REFRESH: Duration = Duration::from_millis(16);
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -26,6 +49,39 @@ struct Cli {
     /// optional config file
     #[arg(short = 'c', long, value_name = "config_file")]
     config_file: Option<PathBuf>,
+
+    /// optional save state file to start from
+    #[arg(long, value_name = "state_file", conflicts_with = "load_slot")]
+    load_state: Option<PathBuf>,
+
+    /// optional save state slot (0-9) of the game to start from
+    #[arg(long, value_name = "slot", value_parser = clap::value_parser!(u8).range(0..=9))]
+    load_slot: Option<u8>,
+
+    /// optional fceux movie (.fm2) to play the controllers from
+    #[arg(long, value_name = "movie_file", conflicts_with = "input_script")]
+    movie: Option<PathBuf>,
+
+    /// optional input script to play the controllers from
+    #[arg(long, value_name = "script_file")]
+    input_script: Option<PathBuf>,
+
+    /// optional file to write a line for every instruction to, in the nestest.log format
+    #[arg(long, value_name = "trace_file", global = true)]
+    trace: Option<PathBuf>,
+
+    /// the frames to trace, like 100..200 or 100.. (defaults to all of them)
+    #[arg(long, value_name = "frames", requires = "trace", global = true)]
+    trace_range: Option<TraceRange>,
+
+    /// control the emulator from a debugger in this terminal, it starts stopped at the first instruction
+    #[arg(long)]
+    debug: bool,
+
+    /// optional files with labels for the addresses, for the disassembler, the trace and the debugger
+    /// (ca65 .dbg, fceux .nl or mesen .mlb). can be given more than once, like for the .nl file of each bank
+    #[arg(long, value_name = "symbol_file", global = true)]
+    symbols: Vec<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -58,6 +114,65 @@ enum Commands {
         #[clap(default_value = "./nes_config.toml")]
         output_path: PathBuf,
     },
+    /// play an .nsf/.nsfe music file. the d-pad switches between the tracks
+    PlayNsf {
+        /// the music file
+        nsf_file: PathBuf,
+        /// the track to start from (1 based), defaults to the file's starting track
+        #[clap(long, short = 't')]
+        track: Option<u8>,
+    },
+    /// run a game without a window, and print the sha-256 of the last frame, the ram and the audio
+    RunHeadless {
+        /// the .nes file
+        #[clap(long)]
+        rom: PathBuf,
+        /// how many frames to run
+        #[clap(long)]
+        frames: u64,
+        /// optional file to write the last frame to, a .png or a .ppm
+        #[clap(long, value_name = "image_file")]
+        dump_frame: Option<PathBuf>,
+        /// optional input script to play the controllers from
+        #[clap(long, value_name = "script_file")]
+        input_script: Option<PathBuf>,
+    },
+    /// run every test rom (.nes) under a directory, and print which ones passed
+    TestRoms {
+        /// the directory with the test roms
+        dir: PathBuf,
+        /// how long a rom may run before it counts as failed
+        #[clap(long, default_value_t = test_roms::DEFAULT_TIMEOUT_FRAMES)]
+        timeout_frames: u64,
+    },
+    /// print the instructions in a prg bank of a rom
+    Disasm {
+        /// the .nes file
+        #[clap(long)]
+        rom: PathBuf,
+        /// the 16KB prg bank
+        #[clap(long, default_value_t = 0)]
+        bank: usize,
+        /// where to start (in hex), defaults to the start of the bank
+        #[clap(long, value_parser = disasm::parse_address)]
+        from: Option<u16>,
+        /// how many instructions to print, defaults to the rest of the bank
+        #[clap(long)]
+        count: Option<usize>,
+    },
+    /// compare two trace logs (nestest, like our tracer writes, mesen or fceux) and show where they diverge
+    TraceDiff {
+        /// our log
+        ours: PathBuf,
+        /// the log of the other emulator
+        theirs: PathBuf,
+        /// how many instructions to show before the divergence
+        #[clap(long, default_value_t = 5)]
+        context: usize,
+        /// fields not to compare, like cycles,ppu (pc, a, x, y, p, sp, cycles, ppu)
+        #[clap(long, value_delimiter = ',')]
+        ignore: Vec<TraceField>,
+    },
 }
 
 fn main() {
@@ -65,6 +180,113 @@ fn main() {
 
     let mut conf = Config::default();
 
+    if let Some(c) = &cli.config_file {
+        if let Ok(s) = read_to_string(c) {
+            if let Ok(t_conf) = toml::from_str(&s) {
+                conf = t_conf;
+            }
+        }
+    }
+
+    if let Some(Commands::PlayNsf { nsf_file, track }) = &cli.command {
+        if let Err(e) = nsf_player::play_nsf(nsf_file, *track, conf) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let symbols = match load_symbols(&cli.symbols) {
+        Ok(symbols) => symbols,
+        Err(e) => {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+    };
+
+    let tracer = match &cli.trace {
+        Some(path) => match Tracer::to_file(path, cli.trace_range.unwrap_or_default()) {
+            Ok(mut tracer) => {
+                tracer.set_symbols(symbols.clone());
+                Some(tracer)
+            }
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(Commands::RunHeadless {
+        rom,
+        frames,
+        dump_frame,
+        input_script,
+    }) = &cli.command
+    {
+        let result = headless::run(
+            rom,
+            *frames,
+            dump_frame.as_deref(),
+            input_script.as_deref(),
+            conf.ram_init,
+            tracer,
+        );
+        if let Err(e) = result {
+            // a failed run should fail the ci job
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::Disasm {
+        rom,
+        bank,
+        from,
+        count,
+    }) = &cli.command
+    {
+        if let Err(e) = disasm::run(rom, *bank, *from, *count, symbols.as_ref()) {
+            eprintln!("{e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Commands::TraceDiff {
+        ours,
+        theirs,
+        context,
+        ignore,
+    }) = &cli.command
+    {
+        match trace_diff::run(ours, theirs, *context, ignore) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(Commands::TestRoms {
+        dir,
+        timeout_frames,
+    }) = &cli.command
+    {
+        match run_test_roms(dir, *timeout_frames) {
+            Ok(true) => return,
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     if !cli.game_file.exists() {
         panic!("path {:?} does not exist", cli.game_file);
     }
@@ -88,51 +310,198 @@ fn main() {
                 }
             }
             Commands::CliEdit { output_path } => cli_config::create_config(output_path),
+            Commands::PlayNsf { .. }
+            | Commands::RunHeadless { .. }
+            | Commands::TestRoms { .. }
+            | Commands::Disasm { .. }
+            | Commands::TraceDiff { .. } => {
+                unreachable!("handled before loading the game")
+            }
         }
         return;
     }
 
-    if let Some(c) = &cli.config_file {
-        if let Ok(s) = read_to_string(c) {
-            if let Ok(t_conf) = toml::from_str(&s) {
-                conf = t_conf;
-            }
+    let state_file = match (cli.load_state, cli.load_slot) {
+        (Some(path), _) => Some(path),
+        (None, Some(slot)) => Some(save_states::slot_path(&cli.game_file, slot)),
+        (None, None) => None,
+    };
+    // the keyboard and the gamepads still control the emulator, but not the controllers
+    let playback: Option<Box<dyn InputProvider>> = match (cli.movie, cli.input_script) {
+        (Some(path), _) => Some(Box::new(Movie::load(&path).unwrap())),
+        (None, Some(path)) => Some(Box::new(InputScript::load(&path).unwrap())),
+        (None, None) => None,
+    };
+    run_emu(
+        cli.game_file,
+        conf,
+        state_file,
+        playback,
+        tracer,
+        cli.debug,
+        symbols,
+    );
+}
+
+fn run_test_roms(dir: &Path, timeout_frames: u64) -> anyhow::Result<bool> {
+    // returns true if all of them passed
+    let roms = test_roms::find_test_roms(dir)?;
+    let mut passed = 0;
+    for path in &roms {
+        let rom = std::fs::read(path)?;
+        let result = test_roms::run_test_rom(&rom, timeout_frames);
+        let name = path.strip_prefix(dir).unwrap_or(path).display();
+        println!("{} {name}", if result.passed() { "PASS" } else { "FAIL" });
+        if !result.passed() {
+            println!("  {}", result.to_string().replace('\n', "\n  "));
         }
+        passed += result.passed() as usize;
     }
+    println!("{passed}/{} passed", roms.len());
+    Ok(passed == roms.len())
+}
 
-    run_emu(cli.game_file, conf);
+fn load_symbols(paths: &[PathBuf]) -> anyhow::Result<Option<Symbols>> {
+    // all the files together, None when there are none
+    let mut symbols: Option<Symbols> = None;
+    for path in paths {
+        let loaded = Symbols::load(path)?;
+        symbols.get_or_insert_with(Symbols::new).extend(loaded);
+    }
+    Ok(symbols)
 }
 
-fn run_emu(game_path: PathBuf, conf: Config) {
-    let mut bus: Bus = Bus {
-        config: conf,
-        ..Default::default()
-    };
-    let bytes = std::fs::read(game_path).unwrap();
-    bus.cartridge.load_from_dump(&bytes);
+fn finish_trace(nes: &mut Nes) {
+    if let Some(tracer) = nes.set_tracer(None) {
+        if let Err(e) = tracer.finish() {
+            eprintln!("{e:#}");
+        }
+    }
+}
 
-    let mut bus_ref = &mut bus;
+fn next_controllers(
+    input: &mut SdlInput,
+    playback: &mut Option<Box<dyn InputProvider>>,
+) -> ControllerStates {
+    match playback {
+        Some(provider) => provider.next_frame(),
+        None => input.next_frame(),
+    }
+}
 
-    let mut cpu = CPU::new(bus_ref);
-    cpu.program_counter = cpu.read_memory_2_bytes(0xFFFC); //TODO: create cpu.reset or something?
-    bus_ref = cpu.bus.take().unwrap();
+fn run_emu(
+    game_path: PathBuf,
+    mut conf: Config,
+    state_file: Option<PathBuf>,
+    mut playback: Option<Box<dyn InputProvider>>,
+    tracer: Option<Tracer>,
+    debug: bool,
+    symbols: Option<Symbols>,
+) {
+    let bytes = std::fs::read(&game_path).unwrap();
+    let mut nes = Nes::from_rom_with_ram_init(&bytes, conf.ram_init);
+    nes.bus_mut().apu.set_mixer(&conf.mixer);
+    nes.set_tracer(tracer);
 
-    generate_texture_canvas_event_pump!(texture, canvas, event_pump);
-    let mut frame = Frame::new();
-    let mut ppu = PPU::new(bus_ref);
+    // the frame limiter sets the pace, so we don't wait for vsync (which depends on the monitor)
+    generate_texture_canvas_event_pump!(texture, canvas, event_pump, sdl_context, false);
+    let audio_queue = audio::open_audio_queue(&sdl_context);
+    let mut input = SdlInput::new(event_pump, &conf);
+    input.set_gamepad_subsystem(sdl_context.game_controller().unwrap());
+    let mut video = SdlSink::new(canvas, texture);
+
+    if let Some(path) = state_file {
+        if let Err(e) = save_states::load_from_file(&mut nes, &path) {
+            println!("{e:#}");
+            return;
+        }
+    }
+    let mut save_slots = save_states::SaveSlots::new(game_path);
+    let mut rewind = rewind::Rewind::new(&conf.rewind);
+    let mut speed = speed::Speed::new(conf.speed.clone());
+    let mut pause = pause::Pause::new();
+    let controllers = next_controllers(&mut input, &mut playback);
+    nes.bus_mut().set_controllers(controllers);
+    let mut debugger = if debug {
+        match debugger::TuiDebugger::new(&nes, symbols.unwrap_or_default()) {
+            Ok(debugger) => Some(debugger),
+            Err(e) => {
+                println!("failed to start the debugger: {e}");
+                return;
+            }
+        }
+    } else {
+        None
+    };
 
     loop {
-        bus_ref = ppu.bus.take().unwrap();
-        cpu.bus = Some(bus_ref);
-        cpu.run_one_cycle();
-
-        bus_ref = cpu.bus.take().unwrap();
-        ppu.bus = Some(bus_ref);
-        ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
-        ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
-        ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
-
-        // let sleep_time = time::Duration::from_secs_f64(0.001);
-        // sleep(sleep_time);
+        let mut frame_done = false;
+        let mut requests = Vec::new();
+        let debugger_in_control = debugger.as_ref().is_some_and(|d| d.in_control());
+        if pause.is_paused() || debugger_in_control {
+            // we usually poll the input when a frame is done, but the ppu doesn't run now
+            requests = input.poll_requests();
+            if let Some(debugger) = &mut debugger {
+                requests.extend(debugger.poll(&nes));
+            }
+            video.present(nes.frame()).unwrap();
+            std::thread::sleep(PAUSED_REFRESH);
+        } else {
+            frame_done = match &mut debugger {
+                Some(debugger) => debugger.step_cycle(&mut nes),
+                None => nes.step_cycle(),
+            };
+            pause.cpu_cycle_done(nes.cpu());
+        }
+
+        if frame_done {
+            video.present(nes.frame()).unwrap();
+            requests = input.poll_requests();
+            if let Some(debugger) = &mut debugger {
+                // to break in while the game runs
+                requests.extend(debugger.poll(&nes));
+            }
+            let controllers = next_controllers(&mut input, &mut playback);
+            nes.bus_mut().set_controllers(controllers);
+        }
+
+        if nes.bus().apu.number_of_samples() >= AUDIO_CHUNK_SAMPLES {
+            let samples = nes.bus_mut().apu.take_samples();
+            if speed.is_normal() {
+                audio::queue_samples(&audio_queue, &samples);
+            }
+        }
+
+        for event in input.take_gamepad_events() {
+            match &mut debugger {
+                Some(debugger) => debugger.show_notice(&nes, event.to_string()),
+                None => println!("{event}"),
+            }
+        }
+
+        for request in requests {
+            match request {
+                FrontendRequest::Quit => {
+                    finish_trace(&mut nes);
+                    return;
+                }
+                FrontendRequest::ToggleMute(channel) => {
+                    audio::toggle_mute(&mut conf.mixer, &mut nes.bus_mut().apu, channel)
+                }
+                FrontendRequest::StartRewind => rewind.set_rewinding(true),
+                FrontendRequest::StopRewind => rewind.set_rewinding(false),
+                FrontendRequest::SoftReset => nes.reset(),
+                FrontendRequest::HardReset => nes.power_on(),
+                _ => save_slots.handle_request(request, &mut nes),
+            }
+            speed.handle_request(request);
+            pause.handle_request(request);
+        }
+
+        if frame_done {
+            rewind.on_new_frame(&mut nes);
+            speed.wait_for_next_frame();
+            pause.frame_done();
+        }
     }
 }
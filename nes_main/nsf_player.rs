@@ -0,0 +1,152 @@
+use crate::audio::{open_audio_queue, queue_samples, queued_seconds, toggle_mute};
+use crate::text::{draw_text, CHAR_SPACING, GLYPH_HEIGHT, GLYPH_WIDTH};
+use anyhow::{Context, Result};
+use nes_emulator::bus::controller::{Player, BUTTON_DOWN, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_UP};
+use nes_emulator::bus::mappers::NSF_SUPPORTED_CHIPS;
+use nes_emulator::bus::Bus;
+use nes_emulator::config::Config;
+use nes_emulator::generate_texture_canvas_event_pump;
+use nes_emulator::input::sdl::SdlInput;
+use nes_emulator::input::{FrontendRequest, InputProvider};
+use nes_emulator::nsf::{NSFPlayer, NSF};
+use nes_emulator::ppu::frame::Frame;
+use nes_emulator::ppu::SCREEN_WIDTH;
+use nes_emulator::video::{SdlSink, VideoSink};
+use std::path::Path;
+use std::time::Duration;
+
+const TEXT_X: usize = 8;
+const LINE_HEIGHT: usize = GLYPH_HEIGHT + 5;
+const MAX_CHARS_IN_LINE: usize = (SCREEN_WIDTH - 2 * TEXT_X) / (GLYPH_WIDTH + CHAR_SPACING);
+const TITLE_COLOR: (u8, u8, u8) = (0xFF, 0xFF, 0xFF);
+const TEXT_COLOR: (u8, u8, u8) = (0xA6, 0xFC, 0xFF);
+const HELP_COLOR: (u8, u8, u8) = (0x80, 0x80, 0x80);
+// how much sound we keep ready before running the next play call
+const AUDIO_AHEAD_SECONDS: f32 = 0.05;
+// the screen rarely changes, so we don't present it (and wait for vsync) on every play call
+const PLAY_CALLS_BETWEEN_PRESENTS: u32 = 30;
+
+fn truncate_line(s: &str) -> String {
+    s.chars().take(MAX_CHARS_IN_LINE).collect()
+}
+
+fn draw_track_info(frame: &mut Frame, nsf: &NSF, song: u8) {
+    *frame = Frame::new();
+    let mut lines = vec![
+        (truncate_line(&nsf.title), TITLE_COLOR),
+        (truncate_line(&nsf.artist), TEXT_COLOR),
+        (truncate_line(&nsf.copyright), TEXT_COLOR),
+        (String::new(), TEXT_COLOR),
+        (format!("TRACK {song}/{}", nsf.total_songs), TITLE_COLOR),
+    ];
+    if let Some(track_title) = nsf.track_title(song) {
+        lines.push((truncate_line(track_title), TEXT_COLOR));
+    }
+    lines.push((String::new(), TEXT_COLOR));
+    lines.push(("LEFT/RIGHT - PREVIOUS/NEXT TRACK".to_string(), HELP_COLOR));
+    lines.push(("UP/DOWN - SKIP 10 TRACKS".to_string(), HELP_COLOR));
+
+    for (i, (line, color)) in lines.iter().enumerate() {
+        draw_text(
+            frame,
+            TEXT_X,
+            2 * LINE_HEIGHT + i * LINE_HEIGHT,
+            line,
+            *color,
+        );
+    }
+}
+
+fn next_song(current_song: u8, total_songs: u8, newly_pressed: u8) -> u8 {
+    // the d-pad moves between the tracks, wrapping around at the edges
+    let offset: i16 = match newly_pressed {
+        b if b & BUTTON_RIGHT != 0 => 1,
+        b if b & BUTTON_LEFT != 0 => -1,
+        b if b & BUTTON_UP != 0 => 10,
+        b if b & BUTTON_DOWN != 0 => -10,
+        _ => 0,
+    };
+    ((current_song as i16 - 1 + offset).rem_euclid(total_songs as i16) + 1) as u8
+}
+
+pub fn play_nsf(nsf_path: &Path, track: Option<u8>, mut conf: Config) -> Result<()> {
+    let bytes = std::fs::read(nsf_path).with_context(|| format!("failed to read {nsf_path:?}"))?;
+    let nsf = NSF::parse(&bytes).with_context(|| format!("failed to load {nsf_path:?}"))?;
+    if nsf.extra_sound_chips & !NSF_SUPPORTED_CHIPS != 0 {
+        println!(
+            "This file uses expansion sound chips ({:#04x}), some of them are not supported yet",
+            nsf.extra_sound_chips
+        );
+    }
+
+    let mut bus = Bus::default();
+    bus.apu.set_mixer(&conf.mixer);
+    generate_texture_canvas_event_pump!(texture, canvas, event_pump, sdl_context);
+    let mut video = SdlSink::new(canvas, texture);
+    let audio_queue = open_audio_queue(&sdl_context);
+    let mut input = SdlInput::new(event_pump, &conf);
+    input.set_gamepad_subsystem(sdl_context.game_controller().unwrap());
+    let mut player = NSFPlayer::new(&mut bus, nsf);
+    let mut frame = Frame::new();
+
+    let mut song = track.unwrap_or(player.nsf.starting_song);
+    let mut song_changed = true;
+    let mut previously_pressed = 0;
+    let mut play_calls_since_present = 0;
+
+    loop {
+        if song_changed {
+            player.start_song(song);
+            song = player.current_song();
+            draw_track_info(&mut frame, &player.nsf, song);
+            let window_title = format!("{} - {} ({song})", player.nsf.title, player.nsf.artist);
+            video
+                .canvas_mut()
+                .window_mut()
+                .set_title(&window_title)
+                .unwrap();
+            audio_queue.clear();
+            song_changed = false;
+            play_calls_since_present = PLAY_CALLS_BETWEEN_PRESENTS;
+        }
+
+        player.run_play_period();
+        let samples = player.cpu.bus.as_mut().unwrap().apu.take_samples();
+        queue_samples(&audio_queue, &samples);
+
+        let bus = player.cpu.bus.as_mut().unwrap();
+        for request in input.poll_requests() {
+            match request {
+                FrontendRequest::Quit => return Ok(()),
+                FrontendRequest::ToggleMute(channel) => {
+                    toggle_mute(&mut conf.mixer, &mut bus.apu, channel)
+                }
+                // save states, rewind and the rest don't mean anything for music
+                _ => {}
+            }
+        }
+        for event in input.take_gamepad_events() {
+            println!("{event}");
+        }
+        let pressed = input.next_frame()[Player::One.port()];
+        let newly_pressed = pressed & !previously_pressed;
+        previously_pressed = pressed;
+
+        let new_song = next_song(song, player.nsf.total_songs, newly_pressed);
+        if new_song != song {
+            song = new_song;
+            song_changed = true;
+        }
+
+        if play_calls_since_present >= PLAY_CALLS_BETWEEN_PRESENTS {
+            video.present(&frame).unwrap();
+            play_calls_since_present = 0;
+        }
+        play_calls_since_present += 1;
+
+        // the sound card sets the pace
+        while queued_seconds(&audio_queue) > AUDIO_AHEAD_SECONDS {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use nes_emulator::cpu::CPU;
+use nes_emulator::input::FrontendRequest;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum RunState {
+    Running,
+    Paused,
+    AdvancingFrame,      // runs until the end of the frame, and pauses
+    SteppingInstruction, // runs until the end of the cpu instruction, and pauses
+}
+
+pub struct Pause {
+    state: RunState,
+}
+
+impl Pause {
+    pub fn new() -> Self {
+        Self {
+            state: RunState::Running,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state == RunState::Paused
+    }
+
+    pub fn handle_request(&mut self, request: FrontendRequest) {
+        match request {
+            FrontendRequest::TogglePause => {
+                self.state = match self.state {
+                    RunState::Running => RunState::Paused,
+                    _ => RunState::Running,
+                };
+                println!(
+                    "{}",
+                    if self.is_paused() {
+                        "paused"
+                    } else {
+                        "resumed"
+                    }
+                );
+            }
+            // stepping while running pauses after the step
+            FrontendRequest::AdvanceFrame => self.state = RunState::AdvancingFrame,
+            FrontendRequest::StepInstruction => self.state = RunState::SteppingInstruction,
+            // the other requests are not about pausing
+            _ => {}
+        }
+    }
+
+    pub fn frame_done(&mut self) {
+        if self.state == RunState::AdvancingFrame {
+            self.state = RunState::Paused;
+        }
+    }
+
+    pub fn cpu_cycle_done(&mut self, cpu: &CPU) {
+        // the next instruction starts when the cpu has no more cycles to wait
+        if self.state == RunState::SteppingInstruction && cpu.cpu_idle_cycles == 0 {
+            self.state = RunState::Paused;
+            println!(
+                "PC:{:04X} A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X}",
+                cpu.program_counter,
+                cpu.register_a,
+                cpu.register_x,
+                cpu.register_y,
+                cpu.status,
+                cpu.stack_pointer
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use nes_emulator::nes::Nes;
+use nes_emulator::save_state::rewind::{RewindBuffer, RewindConfig};
+
+pub struct Rewind {
+    buffer: Option<RewindBuffer>, // None when rewinding is disabled in the config
+    rewinding: bool,
+}
+
+impl Rewind {
+    pub fn new(config: &RewindConfig) -> Self {
+        Self {
+            buffer: config.enabled.then(|| RewindBuffer::new(config)),
+            rewinding: false,
+        }
+    }
+
+    pub fn set_rewinding(&mut self, rewinding: bool) {
+        self.rewinding = rewinding;
+    }
+
+    pub fn on_new_frame(&mut self, nes: &mut Nes) {
+        // while rewinding every frame goes one snapshot back, otherwise we take a snapshot every few frames
+        let Some(buffer) = &mut self.buffer else {
+            return;
+        };
+        if self.rewinding {
+            if let Some(state) = buffer.rewind() {
+                if let Err(e) = nes.load_state(state) {
+                    println!("failed to rewind - {e:#}");
+                }
+            }
+            // the sound of the frames we just ran backwards
+            nes.bus_mut().apu.take_samples();
+        } else if buffer.snapshot_due() {
+            buffer.push(nes.save_state());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use nes_emulator::input::FrontendRequest;
+use nes_emulator::nes::Nes;
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+// the save state slots are kept next to the game, "mario.nes" slot 3 is "mario.state3"
+pub fn slot_path(game_path: &Path, slot: u8) -> PathBuf {
+    game_path.with_extension(format!("state{slot}"))
+}
+
+pub fn save_to_file(nes: &Nes, path: &Path) -> Result<()> {
+    let state = nes.save_state();
+    std::fs::write(path, state).with_context(|| format!("failed to write {path:?}"))
+}
+
+pub fn load_from_file(nes: &mut Nes, path: &Path) -> Result<()> {
+    let state = std::fs::read(path).with_context(|| format!("failed to read {path:?}"))?;
+    nes.load_state(&state)
+        .with_context(|| format!("failed to load save state {path:?}"))
+}
+
+pub struct SaveSlots {
+    game_path: PathBuf,
+    selected_slot: u8,
+}
+
+impl SaveSlots {
+    pub fn new(game_path: PathBuf) -> Self {
+        Self {
+            game_path,
+            selected_slot: 0,
+        }
+    }
+
+    pub fn handle_request(&mut self, request: FrontendRequest, nes: &mut Nes) {
+        let path = slot_path(&self.game_path, self.selected_slot);
+        match request {
+            FrontendRequest::SelectSaveSlot(slot) => {
+                self.selected_slot = slot;
+                println!("save state slot {slot} selected");
+            }
+            FrontendRequest::SaveState => match save_to_file(nes, &path) {
+                Ok(_) => println!("saved state to slot {}", self.selected_slot),
+                Err(e) => println!("{e:#}"),
+            },
+            FrontendRequest::LoadState => match load_from_file(nes, &path) {
+                Ok(_) => println!("loaded state from slot {}", self.selected_slot),
+                Err(e) => println!("{e:#}"),
+            },
+            // the other requests are not about save states
+            _ => {}
+        }
+    }
+}
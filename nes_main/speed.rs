@@ -0,0 +1,52 @@
+use nes_emulator::frame_limiter::{FrameLimiter, SpeedConfig};
+use nes_emulator::input::FrontendRequest;
+
+pub struct Speed {
+    config: SpeedConfig,
+    limiter: FrameLimiter,
+    fast_forward: bool,
+    slow_motion: bool,
+}
+
+impl Speed {
+    pub fn new(config: SpeedConfig) -> Self {
+        Self {
+            limiter: FrameLimiter::new(config.region),
+            config,
+            fast_forward: false,
+            slow_motion: false,
+        }
+    }
+
+    pub fn handle_request(&mut self, request: FrontendRequest) {
+        match request {
+            FrontendRequest::StartFastForward => self.fast_forward = true,
+            FrontendRequest::StopFastForward => self.fast_forward = false,
+            FrontendRequest::ToggleSlowMotion => {
+                self.slow_motion = !self.slow_motion;
+                println!(
+                    "slow motion {}",
+                    if self.slow_motion { "on" } else { "off" }
+                );
+            }
+            // the other requests are not about the speed
+            _ => return,
+        }
+        // fast forward wins over slow motion, while it's held
+        let multiplier = match (self.fast_forward, self.slow_motion) {
+            (true, _) => self.config.fast_forward_multiplier,
+            (false, true) => self.config.slow_motion_multiplier,
+            (false, false) => 1.0,
+        };
+        self.limiter.set_speed(Some(multiplier));
+    }
+
+    pub fn is_normal(&self) -> bool {
+        // the sound is only played at the normal speed, otherwise it gets out of sync (or out of tune)
+        self.limiter.speed() == Some(1.0)
+    }
+
+    pub fn wait_for_next_frame(&mut self) {
+        self.limiter.wait_for_next_frame();
+    }
+}
@@ -0,0 +1,50 @@
+use nes_emulator::trace_diff::{diff, DiffOptions, DiffResult, TraceField, TraceLog};
+
+use anyhow::Result;
+use std::path::Path;
+
+pub fn run(
+    ours_path: &Path,
+    theirs_path: &Path,
+    context: usize,
+    ignore: &[TraceField],
+) -> Result<bool> {
+    // prints the first place where the logs disagree, and returns whether they are the same
+    let ours = TraceLog::load(ours_path)?;
+    let theirs = TraceLog::load(theirs_path)?;
+    println!(
+        "{ours_path:?}: {} lines in the {} format",
+        ours.lines.len(),
+        ours.format
+    );
+    println!(
+        "{theirs_path:?}: {} lines in the {} format",
+        theirs.lines.len(),
+        theirs.format
+    );
+
+    let options = DiffOptions {
+        context,
+        ignore: ignore.iter().copied().collect(),
+    };
+    match diff(&ours, &theirs, &options)? {
+        DiffResult::Same {
+            compared,
+            ours_left,
+            theirs_left,
+        } => {
+            println!("the logs are the same for {compared} instructions");
+            if ours_left > 0 {
+                println!("our log goes on for {ours_left} more");
+            }
+            if theirs_left > 0 {
+                println!("their log goes on for {theirs_left} more");
+            }
+            Ok(true)
+        }
+        DiffResult::Diverged(divergence) => {
+            println!("{divergence}");
+            Ok(false)
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use nes_emulator::bus::Bus;
+use nes_emulator::bus::cartridge::Cartridge;
+use nes_emulator::cpu::flat_bus::FlatBus;
 use nes_emulator::cpu::CPU;
 use rand::rngs::ThreadRng;
 use rand::{thread_rng, Rng};
@@ -16,7 +17,7 @@ const SLEEP_TIME_NANOS: u128 = 1_000;
 const SCREEN_FACTOR: f32 = 10.0;
 
 struct SnakeGame<'a, 'sdl> {
-    cpu: CPU<'a>,
+    cpu: CPU<'a, FlatBus>, // the game only needs memory, so it runs on a flat bus and not on the nes
     event_pump: EventPump,
     screen_state: [u8; 32 * 32 * 3],
     rng_gen: ThreadRng,
@@ -52,30 +53,21 @@ impl<'a, 'sdl> SnakeGame<'a, 'sdl> {
             0xa2, 0x00, 0xa9, 0x01, 0x81, 0x10, 0x60, 0xa2, 0x00, 0xea, 0xea, 0xca, 0xd0, 0xfb,
             0x60,
         ];
-        for i in 0x0600..0x0600 + game_code.len() as u16 {
-            self.cpu.write_memory(i, game_code[i as usize - 0x600]);
-        }
-
-        let mut program_vector = vec![0_u8; 0x8000];
-        program_vector[0xFFFC - 0x8000] = 0x00;
-        program_vector[0xFFFD - 0x8000] = 0x06;
-        self.cpu
-            .bus
-            .as_mut()
-            .unwrap()
-            .cartridge
-            .raw_load(program_vector); // mem[0xFFFC] = 0x0600, little endian
+        let bus = self.cpu.bus.as_mut().unwrap();
+        bus.load(0x0600, &game_code);
+        bus.load(0xFFFC, &[0x00, 0x06]); // mem[0xFFFC] = 0x0600, little endian
         self.cpu.program_counter = self.cpu.read_memory_2_bytes(0xFFFC); // set pc from 0xFFFC
     }
 
     fn load_from_dump(&mut self) {
         let bytes = std::fs::read("./snake_game/snake.nes").unwrap();
-        self.cpu
-            .bus
-            .as_mut()
-            .unwrap()
-            .cartridge
-            .load_from_dump(&bytes);
+        let mut cartridge = Cartridge::new();
+        cartridge.load_from_dump(&bytes);
+        // copy the prg rom to where the cartridge is mapped
+        let prg_rom: Vec<u8> = (0..0x8000)
+            .map(|addr| cartridge.read_prg_rom(addr))
+            .collect();
+        self.cpu.bus.as_mut().unwrap().load(0x8000, &prg_rom);
         self.cpu.program_counter = self.cpu.read_memory_2_bytes(0xFFFC);
     }
 
@@ -279,7 +271,7 @@ fn main() {
 
     let current_frame = [0_u8; 32 * 32 * 3];
     let rng = thread_rng();
-    let mut bus = Bus::default();
+    let mut bus = FlatBus::new();
     let mut snake_game = SnakeGame {
         cpu: CPU::new(&mut bus),
         event_pump,
@@ -0,0 +1,172 @@
+use crate::apu::mixer::{MixerChannel, MixerConfig};
+use crate::apu::{APU, CPU_CLOCK_RATE, SAMPLE_RATE};
+
+fn run_cycles(apu: &mut APU, cycles: u32) {
+    for _ in 0..cycles {
+        apu.clock();
+    }
+}
+
+#[test]
+fn test_length_counter_loaded_only_when_enabled() {
+    let mut apu = APU::new();
+    apu.write_register(0x4003, 0b0000_1000); // length index 1
+    assert_eq!(apu.read_status() & 0x01, 0);
+
+    apu.write_register(0x4015, 0x01);
+    apu.write_register(0x4003, 0b0000_1000);
+    assert_eq!(apu.read_status() & 0x01, 0x01);
+
+    // disabling the channel clears the length counter
+    apu.write_register(0x4015, 0x00);
+    assert_eq!(apu.read_status() & 0x01, 0);
+}
+
+#[test]
+fn test_status_of_all_length_counters() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0x0F);
+    apu.write_register(0x4003, 0xF8);
+    apu.write_register(0x4007, 0xF8);
+    apu.write_register(0x400B, 0xF8);
+    apu.write_register(0x400F, 0xF8);
+    assert_eq!(apu.read_status(), 0x0F);
+}
+
+#[test]
+fn test_length_counter_runs_out() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0x01);
+    apu.write_register(0x4000, 0x00); // no halt
+    apu.write_register(0x4003, 0b0001_1000); // length index 3 = 2 half frames
+    run_cycles(&mut apu, 14913);
+    assert_eq!(apu.read_status() & 0x01, 0x01);
+    run_cycles(&mut apu, 29829 - 14913);
+    assert_eq!(apu.read_status() & 0x01, 0);
+}
+
+#[test]
+fn test_length_counter_halt() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0x01);
+    apu.write_register(0x4000, 0x20); // halt
+    apu.write_register(0x4003, 0b0001_1000);
+    run_cycles(&mut apu, 2 * 29830);
+    assert_eq!(apu.read_status() & 0x01, 0x01);
+}
+
+#[test]
+fn test_frame_irq() {
+    let mut apu = APU::new();
+    run_cycles(&mut apu, 29828);
+    assert!(!apu.irq_pending());
+    run_cycles(&mut apu, 1);
+    assert!(apu.irq_pending());
+    assert_eq!(apu.read_status() & 0x40, 0x40);
+    // reading the status clears the frame irq
+    assert!(!apu.irq_pending());
+    assert_eq!(apu.read_status() & 0x40, 0);
+}
+
+#[test]
+fn test_frame_irq_inhibit_and_5_step_mode() {
+    let mut apu = APU::new();
+    apu.write_register(0x4017, 0x40);
+    run_cycles(&mut apu, 2 * 29830);
+    assert!(!apu.irq_pending());
+
+    let mut apu = APU::new();
+    apu.write_register(0x4017, 0x80);
+    run_cycles(&mut apu, 2 * 37282);
+    assert!(!apu.irq_pending());
+}
+
+#[test]
+fn test_dmc_fetches_sample_bytes() {
+    let mut apu = APU::new();
+    apu.write_register(0x4012, 0x01); // sample in 0xC040
+    apu.write_register(0x4013, 0x00); // 1 byte long
+    assert_eq!(apu.needed_dmc_sample_address(), None);
+
+    apu.write_register(0x4015, 0x10);
+    assert_eq!(apu.read_status() & 0x10, 0x10);
+    assert_eq!(apu.needed_dmc_sample_address(), Some(0xC040));
+
+    apu.write_register(0x4010, 0x80); // irq when done
+    apu.load_dmc_sample_byte(0xFF);
+    assert_eq!(apu.needed_dmc_sample_address(), None);
+    assert_eq!(apu.read_status() & 0x90, 0x80);
+}
+
+#[test]
+fn test_samples_rate() {
+    let mut apu = APU::new();
+    run_cycles(&mut apu, CPU_CLOCK_RATE / 10);
+    let samples = apu.take_samples();
+    assert!((samples.len() as i64 - (SAMPLE_RATE / 10) as i64).abs() <= 1);
+    assert_eq!(apu.number_of_samples(), 0);
+}
+
+#[test]
+fn test_silent_apu_settles_to_zero() {
+    // the triangle holds a constant level even when silent, which the high pass should remove
+    let mut apu = APU::new();
+    run_cycles(&mut apu, CPU_CLOCK_RATE / 2);
+    assert!(apu.take_samples().last().unwrap().abs() < 0.0001);
+}
+
+#[test]
+fn test_pulse_makes_noise() {
+    let mut apu = APU::new();
+    apu.write_register(0x4015, 0x01);
+    apu.write_register(0x4000, 0b1011_1111); // 50% duty, constant max volume
+    apu.write_register(0x4002, 0xFD); // ~440hz
+    apu.write_register(0x4003, 0b0000_1000);
+    run_cycles(&mut apu, 10_000);
+    assert!(apu.take_samples().iter().any(|s| *s != 0.0));
+}
+
+fn max_amplitude_of_pulse(mixer: &MixerConfig) -> f32 {
+    let mut apu = APU::new();
+    apu.set_mixer(mixer);
+    apu.write_register(0x4015, 0x01);
+    apu.write_register(0x4000, 0b1011_1111);
+    apu.write_register(0x4002, 0xFD);
+    apu.write_register(0x4003, 0b0000_1000);
+    // let the high pass settle from the triangle dc level first
+    run_cycles(&mut apu, CPU_CLOCK_RATE / 2);
+    let samples = apu.take_samples();
+    samples[samples.len() - 1000..]
+        .iter()
+        .fold(0.0, |max, s| s.abs().max(max))
+}
+
+#[test]
+fn test_mixer_volume_and_mute() {
+    let mut mixer = MixerConfig::default();
+    let full_volume = max_amplitude_of_pulse(&mixer);
+
+    mixer.pulse_1.volume = 50;
+    let half_volume = max_amplitude_of_pulse(&mixer);
+    assert!(half_volume > 0.0 && half_volume < full_volume);
+
+    // muting the other channels doesn't matter
+    mixer.toggle_mute(MixerChannel::Pulse2);
+    mixer.toggle_mute(MixerChannel::Expansion);
+    assert_eq!(max_amplitude_of_pulse(&mixer), half_volume);
+
+    assert!(mixer.toggle_mute(MixerChannel::Pulse1));
+    assert!(max_amplitude_of_pulse(&mixer) < 0.0001);
+    assert!(!mixer.toggle_mute(MixerChannel::Pulse1));
+    assert_eq!(max_amplitude_of_pulse(&mixer), half_volume);
+}
+
+#[test]
+fn test_mixer_toggles_all_expansion_channels() {
+    let mut mixer = MixerConfig::default();
+    mixer.expansion[2].muted = true;
+    assert!(mixer.toggle_mute(MixerChannel::Expansion));
+    assert!(mixer.expansion.iter().all(|c| c.muted));
+    assert!(!mixer.toggle_mute(MixerChannel::Expansion));
+    assert!(mixer.expansion.iter().all(|c| !c.muted));
+}
@@ -0,0 +1,23 @@
+mod vrc6;
+
+pub use vrc6::VRC6Audio;
+
+// sound chips on the cartridge (or in nsf files) are mixed with the apu output on the cartridge edge connector.
+// every chip is owned by its mapper, which exposes it through Mapper::expansion_audio
+pub trait ExpansionAudio {
+    // called once every cpu cycle
+    fn clock(&mut self);
+    fn number_of_channels(&self) -> usize;
+    // the current output of a channel, already scaled to the units of the apu mixer (a full volume apu pulse is ~0.15)
+    fn channel_output(&self, channel: usize) -> f32;
+
+    // the output of the whole chip, with every channel multiplied by its mixer gain.
+    // chips that don't mix their channels linearly should override this
+    fn output(&self, gains: &[f32]) -> f32 {
+        (0..self.number_of_channels())
+            .map(|channel| {
+                self.channel_output(channel) * gains.get(channel).copied().unwrap_or(1.0)
+            })
+            .sum()
+    }
+}
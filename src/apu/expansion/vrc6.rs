@@ -0,0 +1,238 @@
+use crate::apu::expansion::ExpansionAudio;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+// a vrc6 pulse at full volume is about as loud as an apu pulse at full volume
+const VRC6_OUTPUT_SCALE: f32 = 0.00996;
+
+// see https://www.nesdev.org/wiki/VRC6_audio
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+struct VRC6Pulse {
+    volume: u8,
+    duty: u8,
+    ignore_duty: bool,
+    period: u16,
+    enabled: bool,
+    timer: u16,
+    duty_step: u8,
+}
+
+impl VRC6Pulse {
+    fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            0 => {
+                // MDDD VVVV
+                self.ignore_duty = value & 0x80 != 0;
+                self.duty = (value >> 4) & 0b111;
+                self.volume = value & 0x0F;
+            }
+            1 => self.period = (self.period & 0x0F00) | value as u16,
+            2 => {
+                // E--- FFFF
+                self.period = (self.period & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.enabled = value & 0x80 != 0;
+                if !self.enabled {
+                    self.duty_step = 15;
+                }
+            }
+            _ => unreachable!("vrc6 pulse has only 3 registers"),
+        }
+    }
+
+    fn clock_timer(&mut self, frequency_shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.timer == 0 {
+            self.timer = self.period >> frequency_shift;
+            self.duty_step = self.duty_step.checked_sub(1).unwrap_or(15);
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    fn output(&self) -> u8 {
+        if self.enabled && (self.ignore_duty || self.duty_step <= self.duty) {
+            self.volume
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+struct VRC6Sawtooth {
+    accumulator_rate: u8,
+    period: u16,
+    enabled: bool,
+    timer: u16,
+    step: u8,
+    accumulator: u8,
+}
+
+impl VRC6Sawtooth {
+    fn write_register(&mut self, register: u16, value: u8) {
+        match register {
+            0 => self.accumulator_rate = value & 0x3F,
+            1 => self.period = (self.period & 0x0F00) | value as u16,
+            2 => {
+                self.period = (self.period & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.enabled = value & 0x80 != 0;
+                if !self.enabled {
+                    self.step = 0;
+                    self.accumulator = 0;
+                }
+            }
+            _ => unreachable!("vrc6 sawtooth has only 3 registers"),
+        }
+    }
+
+    fn clock_timer(&mut self, frequency_shift: u8) {
+        if !self.enabled {
+            return;
+        }
+        if self.timer != 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.period >> frequency_shift;
+        // the rate is added to the accumulator every second step, and the 7th addition resets it instead
+        self.step += 1;
+        if self.step == 14 {
+            self.step = 0;
+            self.accumulator = 0;
+        } else if self.step.is_multiple_of(2) {
+            self.accumulator = self.accumulator.wrapping_add(self.accumulator_rate);
+        }
+    }
+
+    fn output(&self) -> u8 {
+        // only the top 5 bits of the accumulator reach the dac
+        self.accumulator >> 3
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct VRC6Audio {
+    pulse_1: VRC6Pulse,
+    pulse_2: VRC6Pulse,
+    sawtooth: VRC6Sawtooth,
+    halt: bool,
+    frequency_shift: u8,
+}
+
+impl VRC6Audio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_register(&mut self, addr: u16, value: u8) -> bool {
+        // gets the cpu address (after fixing the address lines for mapper 26)
+        // and returns true if it is a sound register
+        match addr {
+            0x9000..=0x9002 => self.pulse_1.write_register(addr - 0x9000, value),
+            0x9003 => {
+                // ---- -ABH - halt, 16x and 256x frequency. 256x takes priority
+                self.halt = value & 0x01 != 0;
+                self.frequency_shift = match value & 0b110 {
+                    0b100 | 0b110 => 8,
+                    0b010 => 4,
+                    _ => 0,
+                };
+            }
+            0xA000..=0xA002 => self.pulse_2.write_register(addr - 0xA000, value),
+            0xB000..=0xB002 => self.sawtooth.write_register(addr - 0xB000, value),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl ExpansionAudio for VRC6Audio {
+    fn clock(&mut self) {
+        if self.halt {
+            return;
+        }
+        self.pulse_1.clock_timer(self.frequency_shift);
+        self.pulse_2.clock_timer(self.frequency_shift);
+        self.sawtooth.clock_timer(self.frequency_shift);
+    }
+
+    fn number_of_channels(&self) -> usize {
+        3
+    }
+
+    fn channel_output(&self, channel: usize) -> f32 {
+        // the vrc6 mixes its channels linearly, so the default output works
+        let output = match channel {
+            0 => self.pulse_1.output(),
+            1 => self.pulse_2.output(),
+            2 => self.sawtooth.output(),
+            _ => 0,
+        };
+        output as f32 * VRC6_OUTPUT_SCALE
+    }
+}
+
+impl SaveState for VRC6Pulse {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.volume);
+        state.write_u8(self.duty);
+        state.write_bool(self.ignore_duty);
+        state.write_u16(self.period);
+        state.write_bool(self.enabled);
+        state.write_u16(self.timer);
+        state.write_u8(self.duty_step);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.volume = state.read_u8()?;
+        self.duty = state.read_u8()?;
+        self.ignore_duty = state.read_bool()?;
+        self.period = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        self.timer = state.read_u16()?;
+        self.duty_step = state.read_u8()?;
+        Ok(())
+    }
+}
+
+impl SaveState for VRC6Sawtooth {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.accumulator_rate);
+        state.write_u16(self.period);
+        state.write_bool(self.enabled);
+        state.write_u16(self.timer);
+        state.write_u8(self.step);
+        state.write_u8(self.accumulator);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.accumulator_rate = state.read_u8()?;
+        self.period = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        self.timer = state.read_u16()?;
+        self.step = state.read_u8()?;
+        self.accumulator = state.read_u8()?;
+        Ok(())
+    }
+}
+
+impl SaveState for VRC6Audio {
+    fn save_state(&self, state: &mut StateWriter) {
+        self.pulse_1.save_state(state);
+        self.pulse_2.save_state(state);
+        self.sawtooth.save_state(state);
+        state.write_bool(self.halt);
+        state.write_u8(self.frequency_shift);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.pulse_1.load_state(state)?;
+        self.pulse_2.load_state(state)?;
+        self.sawtooth.load_state(state)?;
+        self.halt = state.read_bool()?;
+        self.frequency_shift = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+// the most channels an expansion chip can have (namco 163 has 8)
+pub const MAX_EXPANSION_CHANNELS: usize = 8;
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct ChannelVolume {
+    pub volume: u8, // in percent, can be above 100 to amplify a channel
+    pub muted: bool,
+}
+
+impl Default for ChannelVolume {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            muted: false,
+        }
+    }
+}
+
+impl ChannelVolume {
+    pub fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume as f32 / 100.0
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum MixerChannel {
+    Pulse1,
+    Pulse2,
+    Triangle,
+    Noise,
+    DMC,
+    Expansion, // all the channels of the expansion chip together
+}
+
+// the per channel volumes, as written in the config file
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MixerConfig {
+    pub pulse_1: ChannelVolume,
+    pub pulse_2: ChannelVolume,
+    pub triangle: ChannelVolume,
+    pub noise: ChannelVolume,
+    pub dmc: ChannelVolume,
+    pub expansion: [ChannelVolume; MAX_EXPANSION_CHANNELS],
+}
+
+impl MixerConfig {
+    pub fn toggle_mute(&mut self, channel: MixerChannel) -> bool {
+        // returns whether the channel is muted now
+        let channel_volume = match channel {
+            MixerChannel::Pulse1 => &mut self.pulse_1,
+            MixerChannel::Pulse2 => &mut self.pulse_2,
+            MixerChannel::Triangle => &mut self.triangle,
+            MixerChannel::Noise => &mut self.noise,
+            MixerChannel::DMC => &mut self.dmc,
+            MixerChannel::Expansion => {
+                // if any of the channels can be heard we mute all of them, otherwise unmute all
+                let muted = self.expansion.iter().any(|c| !c.muted);
+                for channel_volume in self.expansion.iter_mut() {
+                    channel_volume.muted = muted;
+                }
+                return muted;
+            }
+        };
+        channel_volume.muted = !channel_volume.muted;
+        channel_volume.muted
+    }
+}
+
+// the volumes converted to multipliers, so the apu doesn't recompute them every cycle
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct ChannelGains {
+    pub(crate) pulse_1: f32,
+    pub(crate) pulse_2: f32,
+    pub(crate) triangle: f32,
+    pub(crate) noise: f32,
+    pub(crate) dmc: f32,
+    pub(crate) expansion: [f32; MAX_EXPANSION_CHANNELS],
+}
+
+impl From<&MixerConfig> for ChannelGains {
+    fn from(config: &MixerConfig) -> Self {
+        Self {
+            pulse_1: config.pulse_1.gain(),
+            pulse_2: config.pulse_2.gain(),
+            triangle: config.triangle.gain(),
+            noise: config.noise.gain(),
+            dmc: config.dmc.gain(),
+            expansion: config.expansion.map(|c| c.gain()),
+        }
+    }
+}
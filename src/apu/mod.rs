@@ -0,0 +1,340 @@
+#[cfg(test)]
+mod apu_tests;
+mod dmc;
+pub mod expansion;
+pub mod mixer;
+mod noise;
+mod pulse;
+mod triangle;
+mod units;
+
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+use dmc::DMCChannel;
+use mixer::{ChannelGains, MixerConfig};
+use noise::NoiseChannel;
+use pulse::PulseChannel;
+use triangle::TriangleChannel;
+
+pub const CPU_CLOCK_RATE: u32 = 1_789_773; // ntsc
+pub const SAMPLE_RATE: u32 = 44_100;
+
+// frame counter steps, in cpu cycles. see https://www.nesdev.org/wiki/APU_Frame_Counter
+const FRAME_COUNTER_QUARTER_1: u32 = 7457;
+const FRAME_COUNTER_HALF_1: u32 = 14913;
+const FRAME_COUNTER_QUARTER_3: u32 = 22371;
+const FRAME_COUNTER_4_STEP_LAST: u32 = 29829;
+const FRAME_COUNTER_5_STEP_LAST: u32 = 37281;
+
+// simple first order high pass, to remove the dc offset of the mixer (~37hz cutoff at 44.1khz)
+const HIGH_PASS_FACTOR: f32 = 0.996;
+// if nobody takes the samples, we keep at most one second of them
+const MAX_BUFFERED_SAMPLES: usize = SAMPLE_RATE as usize;
+
+#[derive(Debug, PartialEq)]
+pub struct APU {
+    pulse_1: PulseChannel,
+    pulse_2: PulseChannel,
+    triangle: TriangleChannel,
+    noise: NoiseChannel,
+    dmc: DMCChannel,
+    // the output of the cartridge sound chip, if there is one
+    expansion_output: f32,
+    gains: ChannelGains,
+    frame_counter_5_step_mode: bool,
+    frame_irq_inhibit: bool,
+    frame_irq_flag: bool,
+    frame_counter_cycles: u32,
+    cycles: u64,
+    // resampling the cpu rate output to SAMPLE_RATE
+    sample_clock: u32,
+    sample_accumulator: f32,
+    sample_accumulator_count: u32,
+    high_pass_previous_input: f32,
+    high_pass_previous_output: f32,
+    samples: Vec<f32>,
+}
+
+impl Default for APU {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl APU {
+    pub fn new() -> Self {
+        Self {
+            pulse_1: PulseChannel::new(true),
+            pulse_2: PulseChannel::new(false),
+            triangle: TriangleChannel::default(),
+            noise: NoiseChannel::new(),
+            dmc: DMCChannel::new(),
+            expansion_output: 0.0,
+            gains: ChannelGains::from(&MixerConfig::default()),
+            frame_counter_5_step_mode: false,
+            frame_irq_inhibit: false,
+            frame_irq_flag: false,
+            frame_counter_cycles: 0,
+            cycles: 0,
+            sample_clock: 0,
+            sample_accumulator: 0.0,
+            sample_accumulator_count: 0,
+            high_pass_previous_input: 0.0,
+            high_pass_previous_output: 0.0,
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn power_on(&mut self) {
+        // everything starts from scratch, but the gains belong to the user
+        *self = Self {
+            gains: self.gains.clone(),
+            ..Self::new()
+        };
+    }
+
+    pub fn reset(&mut self) {
+        // the reset silences all the channels, as if 0 was written to 0x4015, and restarts the frame counter.
+        // see https://www.nesdev.org/wiki/CPU_power_up_state
+        self.write_register(0x4015, 0);
+        self.frame_irq_flag = false;
+        self.dmc.irq_flag = false;
+        self.frame_counter_cycles = 0;
+    }
+
+    pub fn write_register(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x4000..=0x4003 => self.pulse_1.write_register(addr - 0x4000, value),
+            0x4004..=0x4007 => self.pulse_2.write_register(addr - 0x4004, value),
+            0x4008..=0x400B => self.triangle.write_register(addr - 0x4008, value),
+            0x400C..=0x400F => self.noise.write_register(addr - 0x400C, value),
+            0x4010..=0x4013 => self.dmc.write_register(addr - 0x4010, value),
+            0x4015 => {
+                // ---D NT21 - enable channels
+                self.pulse_1.length_counter.set_enabled(value & 0x01 != 0);
+                self.pulse_2.length_counter.set_enabled(value & 0x02 != 0);
+                self.triangle.length_counter.set_enabled(value & 0x04 != 0);
+                self.noise.length_counter.set_enabled(value & 0x08 != 0);
+                self.dmc.set_enabled(value & 0x10 != 0);
+            }
+            0x4017 => {
+                // MI-- ---- - mode and irq inhibit
+                self.frame_counter_5_step_mode = value & 0x80 != 0;
+                self.frame_irq_inhibit = value & 0x40 != 0;
+                if self.frame_irq_inhibit {
+                    self.frame_irq_flag = false;
+                }
+                self.frame_counter_cycles = 0;
+                if self.frame_counter_5_step_mode {
+                    self.clock_quarter_frame();
+                    self.clock_half_frame();
+                }
+            }
+            _ => panic!("address {addr:#06x} is not an apu register"),
+        }
+    }
+
+    pub fn read_status(&mut self) -> u8 {
+        // IF-D NT21 - dmc irq, frame irq, and which channels are still playing
+        let mut status = 0;
+        if self.pulse_1.length_counter.is_active() {
+            status |= 0x01;
+        }
+        if self.pulse_2.length_counter.is_active() {
+            status |= 0x02;
+        }
+        if self.triangle.length_counter.is_active() {
+            status |= 0x04;
+        }
+        if self.noise.length_counter.is_active() {
+            status |= 0x08;
+        }
+        if self.dmc.is_active() {
+            status |= 0x10;
+        }
+        if self.frame_irq_flag {
+            status |= 0x40;
+        }
+        if self.dmc.irq_flag {
+            status |= 0x80;
+        }
+        // reading the status clears the frame interrupt flag
+        self.frame_irq_flag = false;
+        status
+    }
+
+    pub fn irq_pending(&self) -> bool {
+        self.frame_irq_flag || self.dmc.irq_flag
+    }
+
+    pub fn needed_dmc_sample_address(&self) -> Option<u16> {
+        self.dmc.needed_sample_address()
+    }
+
+    pub fn load_dmc_sample_byte(&mut self, value: u8) {
+        self.dmc.load_sample_byte(value);
+    }
+
+    pub fn set_expansion_output(&mut self, output: f32) {
+        self.expansion_output = output;
+    }
+
+    pub fn set_mixer(&mut self, mixer: &MixerConfig) {
+        self.gains = ChannelGains::from(mixer);
+    }
+
+    pub fn expansion_gains(&self) -> &[f32] {
+        &self.gains.expansion
+    }
+
+    fn clock_quarter_frame(&mut self) {
+        self.pulse_1.envelope.clock();
+        self.pulse_2.envelope.clock();
+        self.noise.envelope.clock();
+        self.triangle.clock_linear_counter();
+    }
+
+    fn clock_half_frame(&mut self) {
+        self.pulse_1.length_counter.clock();
+        self.pulse_1.clock_sweep();
+        self.pulse_2.length_counter.clock();
+        self.pulse_2.clock_sweep();
+        self.triangle.length_counter.clock();
+        self.noise.length_counter.clock();
+    }
+
+    fn clock_frame_counter(&mut self) {
+        self.frame_counter_cycles += 1;
+        match self.frame_counter_cycles {
+            FRAME_COUNTER_QUARTER_1 | FRAME_COUNTER_QUARTER_3 => self.clock_quarter_frame(),
+            FRAME_COUNTER_HALF_1 => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            FRAME_COUNTER_4_STEP_LAST if !self.frame_counter_5_step_mode => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                if !self.frame_irq_inhibit {
+                    self.frame_irq_flag = true;
+                }
+                self.frame_counter_cycles = 0;
+            }
+            FRAME_COUNTER_5_STEP_LAST => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                self.frame_counter_cycles = 0;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clock(&mut self) {
+        // called once every cpu cycle
+        self.clock_frame_counter();
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        if self.cycles % 2 == 1 {
+            self.pulse_1.clock_timer();
+            self.pulse_2.clock_timer();
+        }
+        self.cycles += 1;
+
+        self.sample_accumulator += self.mix();
+        self.sample_accumulator_count += 1;
+        self.sample_clock += SAMPLE_RATE;
+        if self.sample_clock >= CPU_CLOCK_RATE {
+            self.sample_clock -= CPU_CLOCK_RATE;
+            self.push_sample();
+        }
+    }
+
+    fn mix(&self) -> f32 {
+        // the non linear mixer approximation from https://www.nesdev.org/wiki/APU_Mixer
+        // every channel is scaled by its mixer volume before going into the mixer
+        let pulse = self.pulse_1.output() as f32 * self.gains.pulse_1
+            + self.pulse_2.output() as f32 * self.gains.pulse_2;
+        let pulse_out = if pulse == 0.0 {
+            0.0
+        } else {
+            95.88 / (8128.0 / pulse + 100.0)
+        };
+
+        let tnd = self.triangle.output() as f32 * self.gains.triangle / 8227.0
+            + self.noise.output() as f32 * self.gains.noise / 12241.0
+            + self.dmc.output() as f32 * self.gains.dmc / 22638.0;
+        let tnd_out = if tnd == 0.0 {
+            0.0
+        } else {
+            159.79 / (1.0 / tnd + 100.0)
+        };
+
+        pulse_out + tnd_out + self.expansion_output
+    }
+
+    fn push_sample(&mut self) {
+        // average all the cpu cycles since the last sample, and remove the dc offset
+        let input = self.sample_accumulator / self.sample_accumulator_count as f32;
+        self.sample_accumulator = 0.0;
+        self.sample_accumulator_count = 0;
+
+        let output = HIGH_PASS_FACTOR
+            * (self.high_pass_previous_output + input - self.high_pass_previous_input);
+        self.high_pass_previous_input = input;
+        self.high_pass_previous_output = output;
+        if self.samples.len() >= MAX_BUFFERED_SAMPLES {
+            self.samples.drain(..MAX_BUFFERED_SAMPLES / 2);
+        }
+        self.samples.push(output);
+    }
+
+    pub fn number_of_samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+}
+
+impl SaveState for APU {
+    // the mixer gains come from the config, and the buffered samples are already on their way out
+    fn save_state(&self, state: &mut StateWriter) {
+        self.pulse_1.save_state(state);
+        self.pulse_2.save_state(state);
+        self.triangle.save_state(state);
+        self.noise.save_state(state);
+        self.dmc.save_state(state);
+        state.write_f32(self.expansion_output);
+        state.write_bool(self.frame_counter_5_step_mode);
+        state.write_bool(self.frame_irq_inhibit);
+        state.write_bool(self.frame_irq_flag);
+        state.write_u32(self.frame_counter_cycles);
+        state.write_u64(self.cycles);
+        state.write_u32(self.sample_clock);
+        state.write_f32(self.sample_accumulator);
+        state.write_u32(self.sample_accumulator_count);
+        state.write_f32(self.high_pass_previous_input);
+        state.write_f32(self.high_pass_previous_output);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.pulse_1.load_state(state)?;
+        self.pulse_2.load_state(state)?;
+        self.triangle.load_state(state)?;
+        self.noise.load_state(state)?;
+        self.dmc.load_state(state)?;
+        self.expansion_output = state.read_f32()?;
+        self.frame_counter_5_step_mode = state.read_bool()?;
+        self.frame_irq_inhibit = state.read_bool()?;
+        self.frame_irq_flag = state.read_bool()?;
+        self.frame_counter_cycles = state.read_u32()?;
+        self.cycles = state.read_u64()?;
+        self.sample_clock = state.read_u32()?;
+        self.sample_accumulator = state.read_f32()?;
+        self.sample_accumulator_count = state.read_u32()?;
+        self.high_pass_previous_input = state.read_f32()?;
+        self.high_pass_previous_output = state.read_f32()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::apu::units::{Envelope, LengthCounter};
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+// ntsc periods, in cpu cycles
+static NOISE_PERIOD_TABLE: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NoiseChannel {
+    mode: bool,
+    timer_period: u16,
+    timer: u16,
+    shift_register: u16,
+    pub(crate) envelope: Envelope,
+    pub(crate) length_counter: LengthCounter,
+}
+
+impl NoiseChannel {
+    pub fn new() -> Self {
+        Self {
+            mode: false,
+            timer_period: NOISE_PERIOD_TABLE[0],
+            timer: 0,
+            shift_register: 1, // the shift register is loaded with 1 on power up
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default(),
+        }
+    }
+
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        // register is the offset from 0x400C. 0x400D is unused
+        match register {
+            0 => {
+                self.length_counter.set_halt(value & 0x20 != 0);
+                self.envelope.write_control(value);
+            }
+            1 => {}
+            2 => {
+                // M--- PPPP
+                self.mode = value & 0x80 != 0;
+                self.timer_period = NOISE_PERIOD_TABLE[(value & 0x0F) as usize];
+            }
+            3 => {
+                self.length_counter.load(value >> 3);
+                self.envelope.restart();
+            }
+            _ => unreachable!("noise channel has only 4 registers"),
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // the period table is in cpu cycles, so this is clocked every cpu cycle
+        if self.timer == 0 {
+            self.timer = self.timer_period - 1;
+            let other_bit = if self.mode { 6 } else { 1 };
+            let feedback = (self.shift_register & 1) ^ ((self.shift_register >> other_bit) & 1);
+            self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.shift_register & 1 == 1 || !self.length_counter.is_active() {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
+
+impl SaveState for NoiseChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.mode);
+        state.write_u16(self.timer_period);
+        state.write_u16(self.timer);
+        state.write_u16(self.shift_register);
+        self.envelope.save_state(state);
+        self.length_counter.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.mode = state.read_bool()?;
+        self.timer_period = state.read_u16()?;
+        self.timer = state.read_u16()?;
+        self.shift_register = state.read_u16()?;
+        self.envelope.load_state(state)?;
+        self.length_counter.load_state(state)
+    }
+}
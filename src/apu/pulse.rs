@@ -0,0 +1,103 @@
+use crate::apu::units::{Envelope, LengthCounter, Sweep};
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+static DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0], // 12.5%
+    [0, 1, 1, 0, 0, 0, 0, 0], // 25%
+    [0, 1, 1, 1, 1, 0, 0, 0], // 50%
+    [1, 0, 0, 1, 1, 1, 1, 1], // 25% negated
+];
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PulseChannel {
+    duty: u8,
+    sequence_step: u8,
+    timer_period: u16,
+    timer: u16,
+    pub(crate) envelope: Envelope,
+    pub(crate) length_counter: LengthCounter,
+    sweep: Sweep,
+}
+
+impl PulseChannel {
+    pub fn new(is_first_channel: bool) -> Self {
+        Self {
+            duty: 0,
+            sequence_step: 0,
+            timer_period: 0,
+            timer: 0,
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default(),
+            sweep: Sweep::new(is_first_channel),
+        }
+    }
+
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        // register is the offset from the channel first register (0x4000 or 0x4004)
+        match register {
+            0 => {
+                // DDLC VVVV
+                self.duty = value >> 6;
+                self.length_counter.set_halt(value & 0x20 != 0);
+                self.envelope.write_control(value);
+            }
+            1 => self.sweep.write(value),
+            2 => self.timer_period = (self.timer_period & 0x0700) | value as u16,
+            3 => {
+                // LLLL Lttt
+                self.timer_period = (self.timer_period & 0x00FF) | ((value as u16 & 0b111) << 8);
+                self.length_counter.load(value >> 3);
+                self.sequence_step = 0;
+                self.envelope.restart();
+            }
+            _ => unreachable!("pulse channel has only 4 registers"),
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // clocked every apu cycle (every second cpu cycle)
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence_step = (self.sequence_step + 1) % 8;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_sweep(&mut self) {
+        self.sweep.clock(&mut self.timer_period);
+    }
+
+    pub fn output(&self) -> u8 {
+        if !self.length_counter.is_active()
+            || self.sweep.is_muting(self.timer_period)
+            || DUTY_TABLE[self.duty as usize][self.sequence_step as usize] == 0
+        {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
+
+impl SaveState for PulseChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.duty);
+        state.write_u8(self.sequence_step);
+        state.write_u16(self.timer_period);
+        state.write_u16(self.timer);
+        self.envelope.save_state(state);
+        self.length_counter.save_state(state);
+        self.sweep.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.duty = state.read_u8()?;
+        self.sequence_step = state.read_u8()?;
+        self.timer_period = state.read_u16()?;
+        self.timer = state.read_u16()?;
+        self.envelope.load_state(state)?;
+        self.length_counter.load_state(state)?;
+        self.sweep.load_state(state)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::apu::units::LengthCounter;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+#[rustfmt::skip]
+static TRIANGLE_SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct TriangleChannel {
+    sequence_step: u8,
+    timer_period: u16,
+    timer: u16,
+    control_flag: bool, // same bit as the length counter halt flag
+    linear_counter_reload_value: u8,
+    linear_counter: u8,
+    linear_counter_reload: bool,
+    pub(crate) length_counter: LengthCounter,
+}
+
+impl TriangleChannel {
+    pub fn write_register(&mut self, register: u16, value: u8) {
+        // register is the offset from 0x4008. 0x4009 is unused
+        match register {
+            0 => {
+                // CRRR RRRR
+                self.control_flag = value & 0x80 != 0;
+                self.length_counter.set_halt(self.control_flag);
+                self.linear_counter_reload_value = value & 0x7F;
+            }
+            1 => {}
+            2 => self.timer_period = (self.timer_period & 0x0700) | value as u16,
+            3 => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((value as u16 & 0b111) << 8);
+                self.length_counter.load(value >> 3);
+                self.linear_counter_reload = true;
+            }
+            _ => unreachable!("triangle channel has only 4 registers"),
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // unlike the other channels, the triangle timer is clocked every cpu cycle
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            if self.linear_counter > 0 && self.length_counter.is_active() {
+                self.sequence_step = (self.sequence_step + 1) % 32;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_linear_counter(&mut self) {
+        // clocked by the quarter frames of the frame counter
+        if self.linear_counter_reload {
+            self.linear_counter = self.linear_counter_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control_flag {
+            self.linear_counter_reload = false;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        // very small periods create ultrasonic frequencies, so we hold the output in the middle instead
+        if self.timer_period < 2 {
+            return 7;
+        }
+        TRIANGLE_SEQUENCE[self.sequence_step as usize]
+    }
+}
+
+impl SaveState for TriangleChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.sequence_step);
+        state.write_u16(self.timer_period);
+        state.write_u16(self.timer);
+        state.write_bool(self.control_flag);
+        state.write_u8(self.linear_counter_reload_value);
+        state.write_u8(self.linear_counter);
+        state.write_bool(self.linear_counter_reload);
+        self.length_counter.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.sequence_step = state.read_u8()?;
+        self.timer_period = state.read_u16()?;
+        self.timer = state.read_u16()?;
+        self.control_flag = state.read_bool()?;
+        self.linear_counter_reload_value = state.read_u8()?;
+        self.linear_counter = state.read_u8()?;
+        self.linear_counter_reload = state.read_bool()?;
+        self.length_counter.load_state(state)
+    }
+}
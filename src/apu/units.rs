@@ -0,0 +1,220 @@
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+// the building blocks that are shared between the apu channels
+// see https://www.nesdev.org/wiki/APU_Envelope, https://www.nesdev.org/wiki/APU_Length_Counter
+// and https://www.nesdev.org/wiki/APU_Sweep
+
+#[rustfmt::skip]
+static LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Envelope {
+    start: bool,
+    loop_flag: bool, // same bit as the length counter halt flag
+    constant_volume: bool,
+    volume: u8, // either the constant volume or the envelope period
+    divider: u8,
+    decay_level: u8,
+}
+
+impl Envelope {
+    pub fn write_control(&mut self, value: u8) {
+        // --LC VVVV
+        self.loop_flag = value & 0x20 != 0;
+        self.constant_volume = value & 0x10 != 0;
+        self.volume = value & 0x0F;
+    }
+
+    pub fn restart(&mut self) {
+        self.start = true;
+    }
+
+    pub fn clock(&mut self) {
+        // clocked by the quarter frames of the frame counter
+        if self.start {
+            self.start = false;
+            self.decay_level = 15;
+            self.divider = self.volume;
+            return;
+        }
+        if self.divider == 0 {
+            self.divider = self.volume;
+            if self.decay_level > 0 {
+                self.decay_level -= 1;
+            } else if self.loop_flag {
+                self.decay_level = 15;
+            }
+        } else {
+            self.divider -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.constant_volume {
+            self.volume
+        } else {
+            self.decay_level
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct LengthCounter {
+    enabled: bool,
+    halt: bool,
+    counter: u8,
+}
+
+impl LengthCounter {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        // disabling the channel (via 0x4015) immediately silences it
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    pub fn set_halt(&mut self, halt: bool) {
+        self.halt = halt;
+    }
+
+    pub fn load(&mut self, index: u8) {
+        // the length is loaded only if the channel is enabled
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(index & 0x1F) as usize];
+        }
+    }
+
+    pub fn clock(&mut self) {
+        // clocked by the half frames of the frame counter
+        if !self.halt && self.counter > 0 {
+            self.counter -= 1;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.counter > 0
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    reload: bool,
+    divider: u8,
+    ones_complement: bool, // pulse 1 negates with ones' complement, pulse 2 with two's complement
+}
+
+impl Sweep {
+    pub fn new(ones_complement: bool) -> Self {
+        Self {
+            ones_complement,
+            ..Default::default()
+        }
+    }
+
+    pub fn write(&mut self, value: u8) {
+        // EPPP NSSS
+        self.enabled = value & 0x80 != 0;
+        self.period = (value >> 4) & 0b111;
+        self.negate = value & 0x08 != 0;
+        self.shift = value & 0b111;
+        self.reload = true;
+    }
+
+    pub fn target_period(&self, timer_period: u16) -> u16 {
+        let change = timer_period >> self.shift;
+        if self.negate {
+            if self.ones_complement {
+                timer_period.saturating_sub(change + 1)
+            } else {
+                timer_period.saturating_sub(change)
+            }
+        } else {
+            timer_period + change
+        }
+    }
+
+    pub fn is_muting(&self, timer_period: u16) -> bool {
+        // the channel is muted when the period is too small, or when the sweep would overflow it
+        // (even if the sweep unit is disabled)
+        timer_period < 8 || self.target_period(timer_period) > 0x7FF
+    }
+
+    pub fn clock(&mut self, timer_period: &mut u16) {
+        // clocked by the half frames of the frame counter
+        if self.divider == 0 && self.enabled && self.shift != 0 && !self.is_muting(*timer_period) {
+            *timer_period = self.target_period(*timer_period);
+        }
+        if self.divider == 0 || self.reload {
+            self.divider = self.period;
+            self.reload = false;
+        } else {
+            self.divider -= 1;
+        }
+    }
+}
+
+impl SaveState for Envelope {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.start);
+        state.write_bool(self.loop_flag);
+        state.write_bool(self.constant_volume);
+        state.write_u8(self.volume);
+        state.write_u8(self.divider);
+        state.write_u8(self.decay_level);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.start = state.read_bool()?;
+        self.loop_flag = state.read_bool()?;
+        self.constant_volume = state.read_bool()?;
+        self.volume = state.read_u8()?;
+        self.divider = state.read_u8()?;
+        self.decay_level = state.read_u8()?;
+        Ok(())
+    }
+}
+
+impl SaveState for LengthCounter {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.enabled);
+        state.write_bool(self.halt);
+        state.write_u8(self.counter);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.enabled = state.read_bool()?;
+        self.halt = state.read_bool()?;
+        self.counter = state.read_u8()?;
+        Ok(())
+    }
+}
+
+impl SaveState for Sweep {
+    // ones_complement depends on the channel, so it is not saved
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.enabled);
+        state.write_u8(self.period);
+        state.write_bool(self.negate);
+        state.write_u8(self.shift);
+        state.write_bool(self.reload);
+        state.write_u8(self.divider);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.enabled = state.read_bool()?;
+        self.period = state.read_u8()?;
+        self.negate = state.read_bool()?;
+        self.shift = state.read_u8()?;
+        self.reload = state.read_bool()?;
+        self.divider = state.read_u8()?;
+        Ok(())
+    }
+}
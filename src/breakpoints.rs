@@ -0,0 +1,364 @@
+#[cfg(test)]
+mod breakpoints_tests;
+pub mod condition;
+
+use crate::bus::Bus;
+use crate::cpu::interrupt_lines::Interrupt;
+use crate::cpu::CPU;
+use crate::debug_hooks::{AccessKind, AddressSpace, DebugHooks, MemoryAccess};
+use crate::symbols::Labels;
+use anyhow::{bail, Context, Result};
+use condition::Condition;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BreakOn {
+    // the cpu is about to run the instruction at the address
+    Address(u16),
+    // the cpu reads or writes an address in the range (the reads include the instruction fetches)
+    Access {
+        space: AddressSpace,
+        read: bool,
+        write: bool,
+        first: u16,
+        last: u16,
+    },
+    // stops at the first instruction of the handler
+    Interrupt(Interrupt),
+    // before every instruction, when the condition holds
+    Always,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Breakpoint {
+    pub on: BreakOn,
+    pub condition: Option<Condition>,
+    pub enabled: bool,
+}
+
+impl Breakpoint {
+    pub fn new(on: BreakOn) -> Self {
+        Self {
+            on,
+            condition: None,
+            enabled: true,
+        }
+    }
+
+    fn watches(&self, access: &MemoryAccess) -> bool {
+        match self.on {
+            BreakOn::Access {
+                space,
+                read,
+                write,
+                first,
+                last,
+            } => {
+                let kind_matches = match access.kind {
+                    AccessKind::Read => read,
+                    AccessKind::Write => write,
+                };
+                space == access.space && kind_matches && (first..=last).contains(&access.address)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn parse_address(text: &str, symbols: Option<&dyn Labels>) -> Result<u16> {
+    // a label, or hex with or without $ or 0x in front
+    if let Some(address) = symbols.and_then(|symbols| symbols.address_of(text)) {
+        return Ok(address);
+    }
+    let digits = text
+        .strip_prefix('$')
+        .or_else(|| text.strip_prefix("0x"))
+        .unwrap_or(text);
+    u16::from_str_radix(digits, 16).with_context(|| format!("bad address {text:?}"))
+}
+
+impl Breakpoint {
+    pub fn parse(text: &str, symbols: Option<&dyn Labels>) -> Result<Self> {
+        // "C000", "read 0300-03FF", "write ppu 2000", "access 4016", "nmi", "irq" or "brk", any of them with
+        // "if <condition>" after it, or just "if <condition>" to check it before every instruction.
+        // with symbols the addresses can be labels too ("NMI_handler", "read player_x")
+        let text = text.trim();
+        let (target, condition) = match text.strip_prefix("if ") {
+            Some(condition) => ("", Some(condition)),
+            None => match text.split_once(" if ") {
+                Some((target, condition)) => (target, Some(condition)),
+                None => (text, None),
+            },
+        };
+        let condition = condition
+            .map(|condition| Condition::parse(condition, symbols))
+            .transpose()?;
+        let words: Vec<&str> = target.split_whitespace().collect();
+        let on = match words.as_slice() {
+            [] if condition.is_some() => BreakOn::Always,
+            ["nmi"] => BreakOn::Interrupt(Interrupt::Nmi),
+            ["irq"] => BreakOn::Interrupt(Interrupt::Irq),
+            ["brk"] => BreakOn::Interrupt(Interrupt::Brk),
+            [kind @ ("read" | "write" | "access"), rest @ ..] => {
+                let (space, range) = match rest {
+                    ["ppu", range] => (AddressSpace::Ppu, range),
+                    ["cpu", range] | [range] => (AddressSpace::Cpu, range),
+                    _ => bail!("bad watchpoint {text:?}, try like \"write ppu 2000-23FF\""),
+                };
+                let (first, last) = match range.split_once('-') {
+                    Some((first, last)) => (
+                        parse_address(first, symbols)?,
+                        parse_address(last, symbols)?,
+                    ),
+                    None => (
+                        parse_address(range, symbols)?,
+                        parse_address(range, symbols)?,
+                    ),
+                };
+                if last < first {
+                    bail!("the range {range:?} is empty");
+                }
+                BreakOn::Access {
+                    space,
+                    read: *kind != "write",
+                    write: *kind != "read",
+                    first,
+                    last,
+                }
+            }
+            [address] => BreakOn::Address(parse_address(address, symbols)?),
+            _ => bail!("bad breakpoint {text:?}"),
+        };
+        Ok(Self {
+            on,
+            condition,
+            enabled: true,
+        })
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Self::parse(text, None)
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.on {
+            BreakOn::Address(address) => write!(f, "{address:04X}")?,
+            BreakOn::Access {
+                space,
+                read,
+                write,
+                first,
+                last,
+            } => {
+                let kind = match (read, write) {
+                    (true, false) => "read",
+                    (false, true) => "write",
+                    _ => "access",
+                };
+                let space = if *space == AddressSpace::Ppu {
+                    " ppu"
+                } else {
+                    ""
+                };
+                write!(f, "{kind}{space} {first:04X}")?;
+                if last != first {
+                    write!(f, "-{last:04X}")?;
+                }
+            }
+            BreakOn::Interrupt(interrupt) => write!(f, "{interrupt}")?,
+            BreakOn::Always => {}
+        }
+        if let Some(condition) = &self.condition {
+            if self.on != BreakOn::Always {
+                write!(f, " ")?;
+            }
+            write!(f, "if {condition}")?;
+        }
+        if !self.enabled {
+            write!(f, " (disabled)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Cause {
+    Address(u16),
+    Access(MemoryAccess),
+    Interrupt(Interrupt),
+    Condition,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Hit {
+    pub id: usize,
+    pub cause: Cause,
+}
+
+impl fmt::Display for Hit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "breakpoint {}: ", self.id)?;
+        match self.cause {
+            Cause::Address(address) => write!(f, "at {address:04X}"),
+            Cause::Access(access) => {
+                let kind = match access.kind {
+                    AccessKind::Read => "read",
+                    AccessKind::Write => "write",
+                };
+                let space = if access.space == AddressSpace::Ppu {
+                    " ppu"
+                } else {
+                    ""
+                };
+                write!(
+                    f,
+                    "{kind}{space} {:04X} = {:02X}",
+                    access.address, access.value
+                )
+            }
+            Cause::Interrupt(interrupt) => write!(f, "{interrupt}"),
+            Cause::Condition => write!(f, "the condition holds"),
+        }
+    }
+}
+
+// the breakpoints and the watchpoints of a debugger. as the hooks of a running nes they find the first one
+// that hits, and the debugger takes it with take_hit. the watchpoints and the interrupts are checked with
+// their conditions after the instruction that set them off
+#[derive(Debug, Default)]
+pub struct Breakpoints {
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    next_id: usize,
+    pending: Vec<Hit>,
+    hit: Option<Hit>,
+}
+
+impl Breakpoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, breakpoint: Breakpoint) -> usize {
+        // returns the id of the breakpoint, ids are never reused
+        let id = self.next_id;
+        self.next_id += 1;
+        self.breakpoints.insert(id, breakpoint);
+        id
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Breakpoint> {
+        // it can be removed in the middle of an instruction, with its hits waiting for the end of it
+        self.pending.retain(|hit| hit.id != id);
+        if self.hit.is_some_and(|hit| hit.id == id) {
+            self.hit = None;
+        }
+        self.breakpoints.remove(&id)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Breakpoint> {
+        self.breakpoints.get_mut(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Breakpoint)> {
+        self.breakpoints
+            .iter()
+            .map(|(id, breakpoint)| (*id, breakpoint))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty()
+    }
+
+    pub fn has_address(&self, address: u16) -> bool {
+        self.breakpoints
+            .values()
+            .any(|breakpoint| breakpoint.enabled && breakpoint.on == BreakOn::Address(address))
+    }
+
+    pub fn toggle_address(&mut self, address: u16) {
+        // removes the breakpoints on the address, or adds one if there were none
+        let before = self.breakpoints.len();
+        self.breakpoints
+            .retain(|_, breakpoint| breakpoint.on != BreakOn::Address(address));
+        if self.breakpoints.len() == before {
+            self.add(Breakpoint::new(BreakOn::Address(address)));
+        }
+    }
+
+    pub fn take_hit(&mut self) -> Option<Hit> {
+        self.hit.take()
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = (usize, &Breakpoint)> {
+        self.iter().filter(|(_, breakpoint)| breakpoint.enabled)
+    }
+}
+
+impl DebugHooks for Breakpoints {
+    fn memory_access(&mut self, access: MemoryAccess) {
+        let hits: Vec<Hit> = self
+            .enabled()
+            .filter(|(_, breakpoint)| breakpoint.watches(&access))
+            .map(|(id, _)| Hit {
+                id,
+                cause: Cause::Access(access),
+            })
+            .collect();
+        self.pending.extend(hits);
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt) {
+        let hits: Vec<Hit> = self
+            .enabled()
+            .filter(|(_, breakpoint)| breakpoint.on == BreakOn::Interrupt(interrupt))
+            .map(|(id, _)| Hit {
+                id,
+                cause: Cause::Interrupt(interrupt),
+            })
+            .collect();
+        self.pending.extend(hits);
+    }
+
+    fn instruction_boundary(&mut self, cpu: &CPU, bus: &Bus) {
+        let pc = cpu.program_counter;
+        let pending = std::mem::take(&mut self.pending);
+        let at_instruction = self
+            .enabled()
+            .filter_map(|(id, breakpoint)| match breakpoint.on {
+                BreakOn::Address(address) if address == pc => Some(Hit {
+                    id,
+                    cause: Cause::Address(pc),
+                }),
+                BreakOn::Always => Some(Hit {
+                    id,
+                    cause: Cause::Condition,
+                }),
+                _ => None,
+            });
+        let hit = pending.into_iter().chain(at_instruction).find(|hit| {
+            let access = match hit.cause {
+                Cause::Access(access) => Some(access),
+                _ => None,
+            };
+            // a hit of a breakpoint that is gone doesn't count
+            self.breakpoints.get(&hit.id).is_some_and(|breakpoint| {
+                breakpoint
+                    .condition
+                    .as_ref()
+                    .is_none_or(|condition| condition.evaluate(cpu, bus, access))
+            })
+        });
+        // a hit that nobody took yet stays
+        if self.hit.is_none() {
+            self.hit = hit;
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::breakpoints::condition::Condition;
+use crate::breakpoints::{BreakOn, Breakpoint, Breakpoints, Cause, Hit};
+use crate::cpu::interrupt_lines::Interrupt;
+use crate::debug_hooks::{AccessKind, AddressSpace, DebugHooks, MemoryAccess};
+use crate::nes::Nes;
+
+const NESTEST: &[u8] = include_bytes!("../../tests/nestest.nes");
+
+fn run_until_hit(nes: &mut Nes, breakpoints: &mut Breakpoints) -> Hit {
+    for _ in 0..100_000 {
+        nes.step_cycle_with_hooks(breakpoints);
+        if let Some(hit) = breakpoints.take_hit() {
+            return hit;
+        }
+    }
+    panic!("no breakpoint hit");
+}
+
+fn rom_with_code(code: &[(u16, &[u8])]) -> Vec<u8> {
+    // a single 16KB prg bank (at 0x8000 and 0xC000) with the vectors at nmi 8100, reset 8000, irq/brk 8200
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut prg = vec![0; 0x4000];
+    for (address, bytes) in code {
+        let start = (*address - 0x8000) as usize;
+        prg[start..start + bytes.len()].copy_from_slice(bytes);
+    }
+    prg[0x3FFA..].copy_from_slice(&[0x00, 0x81, 0x00, 0x80, 0x00, 0x82]);
+    rom.extend(prg);
+    rom.extend(vec![0; 0x2000]);
+    rom
+}
+
+#[test]
+fn test_parse_and_show() {
+    for text in [
+        "C000",
+        "read 0300-03FF",
+        "write ppu 2000-23FF",
+        "access 4016",
+        "nmi",
+        "brk if A == $20",
+        "if [$0300] > 4 && X != 0",
+    ] {
+        let breakpoint: Breakpoint = text.parse().unwrap();
+        assert_eq!(breakpoint.to_string(), text);
+    }
+    assert_eq!(
+        "$c000".parse::<Breakpoint>().unwrap().on,
+        BreakOn::Address(0xC000)
+    );
+    assert_eq!(
+        "write cpu 10".parse::<Breakpoint>().unwrap().on,
+        BreakOn::Access {
+            space: AddressSpace::Cpu,
+            read: false,
+            write: true,
+            first: 0x10,
+            last: 0x10
+        }
+    );
+
+    for bad in [
+        "",
+        "read",
+        "write 0400-0300",
+        "C000 if A ==",
+        "if foo == 1",
+        "nmi please",
+    ] {
+        assert!(bad.parse::<Breakpoint>().is_err(), "{bad:?}");
+    }
+}
+
+#[test]
+fn test_conditions() {
+    let mut nes = Nes::from_rom(NESTEST);
+    nes.cpu_mut().register_a = 0x20;
+    nes.cpu_mut().register_x = 3;
+    nes.ram_mut()[0x0300] = 5;
+    let holds = |text: &str, access: Option<MemoryAccess>| {
+        let condition: Condition = text.parse().unwrap();
+        condition.evaluate(nes.cpu(), nes.bus(), access)
+    };
+    assert!(holds("A == $20 && [$0300] > 4", None));
+    assert!(!holds("A == $20 && [$0300] > 5", None));
+    assert!(holds("a == 32 || x == 0", None));
+    assert!(holds("[$02FD + x] == 5", None));
+    assert!(holds("(A & 0x30) == $20 && !(X < 3)", None));
+    assert!(holds("sp == $FD", None));
+
+    let access = MemoryAccess {
+        space: AddressSpace::Cpu,
+        kind: AccessKind::Write,
+        address: 0x0300,
+        value: 7,
+    };
+    assert!(holds("value == 7 && address == $300", Some(access)));
+    assert!(!holds("value == 7", None));
+
+    assert!("A = 1".parse::<Condition>().is_err());
+    assert!("[$0300".parse::<Condition>().is_err());
+}
+
+#[test]
+fn test_cpu_breakpoints() {
+    let mut nes = Nes::from_rom(NESTEST);
+    nes.cpu_mut().program_counter = 0xC000;
+    let mut breakpoints = Breakpoints::new();
+
+    // C5F9  86 10     STX $10 = 00, stops after it
+    let watch = breakpoints.add("write 0010".parse().unwrap());
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!(hit.id, watch);
+    assert_eq!(
+        hit.cause,
+        Cause::Access(MemoryAccess {
+            space: AddressSpace::Cpu,
+            kind: AccessKind::Write,
+            address: 0x0010,
+            value: 0
+        })
+    );
+    assert_eq!(nes.cpu().program_counter, 0xC5FB);
+    breakpoints.remove(watch);
+
+    // the first jsr goes to C72D
+    breakpoints.toggle_address(0xC72D);
+    assert!(breakpoints.has_address(0xC72D));
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!(hit.cause, Cause::Address(0xC72D));
+    assert_eq!(nes.cpu().program_counter, 0xC72D);
+    breakpoints.toggle_address(0xC72D);
+    assert!(breakpoints.is_empty());
+
+    let conditional = breakpoints.add("if pc == $C7DB && sp == $FB".parse().unwrap());
+    let disabled = breakpoints.add("C735".parse().unwrap());
+    breakpoints.get_mut(disabled).unwrap().enabled = false;
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!(
+        hit,
+        Hit {
+            id: conditional,
+            cause: Cause::Condition
+        }
+    );
+    assert_eq!(nes.cpu().program_counter, 0xC7DB);
+}
+
+#[test]
+fn test_remove_a_watchpoint_in_the_middle_of_an_instruction() {
+    let mut nes = Nes::from_rom(NESTEST);
+    nes.cpu_mut().program_counter = 0xC000;
+    let mut breakpoints = Breakpoints::new();
+
+    // C5F9  86 10     STX $10 = 00 writes on its first cycle, and the hit waits for the end of it
+    let watch = breakpoints.add("write 0010".parse().unwrap());
+    while breakpoints.pending.is_empty() {
+        nes.step_cycle_with_hooks(&mut breakpoints);
+    }
+    assert_ne!(nes.cpu().cpu_idle_cycles, 0);
+    // like pressing d in the debugger while it runs
+    breakpoints.remove(watch);
+    while nes.cpu().cpu_idle_cycles != 0 {
+        nes.step_cycle_with_hooks(&mut breakpoints);
+    }
+    assert_eq!(nes.cpu().program_counter, 0xC5FB);
+    assert_eq!(breakpoints.take_hit(), None);
+}
+
+#[test]
+fn test_ppu_watchpoints_and_interrupts() {
+    let rom = rom_with_code(&[
+        (
+            0x8000,
+            &[
+                0xA9, 0x20, // LDA #$20
+                0x8D, 0x06, 0x20, // STA $2006
+                0xA9, 0x00, // LDA #$00
+                0x8D, 0x06, 0x20, // STA $2006
+                0xA9, 0x05, // LDA #$05
+                0x8D, 0x07, 0x20, // STA $2007, to the ppu at 2000
+                0x00, 0xEA, // BRK
+                0xA9, 0x80, // LDA #$80
+                0x8D, 0x00, 0x20, // STA $2000, nmis on
+                0x4C, 0x16, 0x80, // JMP to itself
+            ],
+        ),
+        (0x8100, &[0x40]), // RTI
+        (0x8200, &[0x40]), // RTI
+    ]);
+    let mut nes = Nes::from_rom(&rom);
+    // the ppu would ignore the writes to $2000 and $2006 for the first frame
+    nes.bus_mut().ppu_warm_up_cycles = 0;
+    let mut breakpoints = Breakpoints::new();
+    let ppu = breakpoints.add("write ppu 2000-23FF".parse().unwrap());
+    let brk = breakpoints.add("brk".parse().unwrap());
+    let nmi = breakpoints.add("nmi".parse().unwrap());
+
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!(hit.id, ppu);
+    assert_eq!(
+        hit.cause,
+        Cause::Access(MemoryAccess {
+            space: AddressSpace::Ppu,
+            kind: AccessKind::Write,
+            address: 0x2000,
+            value: 5
+        })
+    );
+    assert_eq!(nes.cpu().program_counter, 0x800F);
+
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!((hit.id, hit.cause), (brk, Cause::Interrupt(Interrupt::Brk)));
+    assert_eq!(nes.cpu().program_counter, 0x8200);
+
+    let hit = run_until_hit(&mut nes, &mut breakpoints);
+    assert_eq!((hit.id, hit.cause), (nmi, Cause::Interrupt(Interrupt::Nmi)));
+    assert_eq!(nes.cpu().program_counter, 0x8100);
+    assert_eq!(hit.to_string(), format!("breakpoint {nmi}: nmi"));
+}
+
+#[test]
+fn test_hooks_see_nothing_when_not_asked() {
+    // the hooks don't change how the nes runs
+    let mut with_hooks = Nes::from_rom(NESTEST);
+    let mut without = Nes::from_rom(NESTEST);
+    let mut breakpoints = Breakpoints::new();
+    breakpoints.add("read 0000-FFFF if 0".parse().unwrap());
+    for _ in 0..30_000 {
+        with_hooks.step_cycle_with_hooks(&mut breakpoints);
+        without.step_cycle();
+    }
+    assert!(breakpoints.take_hit().is_none());
+    assert_eq!(with_hooks.save_state(), without.save_state());
+    breakpoints.instruction_boundary(with_hooks.cpu(), with_hooks.bus());
+}
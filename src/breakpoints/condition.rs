@@ -0,0 +1,263 @@
+use crate::bus::Bus;
+use crate::cpu::CPU;
+use crate::debug_hooks::MemoryAccess;
+use crate::symbols::Labels;
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::str::FromStr;
+
+// the binary operators, from the one that binds the weakest
+const PRECEDENCE: [&[&str]; 6] = [
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<=", ">=", "<", ">"],
+    &["|"],
+    &["&"],
+    &["+", "-"],
+];
+const OPERATORS: [&str; 17] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "|", "&", "+", "-", "!", "[", "]", "(", ")",
+];
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Operator(&'static str),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Operand {
+    A,
+    X,
+    Y,
+    P,
+    Sp,
+    Pc,
+    Value,   // the byte the watchpoint saw
+    Address, // the address the watchpoint saw
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Expression {
+    Number(i64),
+    Operand(Operand),
+    Memory(Box<Expression>), // [address], what the cpu would read there
+    Not(Box<Expression>),
+    Binary(&'static str, Box<Expression>, Box<Expression>),
+}
+
+// a condition like "A == $20 && [$0300] > 4". numbers are decimal, or hex after $ or 0x. the names are the
+// registers (a, x, y, p, sp, pc), for watchpoints the value and the address of the access, and the labels
+// of the symbols (their addresses, so [player_x] is the byte there)
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Condition {
+    expression: Expression,
+    text: String,
+}
+
+impl Condition {
+    pub fn parse(text: &str, symbols: Option<&dyn Labels>) -> Result<Self> {
+        let tokens = tokenize(text).with_context(|| format!("bad condition {text:?}"))?;
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            symbols,
+        };
+        let expression = parser
+            .parse()
+            .with_context(|| format!("bad condition {text:?}"))?;
+        Ok(Self {
+            expression,
+            text: text.trim().to_string(),
+        })
+    }
+
+    pub fn evaluate(&self, cpu: &CPU, bus: &Bus, access: Option<MemoryAccess>) -> bool {
+        evaluate(&self.expression, cpu, bus, access) != 0
+    }
+}
+
+impl FromStr for Condition {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Self::parse(text, None)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let word_end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        if let Some(hex) = rest.strip_prefix('$') {
+            let end = hex
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(hex.len());
+            tokens.push(Token::Number(parse_hex(&hex[..end])?));
+            rest = &hex[end..];
+        } else if word_end > 0 {
+            let word = &rest[..word_end];
+            let token = if let Some(hex) = word.strip_prefix("0x") {
+                Token::Number(parse_hex(hex)?)
+            } else if word.starts_with(|c: char| c.is_ascii_digit()) {
+                Token::Number(
+                    word.parse()
+                        .with_context(|| format!("bad number {word:?}"))?,
+                )
+            } else {
+                Token::Name(word.to_string())
+            };
+            tokens.push(token);
+            rest = &rest[word_end..];
+        } else if let Some(operator) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Operator(operator));
+            rest = &rest[operator.len()..];
+        } else {
+            bail!("unexpected {:?}", rest.chars().next().unwrap());
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+fn parse_hex(digits: &str) -> Result<i64> {
+    i64::from_str_radix(digits, 16).with_context(|| format!("bad hex number {digits:?}"))
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    next: usize,
+    symbols: Option<&'a dyn Labels>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> Result<Expression> {
+        let expression = self.binary(0)?;
+        if let Some(token) = self.tokens.get(self.next) {
+            bail!("unexpected {token:?}");
+        }
+        Ok(expression)
+    }
+
+    fn take_operator(&mut self, operators: &[&str]) -> Option<&'static str> {
+        match self.tokens.get(self.next) {
+            Some(Token::Operator(operator)) if operators.contains(operator) => {
+                self.next += 1;
+                Some(operator)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, operator: &str) -> Result<()> {
+        if self.take_operator(&[operator]).is_none() {
+            bail!("missing {operator}");
+        }
+        Ok(())
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expression> {
+        if level == PRECEDENCE.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(operator) = self.take_operator(PRECEDENCE[level]) {
+            let right = self.binary(level + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        if self.take_operator(&["!"]).is_some() {
+            return Ok(Expression::Not(Box::new(self.unary()?)));
+        }
+        if self.take_operator(&["("]).is_some() {
+            let inner = self.binary(0)?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        if self.take_operator(&["["]).is_some() {
+            let address = self.binary(0)?;
+            self.expect("]")?;
+            return Ok(Expression::Memory(Box::new(address)));
+        }
+        let Some(token) = self.tokens.get(self.next).cloned() else {
+            bail!("the condition ends too soon");
+        };
+        self.next += 1;
+        match token {
+            Token::Number(number) => Ok(Expression::Number(number)),
+            Token::Name(name) => match operand(&name) {
+                Some(operand) => Ok(Expression::Operand(operand)),
+                None => match self.symbols.and_then(|symbols| symbols.address_of(&name)) {
+                    Some(address) => Ok(Expression::Number(address as i64)),
+                    None => bail!("unknown name {name:?}"),
+                },
+            },
+            Token::Operator(operator) => bail!("unexpected {operator}"),
+        }
+    }
+}
+
+fn operand(name: &str) -> Option<Operand> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "a" => Operand::A,
+        "x" => Operand::X,
+        "y" => Operand::Y,
+        "p" => Operand::P,
+        "sp" => Operand::Sp,
+        "pc" => Operand::Pc,
+        "value" => Operand::Value,
+        "address" => Operand::Address,
+        _ => return None,
+    })
+}
+
+fn evaluate(expression: &Expression, cpu: &CPU, bus: &Bus, access: Option<MemoryAccess>) -> i64 {
+    let value = |expression| evaluate(expression, cpu, bus, access);
+    match expression {
+        Expression::Number(number) => *number,
+        Expression::Operand(operand) => match operand {
+            Operand::A => cpu.register_a as i64,
+            Operand::X => cpu.register_x as i64,
+            Operand::Y => cpu.register_y as i64,
+            Operand::P => cpu.status as i64,
+            Operand::Sp => cpu.stack_pointer as i64,
+            Operand::Pc => cpu.program_counter as i64,
+            // without an access (a breakpoint on the pc) there is nothing to compare to
+            Operand::Value => access.map_or(-1, |access| access.value as i64),
+            Operand::Address => access.map_or(-1, |access| access.address as i64),
+        },
+        Expression::Memory(address) => bus.peek_memory(value(address) as u16) as i64,
+        Expression::Not(inner) => (value(inner) == 0) as i64,
+        Expression::Binary(operator, left, right) => {
+            let (left, right) = (value(left), value(right));
+            match *operator {
+                "||" => (left != 0 || right != 0) as i64,
+                "&&" => (left != 0 && right != 0) as i64,
+                "==" => (left == right) as i64,
+                "!=" => (left != right) as i64,
+                "<=" => (left <= right) as i64,
+                ">=" => (left >= right) as i64,
+                "<" => (left < right) as i64,
+                ">" => (left > right) as i64,
+                "|" => left | right,
+                "&" => left & right,
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                _ => unreachable!("{operator} is not a binary operator"),
+            }
+        }
+    }
+}
@@ -1,8 +1,14 @@
-#[derive(Debug, PartialEq, Eq)]
+use crate::bus::mappers::Mapper;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::{bail, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Mirroring {
     Vertical,
     Horizontal,
     FourScreen,
+    SingleScreenLower,
+    SingleScreenUpper,
     Unloaded,
 }
 
@@ -10,8 +16,10 @@ pub enum Mirroring {
 pub struct Cartridge {
     pub prg_rom: Vec<u8>,
     pub chr_rom: Vec<u8>,
-    pub mapper: u8,
+    pub mapper: Mapper,
     pub screen_mirroring: Mirroring,
+    // the ines mapper number of a game that runs as nrom because we don't have its mapper
+    pub unsupported_mapper: Option<u8>,
 }
 
 static NES_TAG: [u8; 4] = [b'N', b'E', b'S', 0x1A];
@@ -21,8 +29,9 @@ impl Cartridge {
         Self {
             prg_rom: vec![],
             chr_rom: vec![],
-            mapper: 0,
+            mapper: Mapper::NROM,
             screen_mirroring: Mirroring::Unloaded,
+            unsupported_mapper: None,
         }
     }
     pub fn load_from_dump(&mut self, raw_dump: &Vec<u8>) {
@@ -58,24 +67,98 @@ impl Cartridge {
         let prg_rom_start = 16 + if trainer_512_byte_exists { 512 } else { 0 };
         let chr_rom_start = prg_rom_start + prg_rom_size_u8;
 
-        assert!(prg_rom_size_u8 == 0x4000 || prg_rom_size_u8 == 0x8000); // assert the prg rom size is plausible
+        let nrom_sized = prg_rom_size_u8 == 0x4000 || prg_rom_size_u8 == 0x8000;
+        if mapper == 0 {
+            assert!(nrom_sized); // assert the prg rom size is plausible
+        }
 
         self.prg_rom = raw_dump[prg_rom_start..(prg_rom_start + prg_rom_size_u8)].to_vec();
         self.chr_rom = raw_dump[chr_rom_start..(chr_rom_start + chr_vrom_size_u8)].to_vec();
-        self.mapper = mapper;
+        // games with a mapper we don't have, but that fit in nrom (like tetris on mmc1), mostly run without it
+        self.unsupported_mapper = None;
+        self.mapper = match Mapper::from_ines_number(mapper) {
+            Some(mapper) => mapper,
+            None if nrom_sized => {
+                self.unsupported_mapper = Some(mapper);
+                Mapper::NROM
+            }
+            None => panic!("mapper {mapper} is not supported"),
+        };
         self.screen_mirroring = screen_mirroring;
     }
     pub fn raw_load(&mut self, program: Vec<u8>) {
         self.prg_rom = program;
     }
 
-    pub fn read_prg_rom(&self, mut addr: u16) -> u8 {
+    pub fn read_prg_rom(&self, addr: u16) -> u8 {
         // get address mapped 0x0000 to 0x7FFF, and returns the corresponding ROM value
-        if self.prg_rom.len() == 0x4000 {
-            if addr >= 0x4000 {
-                addr -= 0x4000;
-            }
+        self.mapper.read_prg_rom(&self.prg_rom, addr)
+    }
+
+    pub fn prg_rom_offset(&self, addr: u16) -> Option<usize> {
+        // get address mapped 0x0000 to 0x7FFF, and returns where it is in the prg rom, if it is in there
+        let offset = self.mapper.map_prg_address(self.prg_rom.len(), addr);
+        (offset < self.prg_rom.len()).then_some(offset)
+    }
+
+    pub fn read_chr(&self, addr: u16) -> u8 {
+        // get address mapped 0x0000 to 0x1FFF (the pattern tables), and returns the corresponding CHR value
+        self.chr_rom[self.mapper.map_chr_address(addr) % self.chr_rom.len()]
+    }
+
+    pub fn chr_mut(&mut self, addr: u16) -> &mut u8 {
+        let len = self.chr_rom.len();
+        &mut self.chr_rom[self.mapper.map_chr_address(addr) % len]
+    }
+
+    pub fn write_mapper_register(&mut self, addr: u16, data: u8) -> bool {
+        // the cpu writes to the mapper registers, which are usually on top of the rom addresses
+        // (the registers of a mapper we don't have are ignored)
+        let handled = self.mapper.write_register(addr, data) || self.unsupported_mapper.is_some();
+        if let Some(mirroring) = self.mapper.mirroring() {
+            self.screen_mirroring = mirroring;
         }
-        self.prg_rom[addr as usize]
+        handled
+    }
+}
+
+impl SaveState for Mirroring {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(match self {
+            Mirroring::Vertical => 0,
+            Mirroring::Horizontal => 1,
+            Mirroring::FourScreen => 2,
+            Mirroring::SingleScreenLower => 3,
+            Mirroring::SingleScreenUpper => 4,
+            Mirroring::Unloaded => 5,
+        });
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        *self = match state.read_u8()? {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            2 => Mirroring::FourScreen,
+            3 => Mirroring::SingleScreenLower,
+            4 => Mirroring::SingleScreenUpper,
+            5 => Mirroring::Unloaded,
+            other => bail!("save state is corrupted (unknown mirroring {other})"),
+        };
+        Ok(())
+    }
+}
+
+impl SaveState for Cartridge {
+    // the prg rom is not saved (it can't change), but the chr may be ram that the game writes to
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_vec(&self.chr_rom);
+        self.mapper.save_state(state);
+        self.screen_mirroring.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        state.read_vec(&mut self.chr_rom)?;
+        self.mapper.load_state(state)?;
+        self.screen_mirroring.load_state(state)
     }
 }
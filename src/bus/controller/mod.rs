@@ -1,3 +1,30 @@
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+pub const BUTTON_A: u8 = 0x01;
+pub const BUTTON_B: u8 = 0x02;
+pub const BUTTON_SELECT: u8 = 0x04;
+pub const BUTTON_START: u8 = 0x08;
+pub const BUTTON_UP: u8 = 0x10;
+pub const BUTTON_DOWN: u8 = 0x20;
+pub const BUTTON_LEFT: u8 = 0x40;
+pub const BUTTON_RIGHT: u8 = 0x80;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn port(&self) -> usize {
+        // the index of the player's controller port, in the per frame controller states
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct ControllerByte {
     strobe: bool,
@@ -16,6 +43,12 @@ pub struct ControllerByte {
     // +--------- Right
 }
 
+impl Default for ControllerByte {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ControllerByte {
     pub fn new() -> ControllerByte {
         ControllerByte {
@@ -65,6 +98,23 @@ impl ControllerByte {
         }
     }
 
+    pub fn pressed_buttons(&self) -> u8 {
+        // the currently pressed buttons, in the same order they are read (see BUTTON_*)
+        self.value
+    }
+
+    pub fn set_pressed_buttons(&mut self, buttons: u8) {
+        self.value = buttons;
+    }
+
+    pub fn set_button(&mut self, button: u8) {
+        self.value |= button;
+    }
+
+    pub fn unset_button(&mut self, button: u8) {
+        self.value &= !button;
+    }
+
     pub fn set_right(&mut self) {
         self.value |= 0x80;
     }
@@ -129,3 +179,17 @@ impl ControllerByte {
         self.value &= !0x01;
     }
 }
+
+impl SaveState for ControllerByte {
+    // the pressed buttons are not saved, they come from whoever is holding the controller right now
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.strobe);
+        state.write_u8(self.read_numer);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.strobe = state.read_bool()?;
+        self.read_numer = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::bus::cartridge::{Cartridge, Mirroring};
+use crate::bus::mappers::{Mapper, NSFBanks, NSF_CHIP_VRC6, VRC6};
+
+fn banked_prg_rom(number_of_8kb_banks: usize) -> Vec<u8> {
+    // every 8KB bank is filled with its own number
+    (0..number_of_8kb_banks)
+        .flat_map(|bank| vec![bank as u8; 0x2000])
+        .collect()
+}
+
+#[test]
+fn test_vrc6_prg_banking() {
+    let prg_rom = banked_prg_rom(16);
+    let mut mapper = Mapper::from_ines_number(24).unwrap();
+
+    // the last 8KB are fixed
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x6000), 15);
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x7FFF), 15);
+
+    assert!(mapper.write_register(0x8000, 3)); // 16KB bank 3 = 8KB banks 6 and 7
+    assert!(mapper.write_register(0xC000, 9));
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x0000), 6);
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x3FFF), 7);
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x4000), 9);
+    assert_eq!(mapper.read_prg_rom(&prg_rom, 0x6000), 15);
+
+    // the offsets in the rom, for the debugger's symbols
+    assert_eq!(
+        mapper.map_prg_address(prg_rom.len(), 0x0010),
+        6 * 0x2000 + 0x10
+    );
+    assert_eq!(
+        mapper.map_prg_address(prg_rom.len(), 0x4010),
+        9 * 0x2000 + 0x10
+    );
+    assert_eq!(
+        mapper.map_prg_address(prg_rom.len(), 0x7FFF),
+        prg_rom.len() - 1
+    );
+    assert_eq!(Mapper::NROM.map_prg_address(0x4000, 0x4010), 0x0010);
+    assert_eq!(Mapper::NROM.map_prg_address(0x8000, 0x4010), 0x4010);
+}
+
+#[test]
+fn test_vrc6_chr_banking_and_mirroring() {
+    let mut mapper = Mapper::from_ines_number(24).unwrap();
+    assert_eq!(mapper.map_chr_address(0x0401), 0x0401);
+
+    mapper.write_register(0xD001, 0x20);
+    mapper.write_register(0xE003, 0x05);
+    assert_eq!(mapper.map_chr_address(0x0401), 0x20 * 0x400 + 1);
+    assert_eq!(mapper.map_chr_address(0x1FFF), 0x05 * 0x400 + 0x3FF);
+
+    assert_eq!(mapper.mirroring(), Some(Mirroring::Vertical));
+    mapper.write_register(0xB003, 0x04);
+    assert_eq!(mapper.mirroring(), Some(Mirroring::Horizontal));
+    mapper.write_register(0xB003, 0x0C);
+    assert_eq!(mapper.mirroring(), Some(Mirroring::SingleScreenUpper));
+}
+
+#[test]
+fn test_vrc6_mapper_26_swaps_address_lines() {
+    let mut mapper_24 = Mapper::from_ines_number(24).unwrap();
+    let mut mapper_26 = Mapper::from_ines_number(26).unwrap();
+
+    // 0xD001 on mapper 24 is 0xD002 on mapper 26
+    mapper_24.write_register(0xD001, 0x11);
+    mapper_26.write_register(0xD002, 0x11);
+    assert_eq!(mapper_24.map_chr_address(0x0400), 0x11 * 0x400);
+    assert_eq!(mapper_26.map_chr_address(0x0400), 0x11 * 0x400);
+    assert_eq!(mapper_26.map_chr_address(0x0800), 0x0800);
+}
+
+#[test]
+fn test_vrc6_irq_in_cycle_mode() {
+    let mut vrc6 = VRC6::new(false);
+    vrc6.write_register(0xF000, 0xFC);
+    vrc6.write_register(0xF001, 0b111); // cycle mode, enabled, enable after ack
+
+    // counts 0xFC -> 0xFF, and fires on the next clock
+    for _ in 0..3 {
+        vrc6.irq.clock();
+    }
+    assert!(!vrc6.irq.is_pending());
+    vrc6.irq.clock();
+    assert!(vrc6.irq.is_pending());
+
+    // acknowledging keeps it enabled (because of the A bit), and the counter was reloaded
+    vrc6.write_register(0xF002, 0);
+    assert!(!vrc6.irq.is_pending());
+    for _ in 0..4 {
+        vrc6.irq.clock();
+    }
+    assert!(vrc6.irq.is_pending());
+}
+
+#[test]
+fn test_vrc6_irq_in_scanline_mode() {
+    let mut vrc6 = VRC6::new(false);
+    vrc6.write_register(0xF000, 0xFF);
+    vrc6.write_register(0xF001, 0b010); // scanline mode, enabled
+
+    // a scanline is 341 / 3 = ~113.67 cpu cycles
+    for _ in 0..113 {
+        vrc6.irq.clock();
+    }
+    assert!(!vrc6.irq.is_pending());
+    vrc6.irq.clock();
+    assert!(vrc6.irq.is_pending());
+
+    // without the A bit, acknowledging disables the counter
+    vrc6.write_register(0xF002, 0);
+    for _ in 0..1000 {
+        vrc6.irq.clock();
+    }
+    assert!(!vrc6.irq.is_pending());
+}
+
+#[test]
+fn test_vrc6_audio() {
+    let mut mapper = Mapper::from_ines_number(24).unwrap();
+    assert_eq!(mapper.expansion_audio().unwrap().output(&[1.0; 3]), 0.0);
+
+    // pulse 1 with the duty ignored, so it always outputs its volume
+    mapper.write_register(0x9000, 0x8F);
+    mapper.write_register(0x9002, 0x80);
+    let chip = mapper.expansion_audio().unwrap();
+    chip.clock();
+    let full_pulse = chip.output(&[1.0; 3]);
+    assert!(full_pulse > 0.0);
+
+    // sawtooth with rate 0x20, period 0 - the accumulator rises every second clock
+    mapper.write_register(0xB000, 0x20);
+    mapper.write_register(0xB002, 0x80);
+    let chip = mapper.expansion_audio().unwrap();
+    let mut max_output: f32 = 0.0;
+    for _ in 0..14 {
+        chip.clock();
+        max_output = max_output.max(chip.output(&[1.0; 3]));
+    }
+    // 6 additions of 0x20 = 0xC0 -> 0x18 after dropping the low 3 bits
+    assert!((max_output - full_pulse * (15.0 + 0x18 as f32) / 15.0).abs() < 1e-6);
+
+    // halting stops all the channels
+    mapper.write_register(0x9003, 0x01);
+    let chip = mapper.expansion_audio().unwrap();
+    let halted_output = chip.output(&[1.0; 3]);
+    for _ in 0..100 {
+        chip.clock();
+        assert_eq!(chip.output(&[1.0; 3]), halted_output);
+    }
+}
+
+#[test]
+fn test_nsf_expansion_audio() {
+    let mut without_chips = Mapper::NSF(NSFBanks::new([0; 8], 0));
+    assert!(without_chips.expansion_audio().is_none());
+
+    let mut with_vrc6 = Mapper::NSF(NSFBanks::new([0; 8], NSF_CHIP_VRC6));
+    with_vrc6.write_register(0xA000, 0x8F);
+    with_vrc6.write_register(0xA002, 0x80);
+    let chip = with_vrc6.expansion_audio().unwrap();
+    chip.clock();
+    assert!(chip.output(&[1.0; 3]) > 0.0);
+}
+
+#[test]
+fn test_unsupported_mapper_falls_back_to_nrom() {
+    // mapper 1 (mmc1) with 32KB of prg rom, like tetris
+    let mut rom = vec![
+        b'N', b'E', b'S', 0x1A, 2, 1, 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    rom.extend(banked_prg_rom(4));
+    rom.extend(vec![0; 0x2000]);
+    let mut cartridge = Cartridge::new();
+    cartridge.load_from_dump(&rom);
+    assert_eq!(cartridge.mapper, Mapper::NROM);
+    assert_eq!(cartridge.unsupported_mapper, Some(1));
+    assert_eq!(cartridge.read_prg_rom(0x7FFF), 3);
+    // the mmc1 register writes go nowhere
+    assert!(cartridge.write_mapper_register(0xFF00, 0x80));
+    assert_eq!(cartridge.read_prg_rom(0x7F00), 3);
+    assert!(Mapper::from_ines_number(1).is_none());
+}
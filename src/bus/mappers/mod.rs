@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod mappers_tests;
+mod nsf;
+mod vrc6;
+
+use crate::apu::expansion::ExpansionAudio;
+use crate::bus::cartridge::Mirroring;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::{bail, Result};
+pub use nsf::{
+    NSFBanks, NSF_BANK_REGISTERS_START, NSF_BANK_SIZE, NSF_CHIP_VRC6, NSF_SUPPORTED_CHIPS,
+};
+pub use vrc6::{VRC6, VRCIRQ};
+
+// the mapper is the hardware on the cartridge that decides which part of the rom the cpu (and ppu) see.
+// the cartridge holds the actual rom, and the mapper holds the state needed to translate the addresses
+#[derive(Debug, Eq, PartialEq)]
+pub enum Mapper {
+    NROM,
+    VRC6(VRC6),
+    NSF(NSFBanks), // a virtual cartridge used to play nsf music files
+}
+
+impl Mapper {
+    pub fn from_ines_number(mapper_number: u8) -> Option<Self> {
+        // returns None for the mappers we don't emulate
+        match mapper_number {
+            0 => Some(Mapper::NROM),
+            24 => Some(Mapper::VRC6(VRC6::new(false))),
+            26 => Some(Mapper::VRC6(VRC6::new(true))),
+            _ => None,
+        }
+    }
+
+    pub fn read_prg_rom(&self, prg_rom: &[u8], addr: u16) -> u8 {
+        // get address mapped 0x0000 to 0x7FFF, and returns the corresponding ROM value
+        match self {
+            Mapper::NROM => prg_rom[self.map_prg_address(prg_rom.len(), addr)],
+            Mapper::VRC6(vrc6) => vrc6.read_prg_rom(prg_rom, addr),
+            Mapper::NSF(banks) => banks.read_prg_rom(prg_rom, addr),
+        }
+    }
+
+    pub fn map_prg_address(&self, prg_rom_size: usize, addr: u16) -> usize {
+        // gets an address mapped 0x0000 to 0x7FFF, and returns the offset in the prg rom that the cpu sees there
+        // (with the banks that are switched in now). for the nsf banks it can be past the end of the rom
+        match self {
+            Mapper::NROM => {
+                let mut addr = addr as usize;
+                if prg_rom_size == 0x4000 && addr >= 0x4000 {
+                    addr -= 0x4000;
+                }
+                addr
+            }
+            Mapper::VRC6(vrc6) => vrc6.map_prg_address(prg_rom_size, addr),
+            Mapper::NSF(banks) => banks.map_prg_address(addr),
+        }
+    }
+
+    pub fn map_chr_address(&self, addr: u16) -> usize {
+        // gets a ppu address in the pattern tables (0x0000 to 0x1FFF), and returns the offset in the chr rom
+        match self {
+            Mapper::VRC6(vrc6) => vrc6.map_chr_address(addr),
+            Mapper::NROM | Mapper::NSF(_) => addr as usize,
+        }
+    }
+
+    pub fn write_register(&mut self, addr: u16, data: u8) -> bool {
+        // gets the cpu address, and returns true if the mapper handled the write
+        match self {
+            Mapper::NROM => false,
+            Mapper::VRC6(vrc6) => vrc6.write_register(addr, data),
+            Mapper::NSF(banks) => banks.write_register(addr, data),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        // boards that see the reset line go back to their power on banks. the nsf banks are set by the player
+        if let Mapper::VRC6(vrc6) = self {
+            vrc6.reset();
+        }
+    }
+
+    pub fn mirroring(&self) -> Option<Mirroring> {
+        // the mirroring set by the mapper, if it controls it (instead of the ines header)
+        match self {
+            Mapper::VRC6(vrc6) => Some(vrc6.mirroring()),
+            Mapper::NROM | Mapper::NSF(_) => None,
+        }
+    }
+
+    pub fn clock_cpu_cycle(&mut self) {
+        // mappers with cpu cycle based irq counters count here
+        if let Mapper::VRC6(vrc6) = self {
+            vrc6.irq.clock();
+        }
+    }
+
+    pub fn irq_pending(&self) -> bool {
+        match self {
+            Mapper::VRC6(vrc6) => vrc6.irq.is_pending(),
+            Mapper::NROM | Mapper::NSF(_) => false,
+        }
+    }
+
+    pub fn expansion_audio(&mut self) -> Option<&mut dyn ExpansionAudio> {
+        // the sound chip on the cartridge, which is mixed with the apu output
+        match self {
+            Mapper::VRC6(vrc6) => Some(&mut vrc6.audio),
+            Mapper::NSF(banks) => banks.expansion_audio(),
+            Mapper::NROM => None,
+        }
+    }
+}
+
+impl Mapper {
+    fn state_id(&self) -> u8 {
+        match self {
+            Mapper::NROM => 0,
+            Mapper::VRC6(_) => 1,
+            Mapper::NSF(_) => 2,
+        }
+    }
+}
+
+impl SaveState for Mapper {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.state_id());
+        match self {
+            Mapper::NROM => {}
+            Mapper::VRC6(vrc6) => vrc6.save_state(state),
+            Mapper::NSF(banks) => banks.save_state(state),
+        }
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        // the mapper comes from the loaded rom, the state can only fill it
+        let id = state.read_u8()?;
+        if id != self.state_id() {
+            bail!("save state was made with a different mapper");
+        }
+        match self {
+            Mapper::NROM => Ok(()),
+            Mapper::VRC6(vrc6) => vrc6.load_state(state),
+            Mapper::NSF(banks) => banks.load_state(state),
+        }
+    }
+}
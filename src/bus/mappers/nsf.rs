@@ -0,0 +1,81 @@
+use crate::apu::expansion::{ExpansionAudio, VRC6Audio};
+use crate::bus::memory_mapping_constants::PRG_ROM_START;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+pub const NSF_BANK_REGISTERS_START: u16 = 0x5FF8;
+pub const NSF_BANK_REGISTERS_END: u16 = 0x5FFF;
+pub const NSF_BANK_SIZE: usize = 0x1000;
+pub const NSF_CHIP_VRC6: u8 = 0x01;
+pub const NSF_SUPPORTED_CHIPS: u8 = NSF_CHIP_VRC6;
+
+// nsf files split the 0x8000-0xFFFF range into 8 banks of 4KB, each can be switched by writing to 0x5FF8-0x5FFF
+// see https://www.nesdev.org/wiki/NSF#Bankswitching
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NSFBanks {
+    banks: [u8; 8],
+    vrc6_audio: Option<VRC6Audio>,
+}
+
+impl NSFBanks {
+    pub fn new(banks: [u8; 8], extra_sound_chips: u8) -> Self {
+        // extra_sound_chips is the header bitfield, the unsupported chips are ignored
+        Self {
+            banks,
+            vrc6_audio: (extra_sound_chips & NSF_CHIP_VRC6 != 0).then(VRC6Audio::new),
+        }
+    }
+
+    pub fn expansion_audio(&mut self) -> Option<&mut dyn ExpansionAudio> {
+        self.vrc6_audio
+            .as_mut()
+            .map(|chip| chip as &mut dyn ExpansionAudio)
+    }
+
+    pub fn read_prg_rom(&self, prg_rom: &[u8], addr: u16) -> u8 {
+        // the last bank may be only partially filled, the rest reads as 0
+        prg_rom
+            .get(self.map_prg_address(addr))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn map_prg_address(&self, addr: u16) -> usize {
+        let bank = self.banks[addr as usize / NSF_BANK_SIZE] as usize;
+        bank * NSF_BANK_SIZE + addr as usize % NSF_BANK_SIZE
+    }
+
+    pub fn write_register(&mut self, addr: u16, data: u8) -> bool {
+        match addr {
+            NSF_BANK_REGISTERS_START..=NSF_BANK_REGISTERS_END => {
+                self.banks[(addr - NSF_BANK_REGISTERS_START) as usize] = data;
+                true
+            }
+            // the sound chips registers are on top of the rom, other writes to it are ignored
+            PRG_ROM_START.. => {
+                if let Some(vrc6_audio) = &mut self.vrc6_audio {
+                    vrc6_audio.write_register(addr, data);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl SaveState for NSFBanks {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&self.banks);
+        if let Some(chip) = &self.vrc6_audio {
+            chip.save_state(state);
+        }
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        state.read_bytes(&mut self.banks)?;
+        if let Some(chip) = &mut self.vrc6_audio {
+            chip.load_state(state)?;
+        }
+        Ok(())
+    }
+}
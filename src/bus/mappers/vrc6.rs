@@ -0,0 +1,212 @@
+use crate::apu::expansion::VRC6Audio;
+use crate::bus::cartridge::Mirroring;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+
+const PRG_16KB_BANK_SIZE: usize = 0x4000;
+const PRG_8KB_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x400;
+const IRQ_PRESCALER_PERIOD: i16 = 341;
+
+// konami's vrc irq counter, counts up either every cpu cycle or every scanline (emulated with a cpu cycle prescaler)
+// see https://www.nesdev.org/wiki/VRC_IRQ
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct VRCIRQ {
+    latch: u8,
+    counter: u8,
+    prescaler: i16,
+    enabled: bool,
+    enable_after_acknowledge: bool,
+    cycle_mode: bool,
+    pending: bool,
+}
+
+impl VRCIRQ {
+    pub fn write_latch(&mut self, value: u8) {
+        self.latch = value;
+    }
+
+    pub fn write_control(&mut self, value: u8) {
+        // ---- -MEA
+        self.enable_after_acknowledge = value & 0x01 != 0;
+        self.enabled = value & 0x02 != 0;
+        self.cycle_mode = value & 0x04 != 0;
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = IRQ_PRESCALER_PERIOD;
+        }
+        self.pending = false;
+    }
+
+    pub fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_acknowledge;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    fn clock_counter(&mut self) {
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+
+    pub fn clock(&mut self) {
+        // called once every cpu cycle
+        if !self.enabled {
+            return;
+        }
+        if self.cycle_mode {
+            self.clock_counter();
+            return;
+        }
+        // a scanline is 113.667 cpu cycles, so the prescaler counts 3 ppu dots every cpu cycle
+        self.prescaler -= 3;
+        if self.prescaler <= 0 {
+            self.prescaler += IRQ_PRESCALER_PERIOD;
+            self.clock_counter();
+        }
+    }
+}
+
+// konami vrc6, used by mappers 24 and 26 (which only swap the two lowest address lines)
+// see https://www.nesdev.org/wiki/VRC6
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct VRC6 {
+    swapped_address_lines: bool,
+    prg_16kb_bank: u8,
+    prg_8kb_bank: u8,
+    chr_banks: [u8; 8],
+    mirroring: Mirroring,
+    pub irq: VRCIRQ,
+    pub audio: VRC6Audio,
+}
+
+impl VRC6 {
+    pub fn new(swapped_address_lines: bool) -> Self {
+        Self {
+            swapped_address_lines,
+            prg_16kb_bank: 0,
+            prg_8kb_bank: 0,
+            chr_banks: [0, 1, 2, 3, 4, 5, 6, 7],
+            mirroring: Mirroring::Vertical,
+            irq: VRCIRQ::default(),
+            audio: VRC6Audio::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.swapped_address_lines);
+    }
+
+    pub fn read_prg_rom(&self, prg_rom: &[u8], addr: u16) -> u8 {
+        prg_rom[self.map_prg_address(prg_rom.len(), addr)]
+    }
+
+    pub fn map_prg_address(&self, prg_rom_size: usize, addr: u16) -> usize {
+        // 0x8000-0xBFFF is a switchable 16KB bank, 0xC000-0xDFFF a switchable 8KB bank,
+        // and 0xE000-0xFFFF is fixed to the last 8KB of the rom
+        let addr = addr as usize;
+        let offset = match addr {
+            0x0000..=0x3FFF => self.prg_16kb_bank as usize * PRG_16KB_BANK_SIZE + addr,
+            0x4000..=0x5FFF => {
+                self.prg_8kb_bank as usize * PRG_8KB_BANK_SIZE + addr % PRG_8KB_BANK_SIZE
+            }
+            _ => prg_rom_size - PRG_8KB_BANK_SIZE + addr % PRG_8KB_BANK_SIZE,
+        };
+        offset % prg_rom_size
+    }
+
+    pub fn map_chr_address(&self, addr: u16) -> usize {
+        let bank = self.chr_banks[addr as usize / CHR_BANK_SIZE] as usize;
+        bank * CHR_BANK_SIZE + addr as usize % CHR_BANK_SIZE
+    }
+
+    pub fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    pub fn write_register(&mut self, addr: u16, data: u8) -> bool {
+        if addr < 0x8000 {
+            return false;
+        }
+        // only the top 4 and the bottom 2 address lines are connected
+        let mut addr = addr & 0xF003;
+        if self.swapped_address_lines {
+            addr = (addr & 0xFFFC) | ((addr & 0x01) << 1) | ((addr & 0x02) >> 1);
+        }
+        if self.audio.write_register(addr, data) {
+            return true;
+        }
+        match addr {
+            0x8000..=0x8003 => self.prg_16kb_bank = data & 0x0F,
+            0xB003 => {
+                // only the banking mode used by the released games is supported,
+                // so just the mirroring bits (----MM--) matter
+                self.mirroring = match (data >> 2) & 0b11 {
+                    0 => Mirroring::Vertical,
+                    1 => Mirroring::Horizontal,
+                    2 => Mirroring::SingleScreenLower,
+                    _ => Mirroring::SingleScreenUpper,
+                };
+            }
+            0xC000..=0xC003 => self.prg_8kb_bank = data & 0x1F,
+            0xD000..=0xD003 => self.chr_banks[(addr - 0xD000) as usize] = data,
+            0xE000..=0xE003 => self.chr_banks[(addr - 0xE000) as usize + 4] = data,
+            0xF000 => self.irq.write_latch(data),
+            0xF001 => self.irq.write_control(data),
+            0xF002 => self.irq.acknowledge(),
+            _ => {}
+        }
+        true
+    }
+}
+
+impl SaveState for VRCIRQ {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.latch);
+        state.write_u8(self.counter);
+        state.write_i16(self.prescaler);
+        state.write_bool(self.enabled);
+        state.write_bool(self.enable_after_acknowledge);
+        state.write_bool(self.cycle_mode);
+        state.write_bool(self.pending);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.latch = state.read_u8()?;
+        self.counter = state.read_u8()?;
+        self.prescaler = state.read_i16()?;
+        self.enabled = state.read_bool()?;
+        self.enable_after_acknowledge = state.read_bool()?;
+        self.cycle_mode = state.read_bool()?;
+        self.pending = state.read_bool()?;
+        Ok(())
+    }
+}
+
+impl SaveState for VRC6 {
+    // swapped_address_lines comes from the mapper number, so it is not saved
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.prg_16kb_bank);
+        state.write_u8(self.prg_8kb_bank);
+        state.write_bytes(&self.chr_banks);
+        self.mirroring.save_state(state);
+        self.irq.save_state(state);
+        self.audio.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.prg_16kb_bank = state.read_u8()?;
+        self.prg_8kb_bank = state.read_u8()?;
+        state.read_bytes(&mut self.chr_banks)?;
+        self.mirroring.load_state(state)?;
+        self.irq.load_state(state)?;
+        self.audio.load_state(state)
+    }
+}
@@ -89,3 +89,18 @@ fn test_read_memory_2_bytes() {
         assert_eq!(bus.read_memory_2_bytes(word_addr), expected_result);
     }
 }
+
+#[test]
+fn test_peek_memory() {
+    let mut bus = Bus::default();
+    bus.write_memory(0x0012, 0x34);
+    assert_eq!(bus.peek_memory(0x0812), 0x34);
+
+    // peeking at the status doesn't clear the vblank flag, reading it does
+    bus.ppu_registers.status_register.set_vblank_status(true);
+    assert_eq!(bus.peek_memory(0x2002) & 0x80, 0x80);
+    assert_eq!(bus.peek_memory(0x200A) & 0x80, 0x80);
+    assert!(bus.ppu_registers.status_register.get_vblank_status());
+    bus.read_memory(0x2002);
+    assert!(!bus.ppu_registers.status_register.get_vblank_status());
+}
@@ -33,26 +33,33 @@ impl Mem for Bus {
                 // cartridge.chr_rom[7 * 16..8 * 16].copy_from_slice(&our_tile);
                 self.oam_dma_page = data;
                 self.number_of_copies_in_current_oam_dma = 0;
-                self.cpu_idle_cycles += 1;
+                self.cpu_stall_cycles += 1;
                 self.copy_from_ram_to_oam();
             }
             P1_CONTROLLER => {
+                // the strobe is shared by both controllers
                 self.p1_controller.write(data);
-            }
-            P2_CONTROLLER => {
                 self.p2_controller.write(data);
             }
+            APU_REGISTERS_START..=APU_REGISTERS_END | APU_STATUS | APU_FRAME_COUNTER => {
+                self.apu.write_register(addr, data);
+                self.io_and_audio_registers[(addr - IO_AND_AUDIO_REGISTERS_START) as usize] = data;
+            }
             IO_AND_AUDIO_REGISTERS_START..=IO_AND_AUDIO_REGISTERS_END => {
                 self.io_and_audio_registers[(addr - IO_AND_AUDIO_REGISTERS_START) as usize] = data;
             }
             UNMAPPED_SEG_START..=UNMAPPED_SEG_END => {
-                self.unmapped_seg[(addr - UNMAPPED_SEG_START) as usize] = data;
+                if !self.cartridge.write_mapper_register(addr, data) {
+                    self.unmapped_seg[(addr - UNMAPPED_SEG_START) as usize] = data;
+                }
             }
             PRG_RAM_START..=PRG_RAM_END => {
                 self.prg_ram[(addr - PRG_RAM_START) as usize] = data;
             }
             PRG_ROM_START..=PRG_ROM_END => {
-                panic!("trying to write to the ROM in address {addr}");
+                if !self.cartridge.write_mapper_register(addr, data) {
+                    panic!("trying to write to the ROM in address {addr}");
+                }
             }
         }
     }
@@ -80,6 +87,7 @@ impl Mem for Bus {
                 let canonical_address = PPU_REGISTERS_START + canonical_offset_from_start;
                 self.read_ppu_memory(canonical_address)
             }
+            APU_STATUS => self.apu.read_status(),
             P1_CONTROLLER => self.p1_controller.read(),
             P2_CONTROLLER => self.p2_controller.read(),
             IO_AND_AUDIO_REGISTERS_START..=IO_AND_AUDIO_REGISTERS_END => {
@@ -93,3 +101,44 @@ impl Mem for Bus {
         }
     }
 }
+
+impl Bus {
+    pub fn peek_memory(&self, addr: u16) -> u8 {
+        // what the cpu would read, without what reading does on the side (clearing the vblank flag, moving the ppu
+        // address, acknowledging irqs, shifting the controllers). for the debugger and the tracer.
+        // the registers that can't be read without changing something show 0
+        match addr {
+            CPU_RAM_MEM_START..=CPU_RAM_MEM_END => {
+                self.cpu_ram[((addr - CPU_RAM_MEM_START) % CPU_RAM_MEM_UNIQUE_SIZE) as usize]
+            }
+            PPU_REGISTERS_START..=PPU_REGISTERS_END => {
+                match PPU_REGISTERS_START + (addr - PPU_REGISTERS_START) % PPU_REGISTERS_UNIQUE_SIZE
+                {
+                    0x2000 => self.ppu_registers.control_register.read(),
+                    0x2001 => self.ppu_registers.mask_register.read(),
+                    0x2002 => self.ppu_registers.status_register.read(),
+                    0x2006 => self.ppu_registers.address_register.read(),
+                    _ => 0,
+                }
+            }
+            APU_STATUS | P1_CONTROLLER | P2_CONTROLLER => 0,
+            IO_AND_AUDIO_REGISTERS_START..=IO_AND_AUDIO_REGISTERS_END => {
+                self.io_and_audio_registers[(addr - IO_AND_AUDIO_REGISTERS_START) as usize]
+            }
+            UNMAPPED_SEG_START..=UNMAPPED_SEG_END => {
+                self.unmapped_seg[(addr - UNMAPPED_SEG_START) as usize]
+            }
+            PRG_RAM_START..=PRG_RAM_END => self.prg_ram[(addr - PRG_RAM_START) as usize],
+            PRG_ROM_START..=PRG_ROM_END => self.cartridge.read_prg_rom(addr - PRG_ROM_START),
+        }
+    }
+
+    pub fn prg_rom_offset(&self, addr: u16) -> Option<usize> {
+        // where the cpu address is in the prg rom with the banks that are mapped now, so that the debugger can
+        // tell the banks apart. None outside of the rom
+        match addr {
+            PRG_ROM_START..=PRG_ROM_END => self.cartridge.prg_rom_offset(addr - PRG_ROM_START),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod cartridge;
-mod controller;
+pub mod controller;
+pub mod mappers;
 #[cfg(test)]
 pub mod mem_tests;
 pub mod memory;
@@ -9,15 +10,22 @@ mod ppu_memory_tests;
 pub mod ppu_registers;
 #[cfg(test)]
 mod ppu_registers_tests;
-mod user_input;
+pub mod ram_init;
+#[cfg(test)]
+mod ram_init_tests;
 
+use crate::apu::APU;
 use crate::bus::cartridge::Cartridge;
+use crate::bus::memory::Mem;
 use crate::bus::ppu_memory::PPUMemory;
-use controller::ControllerByte;
+use crate::input::ControllerStates;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+use controller::{ControllerByte, Player};
 use memory_mapping_constants::*;
 pub use ppu_memory::{NUMBER_OF_SPRITE, PPU_NAMETABLE_SIZE, PPU_NAMETABLE_START};
 use ppu_registers::PPURegisters;
-mod sdl2_keycode_serde;
+use ram_init::RamInit;
 
 #[macro_export]
 macro_rules! bus {
@@ -41,9 +49,13 @@ pub(crate) mod memory_mapping_constants {
     pub const PPU_REGISTERS_UNIQUE_SIZE: u16 = 0x0008;
     pub const PPU_REGISTERS_END: u16 = 0x3FFF;
     pub const IO_AND_AUDIO_REGISTERS_START: u16 = 0x4000;
+    pub const APU_REGISTERS_START: u16 = 0x4000;
+    pub const APU_REGISTERS_END: u16 = 0x4013;
     pub const OAM_DMA: u16 = 0x4014;
+    pub const APU_STATUS: u16 = 0x4015;
     pub const P1_CONTROLLER: u16 = 0x4016;
     pub const P2_CONTROLLER: u16 = 0x4017;
+    pub const APU_FRAME_COUNTER: u16 = 0x4017; // write only, reading it reads the second controller
     pub const IO_AND_AUDIO_REGISTERS_END: u16 = 0x401F;
     pub const UNMAPPED_SEG_START: u16 = 0x4020;
     pub const UNMAPPED_SEG_END: u16 = 0x5FFF;
@@ -53,67 +65,21 @@ pub(crate) mod memory_mapping_constants {
     pub const PRG_ROM_END: u16 = 0xFFFF;
 }
 
-use sdl2::keyboard::Keycode;
-use serde::{Deserialize, Serialize};
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
-pub struct Config {
-    #[serde(with = "sdl2_keycode_serde")]
-    pub up: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub down: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub left: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub right: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub a: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub b: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub select: Keycode,
-    #[serde(with = "sdl2_keycode_serde")]
-    pub start: Keycode,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            up: Keycode::UP,
-            down: Keycode::DOWN,
-            left: Keycode::LEFT,
-            right: Keycode::RIGHT,
-            a: Keycode::A,
-            b: Keycode::B,
-            select: Keycode::O,
-            start: Keycode::P,
-        }
-    }
-}
-
-impl Config {
-    pub fn sensible_defaults() -> Self {
-        Self {
-            up: Keycode::W,
-            down: Keycode::S,
-            left: Keycode::A,
-            right: Keycode::D,
-            a: Keycode::N,
-            b: Keycode::M,
-            select: Keycode::U,
-            start: Keycode::I,
-        }
-    }
-}
+// after power on and reset the ppu ignores some register writes until the end of its first frame, about this many cpu cycles.
+// see https://www.nesdev.org/wiki/PPU_power_up_state
+pub const PPU_WARM_UP_CYCLES: u16 = 29658;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, PartialEq)]
 pub struct Bus {
-    // cpu stuff
-    pub cpu_idle_cycles: u8,
+    // cpu stuff, the cycles the cpu is halted for (the oam dma and the dmc), it takes them every cycle
+    pub cpu_stall_cycles: u8,
     // something to hold if cpu should attend nmi
     pub nmi_generated: bool,
     // hold the values for oam dma
     pub oam_dma_page: u8,
     pub number_of_copies_in_current_oam_dma: u8,
+    // while this isn't 0, writes to PPUCTRL, PPUMASK, PPUSCROLL and PPUADDR are ignored
+    pub ppu_warm_up_cycles: u16,
 
     pub cpu_ram: [u8; CPU_RAM_MEM_UNIQUE_SIZE as usize],
     pub ppu_registers: PPURegisters,
@@ -125,16 +91,19 @@ pub struct Bus {
     pub prg_ram: [u8; (PRG_RAM_END - PRG_RAM_START + 1) as usize],
     pub cartridge: Cartridge,
     pub ppu_memory: PPUMemory,
-    pub config: Config,
+    pub apu: APU,
+    // how the memory is filled on power on (not part of the save state)
+    pub ram_init: RamInit,
 }
 
 impl Default for Bus {
     fn default() -> Bus {
         Bus {
-            cpu_idle_cycles: 0,
+            cpu_stall_cycles: 0,
             nmi_generated: false,
             oam_dma_page: 0,
             number_of_copies_in_current_oam_dma: 0,
+            ppu_warm_up_cycles: 0,
             cpu_ram: [0; CPU_RAM_MEM_UNIQUE_SIZE as usize],
             ppu_registers: PPURegisters::new(),
             p1_controller: ControllerByte::new(),
@@ -145,7 +114,125 @@ impl Default for Bus {
             prg_ram: [0; (PRG_RAM_END - PRG_RAM_START + 1) as usize],
             cartridge: Cartridge::new(),
             ppu_memory: PPUMemory::new(),
-            config: Config::default(),
+            apu: APU::new(),
+            ram_init: RamInit::default(),
+        }
+    }
+}
+
+impl Bus {
+    pub fn controller_mut(&mut self, player: Player) -> &mut ControllerByte {
+        match player {
+            Player::One => &mut self.p1_controller,
+            Player::Two => &mut self.p2_controller,
         }
     }
+
+    pub fn set_controllers(&mut self, states: ControllerStates) {
+        // the frontend's input provider decides what is held during the next frame
+        self.p1_controller
+            .set_pressed_buttons(states[Player::One.port()]);
+        self.p2_controller
+            .set_pressed_buttons(states[Player::Two.port()]);
+    }
+
+    pub fn power_on(&mut self) {
+        // turning the console off and on again loses everything but the cartridge (and the user's settings)
+        let cartridge = std::mem::replace(&mut self.cartridge, Cartridge::new());
+        let mut apu = std::mem::take(&mut self.apu);
+        apu.power_on();
+        *self = Bus {
+            cartridge,
+            apu,
+            ram_init: self.ram_init,
+            ..Bus::default()
+        };
+        self.ram_init.fill(&mut [
+            &mut self.cpu_ram,
+            &mut self.prg_ram,
+            &mut self.ppu_memory.vram,
+        ]);
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        // the reset button reaches the cpu, the apu, the ppu and the mapper, the memory keeps its content
+        self.nmi_generated = false;
+        self.cpu_stall_cycles = 0;
+        self.number_of_copies_in_current_oam_dma = 0;
+        self.ppu_registers.reset();
+        self.ppu_warm_up_cycles = PPU_WARM_UP_CYCLES;
+        self.apu.reset();
+        self.cartridge.mapper.reset();
+    }
+
+    pub fn tick_ppu_warm_up(&mut self) {
+        // should be called once every cpu cycle
+        self.ppu_warm_up_cycles = self.ppu_warm_up_cycles.saturating_sub(1);
+    }
+
+    pub fn tick_apu(&mut self) {
+        // the apu runs at the cpu clock, so this should be called once every cpu cycle
+        if let Some(chip) = self.cartridge.mapper.expansion_audio() {
+            chip.clock();
+            let output = chip.output(self.apu.expansion_gains());
+            self.apu.set_expansion_output(output);
+        }
+        self.apu.clock();
+        if let Some(sample_address) = self.apu.needed_dmc_sample_address() {
+            let sample = self.read_memory(sample_address);
+            self.apu.load_dmc_sample_byte(sample);
+            // the cpu is stalled while the dmc reads from the memory
+            self.cpu_stall_cycles += 4;
+        }
+    }
+
+    pub fn tick_mapper(&mut self) {
+        // some mappers count cpu cycles (for irqs), this should be called once every cpu cycle
+        self.cartridge.mapper.clock_cpu_cycle();
+    }
+
+    pub fn irq_pending(&self) -> bool {
+        // the irq line is shared, so any source can hold it
+        self.apu.irq_pending() || self.cartridge.mapper.irq_pending()
+    }
+}
+
+impl SaveState for Bus {
+    // the pressed buttons belong to the input provider, and are not saved
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.cpu_stall_cycles);
+        state.write_bool(self.nmi_generated);
+        state.write_u8(self.oam_dma_page);
+        state.write_u8(self.number_of_copies_in_current_oam_dma);
+        state.write_u16(self.ppu_warm_up_cycles);
+        state.write_bytes(&self.cpu_ram);
+        self.ppu_registers.save_state(state);
+        self.p1_controller.save_state(state);
+        self.p2_controller.save_state(state);
+        state.write_bytes(&self.io_and_audio_registers);
+        state.write_bytes(&self.unmapped_seg);
+        state.write_bytes(&self.prg_ram);
+        self.cartridge.save_state(state);
+        self.ppu_memory.save_state(state);
+        self.apu.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.cpu_stall_cycles = state.read_u8()?;
+        self.nmi_generated = state.read_bool()?;
+        self.oam_dma_page = state.read_u8()?;
+        self.number_of_copies_in_current_oam_dma = state.read_u8()?;
+        self.ppu_warm_up_cycles = state.read_u16()?;
+        state.read_bytes(&mut self.cpu_ram)?;
+        self.ppu_registers.load_state(state)?;
+        self.p1_controller.load_state(state)?;
+        self.p2_controller.load_state(state)?;
+        state.read_bytes(&mut self.io_and_audio_registers)?;
+        state.read_bytes(&mut self.unmapped_seg)?;
+        state.read_bytes(&mut self.prg_ram)?;
+        self.cartridge.load_state(state)?;
+        self.ppu_memory.load_state(state)?;
+        self.apu.load_state(state)
+    }
 }
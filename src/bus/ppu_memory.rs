@@ -1,5 +1,7 @@
 use crate::bus::cartridge::Mirroring;
 use crate::bus::Bus;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
 
 pub const PPU_CHR_ROM_START: u16 = 0x0000;
 pub const PPU_CHR_ROM_END: u16 = 0x1FFF;
@@ -71,6 +73,16 @@ impl Bus {
                     _ => panic!("Somehow messed up vram mirroring. Asked for address {vram_address} which resolved in table {table_number}")
                 }
             }
+            Mirroring::SingleScreenLower => {
+                // [A] [A]
+                // [A] [A]
+                vram_address - table_number * PPU_NAMETABLE_SIZE
+            }
+            Mirroring::SingleScreenUpper => {
+                // [B] [B]
+                // [B] [B]
+                vram_address - table_number * PPU_NAMETABLE_SIZE + PPU_NAMETABLE_SIZE
+            }
             Mirroring::FourScreen => {
                 // maybe this doesn't have mirroring at all? Seems like a very rare mode
                 vram_address
@@ -88,14 +100,14 @@ impl Bus {
         let ram_location: usize = ((self.oam_dma_page as usize) << 8) + current_oam_addr;
         self.ppu_memory.oam_data[current_oam_addr] = self.cpu_ram[ram_location];
         self.ppu_registers.oam_addr_register.increase(1);
-        self.cpu_idle_cycles += 2; // this action takes two cycles
+        self.cpu_stall_cycles += 2; // this action takes two cycles
         self.number_of_copies_in_current_oam_dma =
             self.number_of_copies_in_current_oam_dma.wrapping_add(1);
     }
 
     fn convert_ppu_address_to_actual_address(&mut self, address: u16) -> &mut u8 {
         match address {
-            PPU_CHR_ROM_START..=PPU_CHR_ROM_END => &mut self.cartridge.chr_rom[address as usize],
+            PPU_CHR_ROM_START..=PPU_CHR_ROM_END => self.cartridge.chr_mut(address),
             PPU_NAMETABLE_START..=PPU_NAMETABLE_END => {
                 let canonical_address = self.mirror_vram_address(address) as usize;
                 &mut self.ppu_memory.vram[canonical_address - PPU_NAMETABLE_START as usize]
@@ -165,6 +177,10 @@ impl Bus {
 
     //noinspection RsNonExhaustiveMatch
     pub fn write_ppu_memory(&mut self, address: u16, value: u8) {
+        if self.ppu_warm_up_cycles != 0 && matches!(address, 0x2000 | 0x2001 | 0x2005 | 0x2006) {
+            // the ppu is still warming up
+            return;
+        }
         match address {
             0x00..=0x01fff => panic!("Error: address {address} is not in range of ppu registers"),
             0x2000 =>            {// PPUCTRL
@@ -192,3 +208,17 @@ impl Bus {
         }
     }
 }
+
+impl SaveState for PPUMemory {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&self.palette_table);
+        state.write_bytes(&self.vram);
+        state.write_bytes(&self.oam_data);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        state.read_bytes(&mut self.palette_table)?;
+        state.read_bytes(&mut self.vram)?;
+        state.read_bytes(&mut self.oam_data)
+    }
+}
@@ -194,7 +194,7 @@ fn test_dma() {
 
     for _ in 0..255 {
         bus.copy_from_ram_to_oam();
-        bus.cpu_idle_cycles -= 2;
+        bus.cpu_stall_cycles -= 2;
     }
 
     for i in 0..256 {
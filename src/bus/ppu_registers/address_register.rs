@@ -1,3 +1,5 @@
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
 #[derive(Debug, Eq, PartialEq)]
 pub struct PPUAddressReg {
     high_byte: u8,
@@ -41,3 +43,16 @@ impl PPUAddressReg {
         self.last_byte
     }
 }
+
+impl SaveState for PPUAddressReg {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&[self.high_byte, self.low_byte, self.last_byte]);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.high_byte = state.read_u8()?;
+        self.low_byte = state.read_u8()?;
+        self.last_byte = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ use crate::bus::ppu_registers::oam_address_register::OAMAdressRegister;
 use crate::bus::ppu_registers::scroll_register::PPUScrollReg;
 use crate::bus::ppu_registers::status_register::PPUStatusRegister;
 use crate::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
 
 pub mod address_register;
 pub mod control_register;
@@ -41,6 +43,15 @@ impl PPURegisters {
         }
     }
 
+    pub fn reset(&mut self) {
+        // the reset line clears these, the status, oam address and vram address registers keep their values
+        self.control_register = PPUControlRegister::new();
+        self.mask_register = PPUMaskRegister::new();
+        self.data_register = PPUDataReg::new();
+        self.scroll_register = PPUScrollReg::new();
+        self.reset_latch();
+    }
+
     pub fn reset_latch(&mut self) {
         self.internal_latch = true;
     }
@@ -79,3 +90,29 @@ impl PPURegisters {
         self.control_register.get_sprite_pattern_address()
     }
 }
+
+impl SaveState for PPURegisters {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.control_register.read());
+        state.write_u8(self.mask_register.read());
+        self.status_register.save_state(state);
+        state.write_u8(self.oam_addr_register.read_current_value());
+        self.address_register.save_state(state);
+        state.write_u8(self.data_register.read_current_value());
+        self.scroll_register.save_state(state);
+        state.write_bool(self.internal_latch);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.control_register.write_byte(state.read_u8()?);
+        self.mask_register.write_byte(state.read_u8()?);
+        self.status_register.load_state(state)?;
+        self.oam_addr_register
+            .update_current_value(state.read_u8()?);
+        self.address_register.load_state(state)?;
+        self.data_register.update_current_value(state.read_u8()?);
+        self.scroll_register.load_state(state)?;
+        self.internal_latch = state.read_bool()?;
+        Ok(())
+    }
+}
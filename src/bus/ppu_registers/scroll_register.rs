@@ -1,3 +1,5 @@
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
 #[derive(Debug, Eq, PartialEq)]
 pub struct PPUScrollReg {
     x_scroll: u8,
@@ -33,6 +35,19 @@ impl PPUScrollReg {
     }
 }
 
+impl SaveState for PPUScrollReg {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&[self.x_scroll, self.y_scroll, self.last_byte]);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.x_scroll = state.read_u8()?;
+        self.y_scroll = state.read_u8()?;
+        self.last_byte = state.read_u8()?;
+        Ok(())
+    }
+}
+
 #[test]
 fn test_scroll_setting() {
     let mut scroll_reg = PPUScrollReg::new();
@@ -1,3 +1,5 @@
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
 #[derive(Debug, Eq, PartialEq)]
 pub struct PPUStatusRegister(u8);
 
@@ -64,6 +66,17 @@ impl PPUStatusRegister {
     }
 }
 
+impl SaveState for PPUStatusRegister {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.0);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.0 = state.read_u8()?;
+        Ok(())
+    }
+}
+
 #[test]
 fn test_set_vblank_status() {
     let mut status_register = PPUStatusRegister::new();
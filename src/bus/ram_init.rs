@@ -0,0 +1,42 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+// what the memory holds when the console is turned on. the real ram comes up with semi random content,
+// and some games (mostly by accident) depend on it
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RamPattern {
+    #[default]
+    Zeros,
+    Ones,   // all bytes are 0xFF
+    Fceux,  // 4 bytes of 0x00 and then 4 bytes of 0xFF, over and over, like fceux does
+    Random, // from the seed, so the same seed gives the same run
+}
+
+// the power on ram settings, as written in the config file
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct RamInit {
+    pub pattern: RamPattern,
+    pub seed: u64, // only used by the random pattern
+}
+
+impl RamInit {
+    pub fn fill(&self, memories: &mut [&mut [u8]]) {
+        // the memories are filled one after the other, so with the random pattern each one gets different content
+        let mut rng = ChaCha12Rng::seed_from_u64(self.seed);
+        for memory in memories.iter_mut() {
+            match self.pattern {
+                RamPattern::Zeros => memory.fill(0),
+                RamPattern::Ones => memory.fill(0xFF),
+                RamPattern::Fceux => {
+                    for (i, byte) in memory.iter_mut().enumerate() {
+                        *byte = if i & 4 == 0 { 0x00 } else { 0xFF };
+                    }
+                }
+                RamPattern::Random => rng.fill_bytes(memory),
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::bus::ram_init::{RamInit, RamPattern};
+use crate::bus::Bus;
+
+fn filled(ram_init: RamInit) -> Vec<u8> {
+    let mut first = [0x42; 16];
+    let mut second = [0x42; 16];
+    ram_init.fill(&mut [&mut first, &mut second]);
+    [first, second].concat()
+}
+
+fn with_pattern(pattern: RamPattern) -> RamInit {
+    RamInit { pattern, seed: 7 }
+}
+
+#[test]
+fn test_fixed_patterns() {
+    assert_eq!(filled(with_pattern(RamPattern::Zeros)), vec![0; 32]);
+    assert_eq!(filled(with_pattern(RamPattern::Ones)), vec![0xFF; 32]);
+    assert_eq!(
+        filled(with_pattern(RamPattern::Fceux))[..16],
+        [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+}
+
+#[test]
+fn test_random_pattern_depends_only_on_the_seed() {
+    let random = filled(with_pattern(RamPattern::Random));
+    assert_eq!(random, filled(with_pattern(RamPattern::Random)));
+    assert_ne!(random[..16], random[16..]);
+    let other_seed = RamInit {
+        pattern: RamPattern::Random,
+        seed: 8,
+    };
+    assert_ne!(random, filled(other_seed));
+}
+
+#[test]
+fn test_power_on_fills_the_memory() {
+    let mut bus = Bus {
+        ram_init: with_pattern(RamPattern::Ones),
+        ..Bus::default()
+    };
+    bus.power_on();
+    assert!(bus.cpu_ram.iter().all(|byte| *byte == 0xFF));
+    assert!(bus.prg_ram.iter().all(|byte| *byte == 0xFF));
+    assert!(bus.ppu_memory.vram.iter().all(|byte| *byte == 0xFF));
+    // and it is kept for the next power on
+    assert_eq!(bus.ram_init, with_pattern(RamPattern::Ones));
+}
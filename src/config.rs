@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod config_tests;
+
+use crate::apu::mixer::MixerConfig;
+use crate::bus::controller;
+use crate::bus::ram_init::RamInit;
+use crate::frame_limiter::SpeedConfig;
+use crate::input::gamepads::GamepadConfig;
+use crate::input::hotkeys::Hotkeys;
+use crate::input::sdl2_keycode_serde;
+use crate::save_state::rewind::RewindConfig;
+use sdl2::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
+
+// the keys of a single nes controller
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ControllerBindings {
+    #[serde(with = "sdl2_keycode_serde")]
+    pub up: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub down: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub left: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub right: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub a: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub b: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub select: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub start: Keycode,
+}
+
+impl ControllerBindings {
+    pub fn default_p2() -> Self {
+        // the keypad, so it doesn't collide with any of the player 1 defaults
+        Self {
+            up: Keycode::KP_8,
+            down: Keycode::KP_5,
+            left: Keycode::KP_4,
+            right: Keycode::KP_6,
+            a: Keycode::KP_9,
+            b: Keycode::KP_7,
+            select: Keycode::KP_MINUS,
+            start: Keycode::KP_PLUS,
+        }
+    }
+
+    pub fn button(&self, kc: Keycode) -> Option<u8> {
+        // the controller button (see controller::BUTTON_*) bound to this key, if any
+        match kc {
+            val if val == self.a => Some(controller::BUTTON_A),
+            val if val == self.b => Some(controller::BUTTON_B),
+            val if val == self.select => Some(controller::BUTTON_SELECT),
+            val if val == self.start => Some(controller::BUTTON_START),
+            val if val == self.up => Some(controller::BUTTON_UP),
+            val if val == self.down => Some(controller::BUTTON_DOWN),
+            val if val == self.left => Some(controller::BUTTON_LEFT),
+            val if val == self.right => Some(controller::BUTTON_RIGHT),
+            _ => None,
+        }
+    }
+}
+
+// the user's settings, as written in the config file
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Config {
+    // player 1 keys are at the top level of the file, like before there was a player 2
+    #[serde(flatten)]
+    pub p1: ControllerBindings,
+    #[serde(default = "ControllerBindings::default_p2")]
+    pub p2: ControllerBindings,
+    #[serde(default)]
+    pub gamepads: GamepadConfig,
+    #[serde(default)]
+    pub hotkeys: Hotkeys,
+    #[serde(default)]
+    pub mixer: MixerConfig,
+    #[serde(default)]
+    pub rewind: RewindConfig,
+    #[serde(default)]
+    pub speed: SpeedConfig,
+    #[serde(default)]
+    pub ram_init: RamInit,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            p1: ControllerBindings {
+                up: Keycode::UP,
+                down: Keycode::DOWN,
+                left: Keycode::LEFT,
+                right: Keycode::RIGHT,
+                a: Keycode::A,
+                b: Keycode::B,
+                select: Keycode::O,
+                start: Keycode::P,
+            },
+            p2: ControllerBindings::default_p2(),
+            gamepads: GamepadConfig::default(),
+            hotkeys: Hotkeys::default(),
+            mixer: MixerConfig::default(),
+            rewind: RewindConfig::default(),
+            speed: SpeedConfig::default(),
+            ram_init: RamInit::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn sensible_defaults() -> Self {
+        Self {
+            p1: ControllerBindings {
+                up: Keycode::W,
+                down: Keycode::S,
+                left: Keycode::A,
+                right: Keycode::D,
+                a: Keycode::N,
+                b: Keycode::M,
+                select: Keycode::U,
+                start: Keycode::I,
+            },
+            ..Default::default()
+        }
+    }
+}
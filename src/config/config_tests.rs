@@ -0,0 +1,43 @@
+use crate::bus::ram_init::RamPattern;
+use crate::config::Config;
+use sdl2::keyboard::Keycode;
+
+#[test]
+fn test_config_round_trip() {
+    let mut conf = Config::sensible_defaults();
+    conf.mixer.noise.volume = 30;
+    conf.mixer.expansion[1].muted = true;
+    conf.hotkeys.toggle_dmc = Keycode::K;
+    conf.p2.a = Keycode::F12;
+    conf.rewind.memory_budget_mb = 16;
+    conf.ram_init.pattern = RamPattern::Random;
+    conf.ram_init.seed = 1234;
+    let serialized = toml::to_string(&conf).unwrap();
+    assert_eq!(toml::from_str::<Config>(&serialized).unwrap(), conf);
+}
+
+#[test]
+fn test_config_without_new_sections() {
+    // configs written before player 2, the mixer and the hotkeys existed should still load
+    let old_config = r#"
+        up = "W"
+        down = "S"
+        left = "A"
+        right = "D"
+        a = "N"
+        b = "M"
+        select = "U"
+        start = "I"
+
+        [mixer.triangle]
+        volume = 40
+        muted = true
+    "#;
+    let conf: Config = toml::from_str(old_config).unwrap();
+    assert_eq!(conf.p1, Config::sensible_defaults().p1);
+    assert_eq!(conf.p2, Config::default().p2);
+    assert_eq!(conf.hotkeys, Config::default().hotkeys);
+    assert_eq!(conf.mixer.triangle.volume, 40);
+    assert!(conf.mixer.triangle.muted);
+    assert_eq!(conf.mixer.pulse_1, Default::default());
+}
@@ -1,7 +1,9 @@
 use super::super::CPU;
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
 
 #[allow(non_snake_case)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     ///  Add with Carry
     pub fn ADC(&mut self, operand: u8) {
         let res_as_u16: u16 = self.register_a as u16
@@ -42,11 +44,11 @@ impl<'a> CPU<'a> {
     }
 
     pub fn ASL_memory(&mut self, address: u16) {
-        let address_value = self.read_memory(address);
+        let address_value = self.read_for_modify(address);
         let should_carry: bool = address_value & 0x80 == 0x80;
-        self.write_memory(address, address_value << 1);
+        let result = address_value << 1;
+        self.write_memory(address, result);
         self.set_carry(should_carry);
-        let result = self.read_memory(address);
         self.set_zero_and_negative_flag(result);
     }
 
@@ -82,7 +84,7 @@ impl<'a> CPU<'a> {
     }
 
     pub fn LSR_memory(&mut self, address: u16) {
-        let address_value = self.read_memory(address);
+        let address_value = self.read_for_modify(address);
         self.set_carry(address_value & 0b1 == 1);
         let res = address_value >> 1;
         self.write_memory(address, res);
@@ -112,7 +114,7 @@ impl<'a> CPU<'a> {
 
     /// Rotate left memory
     pub fn ROL_memory(&mut self, address: u16) {
-        let initial = self.read_memory(address);
+        let initial = self.read_for_modify(address);
 
         let bit0: u8 = match self.get_status_c() {
             true => 1,
@@ -145,7 +147,7 @@ impl<'a> CPU<'a> {
 
     /// Rotate right memory
     pub fn ROR_memory(&mut self, address: u16) {
-        let initial = self.read_memory(address);
+        let initial = self.read_for_modify(address);
 
         let bit7: u8 = match self.get_status_c() {
             true => 1 << 7,
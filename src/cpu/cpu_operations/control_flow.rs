@@ -1,109 +1,76 @@
 use super::super::CPU;
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
 use crate::cpu::mem_utils::check_if_on_different_pages;
 
 #[allow(non_snake_case)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
+    fn take_branch(&mut self, new_address: u16) {
+        // a taken branch reads the next opcode for nothing, and when it crosses a page it also reads
+        // the address before the high byte was fixed
+        self.read_memory(self.program_counter);
+        if check_if_on_different_pages(self.program_counter, new_address) {
+            self.read_memory((self.program_counter & 0xFF00) | (new_address & 0x00FF));
+            self.increase_cpu_idle_cycles(2);
+        } else {
+            self.increase_cpu_idle_cycles(1);
+        }
+        self.program_counter = new_address;
+    }
+
     ///  Branch if Carry Clear
     pub fn BCC(&mut self, new_address: u16) {
         if !self.get_status_c() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Carry Set
     pub fn BCS(&mut self, new_address: u16) {
         if self.get_status_c() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Equal
     pub fn BEQ(&mut self, new_address: u16) {
         if self.get_status_z() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Minus
     pub fn BMI(&mut self, new_address: u16) {
         if self.get_status_n() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Not Equal
     pub fn BNE(&mut self, new_address: u16) {
         if !self.get_status_z() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Positive
     pub fn BPL(&mut self, new_address: u16) {
         if !self.get_status_n() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Overflow Clear
     pub fn BVC(&mut self, new_address: u16) {
         if !self.get_status_v() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
     ///  Branch if Overflow Set
     pub fn BVS(&mut self, new_address: u16) {
         if self.get_status_v() {
-            self.increase_cpu_idle_cycles(
-                match check_if_on_different_pages(self.program_counter, new_address) {
-                    true => 2,
-                    false => 1,
-                },
-            );
-            self.program_counter = new_address;
+            self.take_branch(new_address);
         }
     }
 
@@ -113,13 +80,21 @@ impl<'a> CPU<'a> {
     }
 
     ///  Jump to Subroutine
-    pub fn JSR(&mut self, address: u16) {
+    pub fn JSR(&mut self) {
+        // the high byte of the address is read only after the return address is pushed
+        let operand_address = self.program_counter.wrapping_sub(2);
+        let low = self.read_memory(operand_address) as u16;
+        self.stack_dummy_read();
         self.stack_push_u16(self.program_counter.wrapping_sub(1));
-        self.program_counter = address;
+        let high = self.read_memory(operand_address.wrapping_add(1)) as u16;
+        self.program_counter = high << 8 | low;
     }
 
     ///  Return from Subroutine
     pub fn RTS(&mut self) {
-        self.program_counter = self.stack_pull_u16().wrapping_add(1);
+        self.stack_dummy_read();
+        let return_address = self.stack_pull_u16();
+        self.read_memory(return_address);
+        self.program_counter = return_address.wrapping_add(1);
     }
 }
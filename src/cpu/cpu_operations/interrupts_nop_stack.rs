@@ -1,9 +1,12 @@
 use super::super::CPU;
+use crate::bus::memory::Mem;
+use crate::bus_mut;
+use crate::cpu::interrupt_lines::{Interrupt, InterruptLines};
 
 const BRK_ADDRESS: u16 = 0xfffe;
 
 #[allow(non_snake_case)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     /////////////////////////////
     /////// STACK RELATED ///////
     /////////////////////////////
@@ -20,12 +23,14 @@ impl<'a> CPU<'a> {
 
     ///  Pull Accumulator
     pub fn PLA(&mut self) {
+        self.stack_dummy_read();
         self.register_a = self.stack_pull();
         self.set_zero_and_negative_flag(self.register_a);
     }
 
     ///  Pull Processor Status
     pub fn PLP(&mut self) {
+        self.stack_dummy_read();
         self.stack_pull_status();
     }
 
@@ -44,6 +49,7 @@ impl<'a> CPU<'a> {
 
     ///  Return from Interrupt
     pub fn RTI(&mut self) {
+        self.stack_dummy_read();
         self.stack_pull_status(); // for some wierd reason, bits 4 and 5 are probably ignored
         self.program_counter = self.stack_pull_u16();
     }
@@ -52,6 +58,9 @@ impl<'a> CPU<'a> {
     pub fn BRK(&mut self) {
         self.stack_push_u16(self.program_counter.wrapping_add(1));
         self.stack_push(self.status | 0b00010000);
+        // like the other interrupts, irqs are masked in the handler
+        self.set_interrupt(true);
+        bus_mut!(self).interrupt_started(Interrupt::Brk);
         self.program_counter = self.read_memory_2_bytes(BRK_ADDRESS);
     }
 }
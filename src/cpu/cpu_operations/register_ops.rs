@@ -1,7 +1,9 @@
 use super::super::CPU;
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
 
 #[allow(non_snake_case)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     fn compare(&mut self, register: u8, operand: u8) {
         // compare register to operand and set flags
         if register == operand {
@@ -38,7 +40,7 @@ impl<'a> CPU<'a> {
 
     ///  Decrement Memory
     pub fn DEC(&mut self, address: u16) {
-        let new_mem_value: u8 = self.read_memory(address).wrapping_sub(1);
+        let new_mem_value: u8 = self.read_for_modify(address).wrapping_sub(1);
         self.write_memory(address, new_mem_value);
         self.set_zero_and_negative_flag(new_mem_value);
     }
@@ -57,7 +59,7 @@ impl<'a> CPU<'a> {
 
     ///  Increment Memory
     pub fn INC(&mut self, address: u16) {
-        let new_mem_value: u8 = self.read_memory(address).wrapping_add(1);
+        let new_mem_value: u8 = self.read_for_modify(address).wrapping_add(1);
         self.write_memory(address, new_mem_value);
         self.set_zero_and_negative_flag(new_mem_value);
     }
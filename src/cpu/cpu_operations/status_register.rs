@@ -1,7 +1,9 @@
 use super::super::CPU;
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
 
 #[allow(non_snake_case)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     ///  Clear Carry Flag
     pub fn CLC(&mut self) {
         self.set_carry(false);
@@ -82,11 +82,18 @@ fn JMP() {
 #[test]
 fn JSR() {
     generate_cpu!(cpu);
-    cpu.JSR(0x31a9);
-    assert_eq!(cpu.program_counter, 0x31a9);
-    cpu.JSR(0x15ff);
-    assert_eq!(cpu.program_counter, 0x15ff);
-    assert_eq!(cpu.stack_pull_u16(), 0x31a8); // should push pc-1
+    // jsr reads its operand itself, the pc is already after it
+    cpu.write_memory(0x201, 0x31);
+    cpu.write_memory(0x202, 0x07);
+    cpu.program_counter = 0x203;
+    cpu.JSR();
+    assert_eq!(cpu.program_counter, 0x731);
+    cpu.write_memory(0x732, 0xff);
+    cpu.write_memory(0x733, 0x05);
+    cpu.program_counter = 0x734;
+    cpu.JSR();
+    assert_eq!(cpu.program_counter, 0x5ff);
+    assert_eq!(cpu.stack_pull_u16(), 0x733); // should push pc-1
 }
 
 #[allow(non_snake_case)]
@@ -94,10 +101,12 @@ fn JSR() {
 fn RTS() {
     generate_cpu!(cpu);
     cpu.stack_pointer = 0xff;
-    cpu.program_counter = 0x15df;
-    cpu.JSR(0x41f5);
+    cpu.write_memory(0x5dd, 0xf5);
+    cpu.write_memory(0x5de, 0x41);
+    cpu.program_counter = 0x5df;
+    cpu.JSR();
     assert_eq!(cpu.stack_pointer, 0xfd);
     cpu.RTS();
-    assert_eq!(cpu.program_counter, 0x15df); // JSR push pc-1, RTS adds 1
+    assert_eq!(cpu.program_counter, 0x5df); // JSR push pc-1, RTS adds 1
     assert_eq!(cpu.stack_pointer, 0xff);
 }
@@ -1,3 +1,7 @@
+use crate::bus::memory::Mem;
+use crate::cpu::flat_bus::FlatBus;
+use crate::cpu::interrupt_lines::InterruptLines;
+use crate::cpu::CPU;
 use crate::{bus_mut, generate_cpu};
 
 #[test]
@@ -217,6 +221,7 @@ fn nmi_attendance() {
 
     // check we did all nmi things correctly
     assert_eq!(cpu.program_counter, 0x50); // jumped to the right location
+    assert!(cpu.get_status_i()); // and masked the irqs
 
     // check that the two values in the stack are correct
     // the top should be sp
@@ -231,7 +236,7 @@ fn nmi_attendance() {
     // set the status to check that we changed it correctly while pulling back from the stack
     cpu.status = 0xFF;
 
-    while bus_mut!(cpu).cpu_idle_cycles > 0 {
+    while cpu.cpu_idle_cycles > 0 {
         cpu.run_one_cycle();
     }
 
@@ -241,3 +246,156 @@ fn nmi_attendance() {
     assert_eq!(cpu.status, 0x30);
     assert_eq!(cpu.stack_pointer, 0xFF);
 }
+
+#[test]
+fn irq_attendance() {
+    generate_cpu!(cpu);
+    cpu.program_counter = 0x13;
+    cpu.write_memory(0x13, 0xEA); // NOP
+    cpu.write_memory(0x60, 0x40); // RTI
+
+    let mut rom = vec![0_u8; 0x8000];
+    rom[0x7FFE] = 0x60;
+    rom[0x7FFF] = 0x00;
+    bus_mut!(cpu).cartridge.prg_rom = rom;
+
+    // make the apu frame counter raise an irq, while irqs are masked
+    cpu.status = 0x04;
+    bus_mut!(cpu).apu.write_register(0x4017, 0x00);
+    while !bus_mut!(cpu).irq_pending() {
+        bus_mut!(cpu).tick_apu();
+    }
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x14); // just ran the NOP
+
+    // unmask irqs
+    cpu.status = 0x00;
+    while cpu.cpu_idle_cycles > 0 {
+        cpu.run_one_cycle();
+    }
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x60);
+    assert!(cpu.get_status_i());
+    let pushed_status = cpu.stack_pull();
+    assert_eq!(pushed_status, 0x20);
+    cpu.stack_push(pushed_status);
+
+    // the handler acknowledges the irq by reading the status, and returns
+    bus_mut!(cpu).apu.read_status();
+    while cpu.cpu_idle_cycles > 0 {
+        cpu.run_one_cycle();
+    }
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x14);
+    assert!(!cpu.get_status_i());
+}
+
+#[test]
+fn nmi_with_pending_irq() {
+    // the apu frame irq is pending when the nmi comes. the nmi handler must run to its end
+    // before the irq is taken, or the game ends up in its irq vector in the middle of the nmi
+    generate_cpu!(cpu);
+    cpu.program_counter = 0x13;
+    cpu.write_memory(0x13, 0xEA); // NOP
+    cpu.write_memory(0x50, 0xEA); // NOP
+    cpu.write_memory(0x51, 0x40); // RTI
+    cpu.write_memory(0x60, 0x40); // RTI
+
+    let mut rom = vec![0_u8; 0x8000];
+    rom[0x7FFA] = 0x50;
+    rom[0x7FFE] = 0x60;
+    bus_mut!(cpu).cartridge.prg_rom = rom;
+
+    cpu.status = 0x00;
+    bus_mut!(cpu).apu.write_register(0x4017, 0x00);
+    while !bus_mut!(cpu).irq_pending() {
+        bus_mut!(cpu).tick_apu();
+    }
+    bus_mut!(cpu).nmi_generated = true;
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x50);
+
+    // the handler runs with the irq still pending
+    for expected_pc in [0x51, 0x13] {
+        while cpu.cpu_idle_cycles > 0 {
+            cpu.run_one_cycle();
+        }
+        cpu.run_one_cycle();
+        assert_eq!(cpu.program_counter, expected_pc);
+    }
+    assert!(bus_mut!(cpu).irq_pending());
+
+    // rti unmasked the irqs again, so now it is taken
+    while cpu.cpu_idle_cycles > 0 {
+        cpu.run_one_cycle();
+    }
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x60);
+}
+
+#[test]
+fn cpu_on_flat_bus() {
+    // the same core, without the nes around it. the interrupt lines are plain fields
+    let mut bus = FlatBus::new();
+    bus.load(0x0200, &[0xA9, 0x42, 0x85, 0x10, 0xEA]); // LDA #$42; STA $10; NOP
+    bus.load(0x0300, &[0x40]); // RTI
+    bus.load(0xFFFA, &[0x00, 0x03]); // nmi vector
+    let mut cpu = CPU::new(&mut bus);
+    cpu.program_counter = 0x0200;
+
+    // LDA takes 2 cycles and STA zero page takes 3
+    for _ in 0..5 {
+        cpu.run_one_cycle();
+    }
+    assert_eq!(cpu.cpu_idle_cycles, 0);
+    assert_eq!(cpu.read_memory(0x10), 0x42);
+
+    bus_mut!(cpu).nmi = true;
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x0300);
+    assert!(!bus_mut!(cpu).nmi);
+    while cpu.cpu_idle_cycles > 0 {
+        cpu.run_one_cycle();
+    }
+    cpu.run_one_cycle();
+    assert_eq!(cpu.program_counter, 0x0204);
+}
+
+#[test]
+fn stall_cycles() {
+    // a machine that halts the cpu for 3 cycles on every write
+    #[derive(Default)]
+    struct StallingBus {
+        memory: FlatBus,
+        stall_cycles: u8,
+    }
+    impl Mem for StallingBus {
+        fn write_memory(&mut self, addr: u16, data: u8) {
+            self.stall_cycles += 3;
+            self.memory.write_memory(addr, data);
+        }
+        fn read_memory_2_bytes(&mut self, addr: u16) -> u16 {
+            self.memory.read_memory_2_bytes(addr)
+        }
+        fn read_memory(&mut self, addr: u16) -> u8 {
+            self.memory.read_memory(addr)
+        }
+    }
+    impl InterruptLines for StallingBus {
+        fn take_stall_cycles(&mut self) -> u8 {
+            std::mem::take(&mut self.stall_cycles)
+        }
+    }
+
+    let mut bus = StallingBus::default();
+    bus.memory.load(0x0200, &[0x85, 0x10]); // STA $10
+    let mut cpu = CPU::new(&mut bus);
+    cpu.program_counter = 0x0200;
+    let mut cycles = 1;
+    cpu.run_one_cycle();
+    while cpu.cpu_idle_cycles > 0 {
+        cpu.run_one_cycle();
+        cycles += 1;
+    }
+    assert_eq!(cycles, 3 + 3);
+}
@@ -0,0 +1,62 @@
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
+
+const FLAT_MEMORY_SIZE: usize = 0x10000;
+
+// 64KB of ram and nothing else: every address can be read and written, and nothing happens on the side.
+// for running the cpu on its own, like in the single step tests and the snake game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatBus {
+    pub memory: Box<[u8; FLAT_MEMORY_SIZE]>,
+    pub nmi: bool, // the interrupt lines, set them from the outside
+    pub irq: bool,
+}
+
+impl Default for FlatBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlatBus {
+    pub fn new() -> Self {
+        Self {
+            memory: Box::new([0; FLAT_MEMORY_SIZE]),
+            nmi: false,
+            irq: false,
+        }
+    }
+
+    pub fn load(&mut self, start: u16, data: &[u8]) {
+        // copies the data to the memory, starting from start (and wrapping around the end)
+        for (i, byte) in data.iter().enumerate() {
+            self.memory[start.wrapping_add(i as u16) as usize] = *byte;
+        }
+    }
+}
+
+impl Mem for FlatBus {
+    fn write_memory(&mut self, addr: u16, data: u8) {
+        self.memory[addr as usize] = data;
+    }
+
+    fn read_memory_2_bytes(&mut self, addr: u16) -> u16 {
+        let low = self.read_memory(addr) as u16;
+        let high = self.read_memory(addr.wrapping_add(1)) as u16;
+        (high << 8) | low
+    }
+
+    fn read_memory(&mut self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+}
+
+impl InterruptLines for FlatBus {
+    fn take_nmi(&mut self) -> bool {
+        std::mem::take(&mut self.nmi)
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq
+    }
+}
@@ -0,0 +1,78 @@
+use crate::bus::Bus;
+use std::fmt;
+
+// the lines between the cpu and the machine around it (the memory itself is the Mem trait).
+// everything has a default, so a machine that is only memory (like FlatBus) doesn't need to implement anything
+pub trait InterruptLines {
+    // true once for every nmi (it is edge triggered), taking it clears it
+    fn take_nmi(&mut self) -> bool {
+        false
+    }
+
+    // the irq line is level triggered, so it stays on until the source is acknowledged
+    fn irq_pending(&self) -> bool {
+        false
+    }
+
+    // the rdy line: the cycles the machine halted the cpu for since the last call (like the dmc reading a sample)
+    fn take_stall_cycles(&mut self) -> u8 {
+        0
+    }
+
+    // the machine can take the bus from the cpu (like the oam dma), returns true if it took this cycle
+    fn dma_cycle(&mut self) -> bool {
+        false
+    }
+
+    // the clock, called once every cpu cycle
+    fn tick(&mut self) {}
+
+    // the cpu started to handle an interrupt (it pushed the pc and the status, and reads the vector next)
+    fn interrupt_started(&mut self, _interrupt: Interrupt) {}
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Interrupt {
+    Nmi,
+    Irq,
+    Brk,
+}
+
+impl fmt::Display for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Nmi => "nmi",
+            Self::Irq => "irq",
+            Self::Brk => "brk",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl InterruptLines for Bus {
+    fn take_nmi(&mut self) -> bool {
+        std::mem::take(&mut self.nmi_generated)
+    }
+
+    fn irq_pending(&self) -> bool {
+        Bus::irq_pending(self)
+    }
+
+    fn take_stall_cycles(&mut self) -> u8 {
+        std::mem::take(&mut self.cpu_stall_cycles)
+    }
+
+    fn dma_cycle(&mut self) -> bool {
+        if self.number_of_copies_in_current_oam_dma == 0 {
+            return false;
+        }
+        self.copy_from_ram_to_oam();
+        true
+    }
+
+    fn tick(&mut self) {
+        self.tick_apu();
+        self.tick_mapper();
+        self.tick_ppu_warm_up();
+    }
+}
@@ -1,26 +1,32 @@
 use super::CPU;
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
 use crate::cpu::opcodes::{get_opcode_metadat_from_opcode, OpcodeMetadata};
 
 #[allow(unused_variables)]
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     pub fn massive_switch(&mut self, op_code: u8) -> bool {
-        self.program_counter += 1;
+        self.program_counter = self.program_counter.wrapping_add(1);
 
         let opcode_metadata: OpcodeMetadata = get_opcode_metadat_from_opcode(op_code);
         assert_eq!(opcode_metadata.opcode, op_code);
 
         let addressing_mode = opcode_metadata.addressing_mode;
-        let address = self.convert_mode_to_operand_mem_address(addressing_mode);
+        let (address, page_crossed) = match op_code {
+            // jsr pushes the return address between the two bytes of its operand, so it reads them itself
+            0x20 => (0, false),
+            _ => {
+                self.fetch_operand_address(addressing_mode, !opcode_metadata.increase_on_page_cross)
+            }
+        };
         let new_address = address;
 
         self.increase_cpu_idle_cycles(opcode_metadata.base_cycles);
-        if opcode_metadata.increase_on_page_cross {
-            if self.detect_page_cross(addressing_mode) {
-                self.increase_cpu_idle_cycles(1);
-            }
+        if opcode_metadata.increase_on_page_cross && page_crossed {
+            self.increase_cpu_idle_cycles(1);
         }
 
-        self.program_counter += opcode_metadata.bytes - 1;
+        self.program_counter = self.program_counter.wrapping_add(opcode_metadata.bytes - 1);
 
         match op_code {
             ///////////////////////
@@ -620,7 +626,7 @@ impl<'a> CPU<'a> {
 
             // JSR : Jump to Subroutine
             0x20 => {
-                self.JSR(address);
+                self.JSR();
             }
 
             // RTS : Return from Subroutine
@@ -5,9 +5,10 @@ mod mem_tests;
 
 use crate::bus::memory::Mem;
 use crate::bus_mut;
+use crate::cpu::interrupt_lines::InterruptLines;
 use crate::cpu::CPU;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum AddressingMode {
     Immediate,
@@ -31,7 +32,7 @@ pub fn check_if_on_different_pages(addr1: u16, addr2: u16) -> bool {
     (addr1 & 0xFF00) != (addr2 & 0xFF00)
 }
 
-impl<'a> CPU<'a> {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
     pub fn read_memory(&mut self, addr: u16) -> u8 {
         bus_mut!(self).read_memory(addr)
     }
@@ -57,6 +58,13 @@ impl<'a> CPU<'a> {
         bus_mut!(self).write_memory(addr, data);
     }
 
+    pub fn read_for_modify(&mut self, addr: u16) -> u8 {
+        // the read-modify-write opcodes write the old value back before they write the new one
+        let value = self.read_memory(addr);
+        self.write_memory(addr, value);
+        value
+    }
+
     pub fn convert_mode_to_val(&mut self, mode: AddressingMode) -> u8 {
         let operand_memory_address = self.convert_mode_to_operand_mem_address(mode);
         self.read_memory(operand_memory_address)
@@ -109,6 +117,78 @@ impl<'a> CPU<'a> {
         }
     }
 
+    pub fn fetch_operand_address(
+        &mut self,
+        mode: AddressingMode,
+        always_fix_address: bool,
+    ) -> (u16, bool) {
+        // like convert_mode_to_operand_mem_address, but it reads the bus like the real cpu does, dummy reads
+        // included, and it also tells if the indexing crossed a page.
+        // the indexed modes read the address before its high byte is fixed - writes and read-modify-writes
+        // always do it (always_fix_address), plain reads only when the page was crossed
+        let pc = self.program_counter;
+        match mode {
+            AddressingMode::Immediate => (pc, false),
+            AddressingMode::ZeroPage => (self.read_memory(pc) as u16, false),
+            AddressingMode::ZeroPage_X | AddressingMode::ZeroPage_Y => {
+                let base = self.read_memory(pc);
+                self.read_memory(base as u16);
+                let index = match mode {
+                    AddressingMode::ZeroPage_X => self.register_x,
+                    _ => self.register_y,
+                };
+                (base.wrapping_add(index) as u16, false)
+            }
+            AddressingMode::Relative => {
+                let offset = self.read_memory(pc) as i8;
+                (pc.wrapping_add(2).wrapping_add(offset as u16), false)
+            }
+            AddressingMode::Absolute => (self.read_memory_2_bytes(pc), false),
+            AddressingMode::Absolute_X => {
+                let base = self.read_memory_2_bytes(pc);
+                self.index_address(base, self.register_x, always_fix_address)
+            }
+            AddressingMode::Absolute_Y => {
+                let base = self.read_memory_2_bytes(pc);
+                self.index_address(base, self.register_y, always_fix_address)
+            }
+            AddressingMode::Indirect => {
+                let intermediate_memory_value = self.read_memory_2_bytes(pc);
+                let address =
+                    self.read_memory_2_bytes_without_page_cross(intermediate_memory_value);
+                (address, false)
+            }
+            AddressingMode::Indirect_X => {
+                let zero_page_location = self.read_memory(pc);
+                self.read_memory(zero_page_location as u16);
+                let address = self.read_memory_2_bytes_without_page_cross(
+                    zero_page_location.wrapping_add(self.register_x) as u16,
+                );
+                (address, false)
+            }
+            AddressingMode::Indirect_Y => {
+                let zero_page_location = self.read_memory(pc);
+                let base = self.read_memory_2_bytes_without_page_cross(zero_page_location as u16);
+                self.index_address(base, self.register_y, always_fix_address)
+            }
+            AddressingMode::Accumulator | AddressingMode::NoneAddressing => {
+                // the cpu reads the next byte anyway
+                self.read_memory(pc);
+                (0, false)
+            }
+        }
+    }
+
+    fn index_address(&mut self, base: u16, index: u8, always_fix_address: bool) -> (u16, bool) {
+        let address = base.wrapping_add(index as u16);
+        let page_crossed = check_if_on_different_pages(base, address);
+        if page_crossed || always_fix_address {
+            // the low byte is added first, so this reads the wrong page when the page was crossed
+            self.read_memory((base & 0xFF00) | (address & 0x00FF));
+        }
+        (address, page_crossed)
+    }
+
     pub fn detect_page_cross(&mut self, addressing_mode: AddressingMode) -> bool {
         // check if page cross happened while fetching the value
         match addressing_mode {
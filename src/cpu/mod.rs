@@ -3,30 +3,50 @@ mod cpu_operations;
 mod cpu_operations_tests;
 #[cfg(test)]
 mod cpu_tests;
+pub mod flat_bus;
+pub mod interrupt_lines;
 mod massive_switch;
 pub mod mem_utils;
-mod opcodes;
+pub(crate) mod opcodes;
+pub mod single_step;
 
+use crate::bus::memory::Mem;
 use crate::bus::memory_mapping_constants::PRG_ROM_START;
 use crate::bus::Bus;
+use crate::save_state::{SaveState, StateReader, StateWriter};
 use crate::{bus, bus_mut};
+use anyhow::Result;
+use interrupt_lines::{Interrupt, InterruptLines};
 
 const STACK_END: u16 = 0x100;
 const NMI_ADDRESS: u16 = 0xFFFA;
+const IRQ_ADDRESS: u16 = 0xFFFE;
+const RESET_ADDRESS: u16 = 0xFFFC;
 
+// the 6502 core. it runs on the nes bus by default, but any memory with interrupt lines will do
 #[derive(Debug)]
-pub struct CPU<'a> {
+pub struct CPU<'a, B: Mem + InterruptLines = Bus> {
     pub register_a: u8,
     pub status: u8,
     pub program_counter: u16,
     pub register_x: u8,
     pub register_y: u8,
     pub stack_pointer: u8,
-    pub bus: Option<&'a mut Bus>,
+    // the cycles the cpu still rests before the next instruction (the current instruction, interrupts, stalls)
+    pub cpu_idle_cycles: u8,
+    pub bus: Option<&'a mut B>,
 }
 
-impl<'a> CPU<'a> {
-    pub fn new(bus: &'a mut Bus) -> Self {
+impl<'a, B: Mem + InterruptLines> CPU<'a, B> {
+    pub fn new(bus: &'a mut B) -> Self {
+        CPU {
+            bus: Some(bus),
+            ..Self::without_bus()
+        }
+    }
+
+    pub fn without_bus() -> Self {
+        // just the registers, like the cpu the nes keeps between cycles. with_bus gives it a bus to run on
         CPU {
             register_a: 0,
             status: 0,
@@ -34,13 +54,34 @@ impl<'a> CPU<'a> {
             register_x: 0,
             register_y: 0,
             stack_pointer: 0xff,
+            cpu_idle_cycles: 0,
+            bus: None,
+        }
+    }
+
+    // the same cpu (the registers, and how far it is in the instruction) on another bus, like the nes bus with
+    // a debugger in between. copy_registers_from brings the registers back
+    pub fn with_bus<'b, C: Mem + InterruptLines>(&self, bus: &'b mut C) -> CPU<'b, C> {
+        CPU {
+            register_a: self.register_a,
+            status: self.status,
+            program_counter: self.program_counter,
+            register_x: self.register_x,
+            register_y: self.register_y,
+            stack_pointer: self.stack_pointer,
+            cpu_idle_cycles: self.cpu_idle_cycles,
             bus: Some(bus),
         }
     }
 
-    pub fn load(&mut self, program: Vec<u8>) {
-        bus_mut!(self).cartridge.raw_load(program);
-        self.program_counter = PRG_ROM_START;
+    pub fn copy_registers_from<C: Mem + InterruptLines>(&mut self, other: &CPU<'_, C>) {
+        self.register_a = other.register_a;
+        self.status = other.status;
+        self.program_counter = other.program_counter;
+        self.register_x = other.register_x;
+        self.register_y = other.register_y;
+        self.stack_pointer = other.stack_pointer;
+        self.cpu_idle_cycles = other.cpu_idle_cycles;
     }
 
     pub fn get_status_n(&self) -> bool {
@@ -133,6 +174,10 @@ impl<'a> CPU<'a> {
         let high = self.stack_pull() as u16;
         high << 8 | low
     }
+    pub fn stack_dummy_read(&mut self) {
+        // the cpu reads the top of the stack for nothing before it pulls from it (and in jsr, before it pushes)
+        self.read_memory(STACK_END + self.stack_pointer as u16);
+    }
     pub fn stack_push_status(&mut self) {
         // this function push the status register to the stack
         // it mostly regular push, but it ignores the B flag (always set it)
@@ -162,45 +207,90 @@ impl<'a> CPU<'a> {
         // push the pc and the status to the stack
         self.stack_push_u16(self.program_counter);
         self.stack_push_status_nmi();
-        self.set_interrupt(false);
+        // like irqs, the handler starts with irqs masked (rti brings back the old flag)
+        self.set_interrupt(true);
+        bus_mut!(self).interrupt_started(Interrupt::Nmi);
 
         // takes two(?) cycles
         self.increase_cpu_idle_cycles(2);
         self.program_counter = self.read_memory_2_bytes(NMI_ADDRESS);
     }
 
+    fn attend_irq_interrupt(&mut self) {
+        // attends to irq interrupt (from the apu or the mapper)
+        // this loads the address from 0xFFFE, and masks other irqs until the handler is done
+        self.stack_push_u16(self.program_counter);
+        self.stack_push_status_nmi();
+        self.set_interrupt(true);
+        bus_mut!(self).interrupt_started(Interrupt::Irq);
+
+        // takes 7 cycles
+        self.increase_cpu_idle_cycles(7);
+        self.program_counter = self.read_memory_2_bytes(IRQ_ADDRESS);
+    }
+
+    pub fn reset(&mut self) {
+        // the reset sequence is an interrupt with the writes turned into reads, so the stack pointer still
+        // goes down by 3 but nothing is pushed. the registers keep their values, and irqs are masked
+        self.stack_pointer = self.stack_pointer.wrapping_sub(3);
+        self.set_interrupt(true);
+        self.program_counter = self.read_memory_2_bytes(RESET_ADDRESS);
+
+        // takes 7 cycles, like the other interrupts
+        self.cpu_idle_cycles = 7;
+    }
+
     pub fn increase_cpu_idle_cycles(&mut self, inc: u8) {
         // if we want to say certain action took x cycles, we just tell the cpu to rest in the next x cycles
-        bus_mut!(self).cpu_idle_cycles += inc;
+        self.cpu_idle_cycles += inc;
     }
 
     pub fn decrease_cpu_idle_cycles(&mut self, dec: u8) {
         // same thing, every cycle we decrease the number of cycles we need to wait
-        bus_mut!(self).cpu_idle_cycles -= dec;
+        self.cpu_idle_cycles -= dec;
     }
 
-    fn do_one_operation(&mut self) -> bool {
-        if bus!(self).number_of_copies_in_current_oam_dma != 0 {
-            bus_mut!(self).copy_from_ram_to_oam();
+    fn take_stall_cycles(&mut self) {
+        // the machine halted the cpu (the oam dma or the dmc), so it rests for these cycles too
+        let stall = bus_mut!(self).take_stall_cycles();
+        self.increase_cpu_idle_cycles(stall);
+    }
+
+    fn do_one_operation(&mut self, before_instruction: impl FnOnce(&Self)) -> bool {
+        if bus_mut!(self).dma_cycle() {
             return true;
         }
+        before_instruction(self);
         let opcode = self.read_memory(self.program_counter);
         self.massive_switch(opcode)
     }
 
     pub fn run_one_cycle(&mut self) -> bool {
+        self.run_one_cycle_with(|_| {})
+    }
+
+    pub fn run_one_cycle_with(&mut self, before_instruction: impl FnOnce(&Self)) -> bool {
+        // like run_one_cycle, and if an instruction starts in this cycle, before_instruction sees the cpu right
+        // before it (after the interrupts and the dma, so it is the instruction that really runs)
         let mut return_value: bool = true;
 
-        if bus_mut!(self).nmi_generated {
+        if bus_mut!(self).take_nmi() {
             // attend to nmi if needed
             self.attend_nmi_interrupt();
-            bus_mut!(self).nmi_generated = false;
         }
 
-        if bus_mut!(self).cpu_idle_cycles == 0 {
-            return_value = self.do_one_operation();
+        if self.cpu_idle_cycles == 0 && !self.get_status_i() && bus!(self).irq_pending() {
+            // irqs are only checked between instructions
+            self.attend_irq_interrupt();
+        }
+
+        if self.cpu_idle_cycles == 0 {
+            return_value = self.do_one_operation(before_instruction);
         }
+        self.take_stall_cycles();
         self.decrease_cpu_idle_cycles(1);
+        bus_mut!(self).tick();
+        self.take_stall_cycles();
         return_value
     }
 
@@ -208,7 +298,7 @@ impl<'a> CPU<'a> {
         self.program_counter = 0;
 
         loop {
-            if bus_mut!(self).cpu_idle_cycles == 0 {
+            if self.cpu_idle_cycles == 0 {
                 let opcode = program[self.program_counter as usize];
                 if !self.massive_switch(opcode) {
                     return;
@@ -219,3 +309,34 @@ impl<'a> CPU<'a> {
         }
     }
 }
+
+impl CPU<'_> {
+    pub fn load(&mut self, program: Vec<u8>) {
+        bus_mut!(self).cartridge.raw_load(program);
+        self.program_counter = PRG_ROM_START;
+    }
+}
+
+impl<B: Mem + InterruptLines> SaveState for CPU<'_, B> {
+    // only the registers, the bus is saved on its own
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.cpu_idle_cycles);
+        state.write_u8(self.register_a);
+        state.write_u8(self.status);
+        state.write_u16(self.program_counter);
+        state.write_u8(self.register_x);
+        state.write_u8(self.register_y);
+        state.write_u8(self.stack_pointer);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.cpu_idle_cycles = state.read_u8()?;
+        self.register_a = state.read_u8()?;
+        self.status = state.read_u8()?;
+        self.program_counter = state.read_u16()?;
+        self.register_x = state.read_u8()?;
+        self.register_y = state.read_u8()?;
+        self.stack_pointer = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use crate::cpu::mem_utils::AddressingMode;
 
 pub struct OpcodeMetadata {
     pub opcode: u8,                      // the opcodes
+    pub mnemonic: &'static str,          // the name of the operation, like LDA
     pub bytes: u16,                      // the number of bytes the opcode read
     pub base_cycles: u8,                 // number of cycle the opcode takes
     pub addressing_mode: AddressingMode, // the addressing mod
@@ -9,6 +10,7 @@ pub struct OpcodeMetadata {
 }
 const OPCODE_00: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x00,
+    mnemonic: "BRK",
     bytes: 1,
     base_cycles: 7,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -16,6 +18,7 @@ const OPCODE_00: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_01: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x01,
+    mnemonic: "ORA",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -23,6 +26,7 @@ const OPCODE_01: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_05: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x05,
+    mnemonic: "ORA",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -30,6 +34,7 @@ const OPCODE_05: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_06: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x06,
+    mnemonic: "ASL",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -37,6 +42,7 @@ const OPCODE_06: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_08: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x08,
+    mnemonic: "PHP",
     bytes: 1,
     base_cycles: 3,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -44,6 +50,7 @@ const OPCODE_08: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_09: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x09,
+    mnemonic: "ORA",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -51,6 +58,7 @@ const OPCODE_09: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_0A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x0A,
+    mnemonic: "ASL",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::Accumulator,
@@ -58,6 +66,7 @@ const OPCODE_0A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_0D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x0D,
+    mnemonic: "ORA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -65,6 +74,7 @@ const OPCODE_0D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_0E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x0E,
+    mnemonic: "ASL",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -72,6 +82,7 @@ const OPCODE_0E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_10: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x10,
+    mnemonic: "BPL",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -79,6 +90,7 @@ const OPCODE_10: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_11: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x11,
+    mnemonic: "ORA",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -86,6 +98,7 @@ const OPCODE_11: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_15: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x15,
+    mnemonic: "ORA",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -93,6 +106,7 @@ const OPCODE_15: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_16: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x16,
+    mnemonic: "ASL",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -100,6 +114,7 @@ const OPCODE_16: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_18: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x18,
+    mnemonic: "CLC",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -107,6 +122,7 @@ const OPCODE_18: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_19: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x19,
+    mnemonic: "ORA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -114,6 +130,7 @@ const OPCODE_19: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_1D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x1D,
+    mnemonic: "ORA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -121,6 +138,7 @@ const OPCODE_1D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_1E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x1E,
+    mnemonic: "ASL",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
@@ -128,6 +146,7 @@ const OPCODE_1E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_20: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x20,
+    mnemonic: "JSR",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -135,6 +154,7 @@ const OPCODE_20: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_21: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x21,
+    mnemonic: "AND",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -142,6 +162,7 @@ const OPCODE_21: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_24: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x24,
+    mnemonic: "BIT",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -149,6 +170,7 @@ const OPCODE_24: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_25: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x25,
+    mnemonic: "AND",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -156,6 +178,7 @@ const OPCODE_25: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_26: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x26,
+    mnemonic: "ROL",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -163,6 +186,7 @@ const OPCODE_26: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_28: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x28,
+    mnemonic: "PLP",
     bytes: 1,
     base_cycles: 4,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -170,6 +194,7 @@ const OPCODE_28: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_29: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x29,
+    mnemonic: "AND",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -177,6 +202,7 @@ const OPCODE_29: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_2A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x2A,
+    mnemonic: "ROL",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::Accumulator,
@@ -184,6 +210,7 @@ const OPCODE_2A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_2C: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x2C,
+    mnemonic: "BIT",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -191,6 +218,7 @@ const OPCODE_2C: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_2D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x2D,
+    mnemonic: "AND",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -198,6 +226,7 @@ const OPCODE_2D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_2E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x2E,
+    mnemonic: "ROL",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -205,6 +234,7 @@ const OPCODE_2E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_30: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x30,
+    mnemonic: "BMI",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -212,6 +242,7 @@ const OPCODE_30: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_31: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x31,
+    mnemonic: "AND",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -219,6 +250,7 @@ const OPCODE_31: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_35: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x35,
+    mnemonic: "AND",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -226,6 +258,7 @@ const OPCODE_35: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_36: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x36,
+    mnemonic: "ROL",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -233,6 +266,7 @@ const OPCODE_36: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_38: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x38,
+    mnemonic: "SEC",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -240,6 +274,7 @@ const OPCODE_38: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_39: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x39,
+    mnemonic: "AND",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -247,6 +282,7 @@ const OPCODE_39: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_3D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x3D,
+    mnemonic: "AND",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -254,6 +290,7 @@ const OPCODE_3D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_3E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x3E,
+    mnemonic: "ROL",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
@@ -261,6 +298,7 @@ const OPCODE_3E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_40: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x40,
+    mnemonic: "RTI",
     bytes: 1,
     base_cycles: 6,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -268,6 +306,7 @@ const OPCODE_40: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_41: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x41,
+    mnemonic: "EOR",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -275,6 +314,7 @@ const OPCODE_41: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_45: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x45,
+    mnemonic: "EOR",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -282,6 +322,7 @@ const OPCODE_45: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_46: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x46,
+    mnemonic: "LSR",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -289,6 +330,7 @@ const OPCODE_46: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_48: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x48,
+    mnemonic: "PHA",
     bytes: 1,
     base_cycles: 3,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -296,6 +338,7 @@ const OPCODE_48: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_49: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x49,
+    mnemonic: "EOR",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -303,6 +346,7 @@ const OPCODE_49: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_4A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x4A,
+    mnemonic: "LSR",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::Accumulator,
@@ -310,6 +354,7 @@ const OPCODE_4A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_4C: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x4C,
+    mnemonic: "JMP",
     bytes: 3,
     base_cycles: 3,
     addressing_mode: AddressingMode::Absolute,
@@ -317,6 +362,7 @@ const OPCODE_4C: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_4D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x4D,
+    mnemonic: "EOR",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -324,6 +370,7 @@ const OPCODE_4D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_4E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x4E,
+    mnemonic: "LSR",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -331,6 +378,7 @@ const OPCODE_4E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_50: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x50,
+    mnemonic: "BVC",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -338,6 +386,7 @@ const OPCODE_50: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_51: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x51,
+    mnemonic: "EOR",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -345,6 +394,7 @@ const OPCODE_51: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_55: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x55,
+    mnemonic: "EOR",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -352,6 +402,7 @@ const OPCODE_55: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_56: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x56,
+    mnemonic: "LSR",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -359,6 +410,7 @@ const OPCODE_56: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_58: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x58,
+    mnemonic: "CLI",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -366,6 +418,7 @@ const OPCODE_58: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_59: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x59,
+    mnemonic: "EOR",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -373,6 +426,7 @@ const OPCODE_59: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_5D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x5D,
+    mnemonic: "EOR",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -380,6 +434,7 @@ const OPCODE_5D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_5E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x5E,
+    mnemonic: "LSR",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
@@ -387,6 +442,7 @@ const OPCODE_5E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_60: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x60,
+    mnemonic: "RTS",
     bytes: 1,
     base_cycles: 6,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -394,6 +450,7 @@ const OPCODE_60: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_61: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x61,
+    mnemonic: "ADC",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -401,6 +458,7 @@ const OPCODE_61: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_65: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x65,
+    mnemonic: "ADC",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -408,6 +466,7 @@ const OPCODE_65: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_66: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x66,
+    mnemonic: "ROR",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -415,6 +474,7 @@ const OPCODE_66: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_68: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x68,
+    mnemonic: "PLA",
     bytes: 1,
     base_cycles: 4,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -422,6 +482,7 @@ const OPCODE_68: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_69: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x69,
+    mnemonic: "ADC",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -429,6 +490,7 @@ const OPCODE_69: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_6A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x6A,
+    mnemonic: "ROR",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::Accumulator,
@@ -436,6 +498,7 @@ const OPCODE_6A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_6C: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x6C,
+    mnemonic: "JMP",
     bytes: 3,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect,
@@ -443,6 +506,7 @@ const OPCODE_6C: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_6D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x6D,
+    mnemonic: "ADC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -450,6 +514,7 @@ const OPCODE_6D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_6E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x6E,
+    mnemonic: "ROR",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -457,6 +522,7 @@ const OPCODE_6E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_70: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x70,
+    mnemonic: "BVS",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -464,6 +530,7 @@ const OPCODE_70: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_71: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x71,
+    mnemonic: "ADC",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -471,6 +538,7 @@ const OPCODE_71: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_75: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x75,
+    mnemonic: "ADC",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -478,6 +546,7 @@ const OPCODE_75: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_76: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x76,
+    mnemonic: "ROR",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -485,6 +554,7 @@ const OPCODE_76: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_78: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x78,
+    mnemonic: "SEI",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -492,6 +562,7 @@ const OPCODE_78: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_79: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x79,
+    mnemonic: "ADC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -499,6 +570,7 @@ const OPCODE_79: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_7D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x7D,
+    mnemonic: "ADC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -506,6 +578,7 @@ const OPCODE_7D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_7E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x7E,
+    mnemonic: "ROR",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
@@ -513,6 +586,7 @@ const OPCODE_7E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_81: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x81,
+    mnemonic: "STA",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -520,6 +594,7 @@ const OPCODE_81: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_84: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x84,
+    mnemonic: "STY",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -527,6 +602,7 @@ const OPCODE_84: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_85: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x85,
+    mnemonic: "STA",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -534,6 +610,7 @@ const OPCODE_85: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_86: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x86,
+    mnemonic: "STX",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -541,6 +618,7 @@ const OPCODE_86: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_88: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x88,
+    mnemonic: "DEY",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -548,6 +626,7 @@ const OPCODE_88: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_8A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x8A,
+    mnemonic: "TXA",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -555,6 +634,7 @@ const OPCODE_8A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_8C: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x8C,
+    mnemonic: "STY",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -562,6 +642,7 @@ const OPCODE_8C: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_8D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x8D,
+    mnemonic: "STA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -569,6 +650,7 @@ const OPCODE_8D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_8E: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x8E,
+    mnemonic: "STX",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -576,6 +658,7 @@ const OPCODE_8E: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_90: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x90,
+    mnemonic: "BCC",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -583,6 +666,7 @@ const OPCODE_90: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_91: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x91,
+    mnemonic: "STA",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -590,6 +674,7 @@ const OPCODE_91: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_94: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x94,
+    mnemonic: "STY",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -597,6 +682,7 @@ const OPCODE_94: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_95: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x95,
+    mnemonic: "STA",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -604,6 +690,7 @@ const OPCODE_95: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_96: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x96,
+    mnemonic: "STX",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_Y,
@@ -611,6 +698,7 @@ const OPCODE_96: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_98: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x98,
+    mnemonic: "TYA",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -618,6 +706,7 @@ const OPCODE_98: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_99: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x99,
+    mnemonic: "STA",
     bytes: 3,
     base_cycles: 5,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -625,6 +714,7 @@ const OPCODE_99: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_9A: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x9A,
+    mnemonic: "TXS",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -632,6 +722,7 @@ const OPCODE_9A: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_9D: OpcodeMetadata = OpcodeMetadata {
     opcode: 0x9D,
+    mnemonic: "STA",
     bytes: 3,
     base_cycles: 5,
     addressing_mode: AddressingMode::Absolute_X,
@@ -639,6 +730,7 @@ const OPCODE_9D: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA0,
+    mnemonic: "LDY",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -646,6 +738,7 @@ const OPCODE_A0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA1,
+    mnemonic: "LDA",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -653,6 +746,7 @@ const OPCODE_A1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A2: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA2,
+    mnemonic: "LDX",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -660,6 +754,7 @@ const OPCODE_A2: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A4: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA4,
+    mnemonic: "LDY",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -667,6 +762,7 @@ const OPCODE_A4: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA5,
+    mnemonic: "LDA",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -674,6 +770,7 @@ const OPCODE_A5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA6,
+    mnemonic: "LDX",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -681,6 +778,7 @@ const OPCODE_A6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA8,
+    mnemonic: "TAY",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -688,6 +786,7 @@ const OPCODE_A8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_A9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xA9,
+    mnemonic: "LDA",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -695,6 +794,7 @@ const OPCODE_A9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_AA: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xAA,
+    mnemonic: "TAX",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -702,6 +802,7 @@ const OPCODE_AA: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_AC: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xAC,
+    mnemonic: "LDY",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -709,6 +810,7 @@ const OPCODE_AC: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_AD: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xAD,
+    mnemonic: "LDA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -716,6 +818,7 @@ const OPCODE_AD: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_AE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xAE,
+    mnemonic: "LDX",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -723,6 +826,7 @@ const OPCODE_AE: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB0,
+    mnemonic: "BCS",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -730,6 +834,7 @@ const OPCODE_B0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB1,
+    mnemonic: "LDA",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -737,6 +842,7 @@ const OPCODE_B1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B4: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB4,
+    mnemonic: "LDY",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -744,6 +850,7 @@ const OPCODE_B4: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB5,
+    mnemonic: "LDA",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -751,6 +858,7 @@ const OPCODE_B5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB6,
+    mnemonic: "LDX",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_Y,
@@ -758,6 +866,7 @@ const OPCODE_B6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB8,
+    mnemonic: "CLV",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -765,6 +874,7 @@ const OPCODE_B8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_B9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xB9,
+    mnemonic: "LDA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -772,6 +882,7 @@ const OPCODE_B9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_BA: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xBA,
+    mnemonic: "TSX",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -779,6 +890,7 @@ const OPCODE_BA: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_BC: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xBC,
+    mnemonic: "LDY",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -786,6 +898,7 @@ const OPCODE_BC: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_BD: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xBD,
+    mnemonic: "LDA",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -793,6 +906,7 @@ const OPCODE_BD: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_BE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xBE,
+    mnemonic: "LDX",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -800,6 +914,7 @@ const OPCODE_BE: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC0,
+    mnemonic: "CPY",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -807,6 +922,7 @@ const OPCODE_C0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC1,
+    mnemonic: "CMP",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -814,6 +930,7 @@ const OPCODE_C1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C4: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC4,
+    mnemonic: "CPY",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -821,6 +938,7 @@ const OPCODE_C4: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC5,
+    mnemonic: "CMP",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -828,6 +946,7 @@ const OPCODE_C5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC6,
+    mnemonic: "DEC",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -835,6 +954,7 @@ const OPCODE_C6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC8,
+    mnemonic: "INY",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -842,6 +962,7 @@ const OPCODE_C8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_C9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xC9,
+    mnemonic: "CMP",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -849,6 +970,7 @@ const OPCODE_C9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_CA: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xCA,
+    mnemonic: "DEX",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -856,6 +978,7 @@ const OPCODE_CA: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_CC: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xCC,
+    mnemonic: "CPY",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -863,6 +986,7 @@ const OPCODE_CC: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_CD: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xCD,
+    mnemonic: "CMP",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -870,6 +994,7 @@ const OPCODE_CD: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_CE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xCE,
+    mnemonic: "DEC",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -877,6 +1002,7 @@ const OPCODE_CE: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD0,
+    mnemonic: "BNE",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -884,6 +1010,7 @@ const OPCODE_D0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD1,
+    mnemonic: "CMP",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -891,6 +1018,7 @@ const OPCODE_D1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD5,
+    mnemonic: "CMP",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -898,6 +1026,7 @@ const OPCODE_D5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD6,
+    mnemonic: "DEC",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -905,6 +1034,7 @@ const OPCODE_D6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD8,
+    mnemonic: "CLD",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -912,6 +1042,7 @@ const OPCODE_D8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_D9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xD9,
+    mnemonic: "CMP",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -919,6 +1050,7 @@ const OPCODE_D9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_DD: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xDD,
+    mnemonic: "CMP",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -926,6 +1058,7 @@ const OPCODE_DD: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_DE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xDE,
+    mnemonic: "DEC",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
@@ -933,6 +1066,7 @@ const OPCODE_DE: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE0,
+    mnemonic: "CPX",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -940,6 +1074,7 @@ const OPCODE_E0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE1,
+    mnemonic: "SBC",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::Indirect_X,
@@ -947,6 +1082,7 @@ const OPCODE_E1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E4: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE4,
+    mnemonic: "CPX",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -954,6 +1090,7 @@ const OPCODE_E4: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE5,
+    mnemonic: "SBC",
     bytes: 2,
     base_cycles: 3,
     addressing_mode: AddressingMode::ZeroPage,
@@ -961,6 +1098,7 @@ const OPCODE_E5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE6,
+    mnemonic: "INC",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::ZeroPage,
@@ -968,6 +1106,7 @@ const OPCODE_E6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE8,
+    mnemonic: "INX",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -975,6 +1114,7 @@ const OPCODE_E8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_E9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xE9,
+    mnemonic: "SBC",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Immediate,
@@ -982,6 +1122,7 @@ const OPCODE_E9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_EA: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xEA,
+    mnemonic: "NOP",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -989,6 +1130,7 @@ const OPCODE_EA: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_EC: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xEC,
+    mnemonic: "CPX",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -996,6 +1138,7 @@ const OPCODE_EC: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_ED: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xED,
+    mnemonic: "SBC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute,
@@ -1003,6 +1146,7 @@ const OPCODE_ED: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_EE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xEE,
+    mnemonic: "INC",
     bytes: 3,
     base_cycles: 6,
     addressing_mode: AddressingMode::Absolute,
@@ -1010,6 +1154,7 @@ const OPCODE_EE: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F0: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF0,
+    mnemonic: "BEQ",
     bytes: 2,
     base_cycles: 2,
     addressing_mode: AddressingMode::Relative,
@@ -1017,6 +1162,7 @@ const OPCODE_F0: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F1: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF1,
+    mnemonic: "SBC",
     bytes: 2,
     base_cycles: 5,
     addressing_mode: AddressingMode::Indirect_Y,
@@ -1024,6 +1170,7 @@ const OPCODE_F1: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F5: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF5,
+    mnemonic: "SBC",
     bytes: 2,
     base_cycles: 4,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -1031,6 +1178,7 @@ const OPCODE_F5: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F6: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF6,
+    mnemonic: "INC",
     bytes: 2,
     base_cycles: 6,
     addressing_mode: AddressingMode::ZeroPage_X,
@@ -1038,6 +1186,7 @@ const OPCODE_F6: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F8: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF8,
+    mnemonic: "SED",
     bytes: 1,
     base_cycles: 2,
     addressing_mode: AddressingMode::NoneAddressing,
@@ -1045,6 +1194,7 @@ const OPCODE_F8: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_F9: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xF9,
+    mnemonic: "SBC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_Y,
@@ -1052,6 +1202,7 @@ const OPCODE_F9: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_FD: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xFD,
+    mnemonic: "SBC",
     bytes: 3,
     base_cycles: 4,
     addressing_mode: AddressingMode::Absolute_X,
@@ -1059,164 +1210,171 @@ const OPCODE_FD: OpcodeMetadata = OpcodeMetadata {
 };
 const OPCODE_FE: OpcodeMetadata = OpcodeMetadata {
     opcode: 0xFE,
+    mnemonic: "INC",
     bytes: 3,
     base_cycles: 7,
     addressing_mode: AddressingMode::Absolute_X,
     increase_on_page_cross: false,
 };
 pub fn get_opcode_metadat_from_opcode(opcode: u8) -> OpcodeMetadata {
+    try_get_opcode_metadata(opcode)
+        .unwrap_or_else(|| panic!("Received opcode {opcode} which is not supported currently"))
+}
+
+pub fn try_get_opcode_metadata(opcode: u8) -> Option<OpcodeMetadata> {
+    // None for the undocumented opcodes, which aren't implemented
     match opcode {
-        0x00 => OPCODE_00,
-        0x01 => OPCODE_01,
-        0x05 => OPCODE_05,
-        0x06 => OPCODE_06,
-        0x08 => OPCODE_08,
-        0x09 => OPCODE_09,
-        0x0A => OPCODE_0A,
-        0x0D => OPCODE_0D,
-        0x0E => OPCODE_0E,
-        0x10 => OPCODE_10,
-        0x11 => OPCODE_11,
-        0x15 => OPCODE_15,
-        0x16 => OPCODE_16,
-        0x18 => OPCODE_18,
-        0x19 => OPCODE_19,
-        0x1D => OPCODE_1D,
-        0x1E => OPCODE_1E,
-        0x20 => OPCODE_20,
-        0x21 => OPCODE_21,
-        0x24 => OPCODE_24,
-        0x25 => OPCODE_25,
-        0x26 => OPCODE_26,
-        0x28 => OPCODE_28,
-        0x29 => OPCODE_29,
-        0x2A => OPCODE_2A,
-        0x2C => OPCODE_2C,
-        0x2D => OPCODE_2D,
-        0x2E => OPCODE_2E,
-        0x30 => OPCODE_30,
-        0x31 => OPCODE_31,
-        0x35 => OPCODE_35,
-        0x36 => OPCODE_36,
-        0x38 => OPCODE_38,
-        0x39 => OPCODE_39,
-        0x3D => OPCODE_3D,
-        0x3E => OPCODE_3E,
-        0x40 => OPCODE_40,
-        0x41 => OPCODE_41,
-        0x45 => OPCODE_45,
-        0x46 => OPCODE_46,
-        0x48 => OPCODE_48,
-        0x49 => OPCODE_49,
-        0x4A => OPCODE_4A,
-        0x4C => OPCODE_4C,
-        0x4D => OPCODE_4D,
-        0x4E => OPCODE_4E,
-        0x50 => OPCODE_50,
-        0x51 => OPCODE_51,
-        0x55 => OPCODE_55,
-        0x56 => OPCODE_56,
-        0x58 => OPCODE_58,
-        0x59 => OPCODE_59,
-        0x5D => OPCODE_5D,
-        0x5E => OPCODE_5E,
-        0x60 => OPCODE_60,
-        0x61 => OPCODE_61,
-        0x65 => OPCODE_65,
-        0x66 => OPCODE_66,
-        0x68 => OPCODE_68,
-        0x69 => OPCODE_69,
-        0x6A => OPCODE_6A,
-        0x6C => OPCODE_6C,
-        0x6D => OPCODE_6D,
-        0x6E => OPCODE_6E,
-        0x70 => OPCODE_70,
-        0x71 => OPCODE_71,
-        0x75 => OPCODE_75,
-        0x76 => OPCODE_76,
-        0x78 => OPCODE_78,
-        0x79 => OPCODE_79,
-        0x7D => OPCODE_7D,
-        0x7E => OPCODE_7E,
-        0x81 => OPCODE_81,
-        0x84 => OPCODE_84,
-        0x85 => OPCODE_85,
-        0x86 => OPCODE_86,
-        0x88 => OPCODE_88,
-        0x8A => OPCODE_8A,
-        0x8C => OPCODE_8C,
-        0x8D => OPCODE_8D,
-        0x8E => OPCODE_8E,
-        0x90 => OPCODE_90,
-        0x91 => OPCODE_91,
-        0x94 => OPCODE_94,
-        0x95 => OPCODE_95,
-        0x96 => OPCODE_96,
-        0x98 => OPCODE_98,
-        0x99 => OPCODE_99,
-        0x9A => OPCODE_9A,
-        0x9D => OPCODE_9D,
-        0xA0 => OPCODE_A0,
-        0xA1 => OPCODE_A1,
-        0xA2 => OPCODE_A2,
-        0xA4 => OPCODE_A4,
-        0xA5 => OPCODE_A5,
-        0xA6 => OPCODE_A6,
-        0xA8 => OPCODE_A8,
-        0xA9 => OPCODE_A9,
-        0xAA => OPCODE_AA,
-        0xAC => OPCODE_AC,
-        0xAD => OPCODE_AD,
-        0xAE => OPCODE_AE,
-        0xB0 => OPCODE_B0,
-        0xB1 => OPCODE_B1,
-        0xB4 => OPCODE_B4,
-        0xB5 => OPCODE_B5,
-        0xB6 => OPCODE_B6,
-        0xB8 => OPCODE_B8,
-        0xB9 => OPCODE_B9,
-        0xBA => OPCODE_BA,
-        0xBC => OPCODE_BC,
-        0xBD => OPCODE_BD,
-        0xBE => OPCODE_BE,
-        0xC0 => OPCODE_C0,
-        0xC1 => OPCODE_C1,
-        0xC4 => OPCODE_C4,
-        0xC5 => OPCODE_C5,
-        0xC6 => OPCODE_C6,
-        0xC8 => OPCODE_C8,
-        0xC9 => OPCODE_C9,
-        0xCA => OPCODE_CA,
-        0xCC => OPCODE_CC,
-        0xCD => OPCODE_CD,
-        0xCE => OPCODE_CE,
-        0xD0 => OPCODE_D0,
-        0xD1 => OPCODE_D1,
-        0xD5 => OPCODE_D5,
-        0xD6 => OPCODE_D6,
-        0xD8 => OPCODE_D8,
-        0xD9 => OPCODE_D9,
-        0xDD => OPCODE_DD,
-        0xDE => OPCODE_DE,
-        0xE0 => OPCODE_E0,
-        0xE1 => OPCODE_E1,
-        0xE4 => OPCODE_E4,
-        0xE5 => OPCODE_E5,
-        0xE6 => OPCODE_E6,
-        0xE8 => OPCODE_E8,
-        0xE9 => OPCODE_E9,
-        0xEA => OPCODE_EA,
-        0xEC => OPCODE_EC,
-        0xED => OPCODE_ED,
-        0xEE => OPCODE_EE,
-        0xF0 => OPCODE_F0,
-        0xF1 => OPCODE_F1,
-        0xF5 => OPCODE_F5,
-        0xF6 => OPCODE_F6,
-        0xF8 => OPCODE_F8,
-        0xF9 => OPCODE_F9,
-        0xFD => OPCODE_FD,
-        0xFE => OPCODE_FE,
-        _ => panic!("Received opcode {opcode} which is not supported currently"),
+        0x00 => Some(OPCODE_00),
+        0x01 => Some(OPCODE_01),
+        0x05 => Some(OPCODE_05),
+        0x06 => Some(OPCODE_06),
+        0x08 => Some(OPCODE_08),
+        0x09 => Some(OPCODE_09),
+        0x0A => Some(OPCODE_0A),
+        0x0D => Some(OPCODE_0D),
+        0x0E => Some(OPCODE_0E),
+        0x10 => Some(OPCODE_10),
+        0x11 => Some(OPCODE_11),
+        0x15 => Some(OPCODE_15),
+        0x16 => Some(OPCODE_16),
+        0x18 => Some(OPCODE_18),
+        0x19 => Some(OPCODE_19),
+        0x1D => Some(OPCODE_1D),
+        0x1E => Some(OPCODE_1E),
+        0x20 => Some(OPCODE_20),
+        0x21 => Some(OPCODE_21),
+        0x24 => Some(OPCODE_24),
+        0x25 => Some(OPCODE_25),
+        0x26 => Some(OPCODE_26),
+        0x28 => Some(OPCODE_28),
+        0x29 => Some(OPCODE_29),
+        0x2A => Some(OPCODE_2A),
+        0x2C => Some(OPCODE_2C),
+        0x2D => Some(OPCODE_2D),
+        0x2E => Some(OPCODE_2E),
+        0x30 => Some(OPCODE_30),
+        0x31 => Some(OPCODE_31),
+        0x35 => Some(OPCODE_35),
+        0x36 => Some(OPCODE_36),
+        0x38 => Some(OPCODE_38),
+        0x39 => Some(OPCODE_39),
+        0x3D => Some(OPCODE_3D),
+        0x3E => Some(OPCODE_3E),
+        0x40 => Some(OPCODE_40),
+        0x41 => Some(OPCODE_41),
+        0x45 => Some(OPCODE_45),
+        0x46 => Some(OPCODE_46),
+        0x48 => Some(OPCODE_48),
+        0x49 => Some(OPCODE_49),
+        0x4A => Some(OPCODE_4A),
+        0x4C => Some(OPCODE_4C),
+        0x4D => Some(OPCODE_4D),
+        0x4E => Some(OPCODE_4E),
+        0x50 => Some(OPCODE_50),
+        0x51 => Some(OPCODE_51),
+        0x55 => Some(OPCODE_55),
+        0x56 => Some(OPCODE_56),
+        0x58 => Some(OPCODE_58),
+        0x59 => Some(OPCODE_59),
+        0x5D => Some(OPCODE_5D),
+        0x5E => Some(OPCODE_5E),
+        0x60 => Some(OPCODE_60),
+        0x61 => Some(OPCODE_61),
+        0x65 => Some(OPCODE_65),
+        0x66 => Some(OPCODE_66),
+        0x68 => Some(OPCODE_68),
+        0x69 => Some(OPCODE_69),
+        0x6A => Some(OPCODE_6A),
+        0x6C => Some(OPCODE_6C),
+        0x6D => Some(OPCODE_6D),
+        0x6E => Some(OPCODE_6E),
+        0x70 => Some(OPCODE_70),
+        0x71 => Some(OPCODE_71),
+        0x75 => Some(OPCODE_75),
+        0x76 => Some(OPCODE_76),
+        0x78 => Some(OPCODE_78),
+        0x79 => Some(OPCODE_79),
+        0x7D => Some(OPCODE_7D),
+        0x7E => Some(OPCODE_7E),
+        0x81 => Some(OPCODE_81),
+        0x84 => Some(OPCODE_84),
+        0x85 => Some(OPCODE_85),
+        0x86 => Some(OPCODE_86),
+        0x88 => Some(OPCODE_88),
+        0x8A => Some(OPCODE_8A),
+        0x8C => Some(OPCODE_8C),
+        0x8D => Some(OPCODE_8D),
+        0x8E => Some(OPCODE_8E),
+        0x90 => Some(OPCODE_90),
+        0x91 => Some(OPCODE_91),
+        0x94 => Some(OPCODE_94),
+        0x95 => Some(OPCODE_95),
+        0x96 => Some(OPCODE_96),
+        0x98 => Some(OPCODE_98),
+        0x99 => Some(OPCODE_99),
+        0x9A => Some(OPCODE_9A),
+        0x9D => Some(OPCODE_9D),
+        0xA0 => Some(OPCODE_A0),
+        0xA1 => Some(OPCODE_A1),
+        0xA2 => Some(OPCODE_A2),
+        0xA4 => Some(OPCODE_A4),
+        0xA5 => Some(OPCODE_A5),
+        0xA6 => Some(OPCODE_A6),
+        0xA8 => Some(OPCODE_A8),
+        0xA9 => Some(OPCODE_A9),
+        0xAA => Some(OPCODE_AA),
+        0xAC => Some(OPCODE_AC),
+        0xAD => Some(OPCODE_AD),
+        0xAE => Some(OPCODE_AE),
+        0xB0 => Some(OPCODE_B0),
+        0xB1 => Some(OPCODE_B1),
+        0xB4 => Some(OPCODE_B4),
+        0xB5 => Some(OPCODE_B5),
+        0xB6 => Some(OPCODE_B6),
+        0xB8 => Some(OPCODE_B8),
+        0xB9 => Some(OPCODE_B9),
+        0xBA => Some(OPCODE_BA),
+        0xBC => Some(OPCODE_BC),
+        0xBD => Some(OPCODE_BD),
+        0xBE => Some(OPCODE_BE),
+        0xC0 => Some(OPCODE_C0),
+        0xC1 => Some(OPCODE_C1),
+        0xC4 => Some(OPCODE_C4),
+        0xC5 => Some(OPCODE_C5),
+        0xC6 => Some(OPCODE_C6),
+        0xC8 => Some(OPCODE_C8),
+        0xC9 => Some(OPCODE_C9),
+        0xCA => Some(OPCODE_CA),
+        0xCC => Some(OPCODE_CC),
+        0xCD => Some(OPCODE_CD),
+        0xCE => Some(OPCODE_CE),
+        0xD0 => Some(OPCODE_D0),
+        0xD1 => Some(OPCODE_D1),
+        0xD5 => Some(OPCODE_D5),
+        0xD6 => Some(OPCODE_D6),
+        0xD8 => Some(OPCODE_D8),
+        0xD9 => Some(OPCODE_D9),
+        0xDD => Some(OPCODE_DD),
+        0xDE => Some(OPCODE_DE),
+        0xE0 => Some(OPCODE_E0),
+        0xE1 => Some(OPCODE_E1),
+        0xE4 => Some(OPCODE_E4),
+        0xE5 => Some(OPCODE_E5),
+        0xE6 => Some(OPCODE_E6),
+        0xE8 => Some(OPCODE_E8),
+        0xE9 => Some(OPCODE_E9),
+        0xEA => Some(OPCODE_EA),
+        0xEC => Some(OPCODE_EC),
+        0xED => Some(OPCODE_ED),
+        0xEE => Some(OPCODE_EE),
+        0xF0 => Some(OPCODE_F0),
+        0xF1 => Some(OPCODE_F1),
+        0xF5 => Some(OPCODE_F5),
+        0xF6 => Some(OPCODE_F6),
+        0xF8 => Some(OPCODE_F8),
+        0xF9 => Some(OPCODE_F9),
+        0xFD => Some(OPCODE_FD),
+        0xFE => Some(OPCODE_FE),
+        _ => None,
     }
 }
@@ -0,0 +1,151 @@
+use crate::bus::memory::Mem;
+use crate::cpu::flat_bus::FlatBus;
+use crate::cpu::interrupt_lines::InterruptLines;
+use crate::cpu::CPU;
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use std::panic::{self, AssertUnwindSafe};
+
+// the per opcode tests of https://github.com/SingleStepTests/65x02 (the nes6502 set, without decimal mode).
+// every test is a single instruction: the registers and the memory before and after it, and what the bus did on every cycle
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize)]
+pub struct SingleStepTest {
+    pub name: String,
+    pub initial: SingleStepState,
+    #[serde(rename = "final")]
+    pub final_state: SingleStepState,
+    pub cycles: Vec<(u16, u8, BusActivity)>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize)]
+pub struct SingleStepState {
+    pub pc: u16,
+    pub s: u8,
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub p: u8,
+    pub ram: Vec<(u16, u8)>, // only the addresses the instruction touches
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BusActivity {
+    Read,
+    Write,
+}
+
+// the flat bus, writing down every access the cpu makes, in the format of the tests
+struct RecordingBus {
+    bus: FlatBus,
+    activity: Vec<(u16, u8, BusActivity)>,
+}
+
+impl Mem for RecordingBus {
+    fn write_memory(&mut self, addr: u16, data: u8) {
+        self.activity.push((addr, data, BusActivity::Write));
+        self.bus.write_memory(addr, data);
+    }
+
+    fn read_memory_2_bytes(&mut self, addr: u16) -> u16 {
+        let low = self.read_memory(addr) as u16;
+        let high = self.read_memory(addr.wrapping_add(1)) as u16;
+        (high << 8) | low
+    }
+
+    fn read_memory(&mut self, addr: u16) -> u8 {
+        let data = self.bus.read_memory(addr);
+        self.activity.push((addr, data, BusActivity::Read));
+        data
+    }
+}
+
+// the interrupt lines are never on in the tests
+impl InterruptLines for RecordingBus {}
+
+pub fn run_single_step_test(test: &SingleStepTest) -> Result<()> {
+    // runs the instruction on a flat bus, and fails with everything that came out different: the registers,
+    // the memory, the number of cycles and what the bus did on each of them. the cpu does all of an instruction's
+    // accesses when it starts it, so only their order is checked, not the cycle they happen on
+    let mut bus = RecordingBus {
+        bus: FlatBus::new(),
+        activity: Vec::new(),
+    };
+    for (addr, value) in &test.initial.ram {
+        bus.bus.write_memory(*addr, *value);
+    }
+    let mut cpu = CPU::new(&mut bus);
+    let initial = &test.initial;
+    cpu.program_counter = initial.pc;
+    cpu.stack_pointer = initial.s;
+    cpu.register_a = initial.a;
+    cpu.register_x = initial.x;
+    cpu.register_y = initial.y;
+    cpu.status = initial.p;
+
+    // unsupported opcodes panic
+    let run = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut cycles = 0;
+        loop {
+            cpu.run_one_cycle();
+            cycles += 1;
+            if cpu.cpu_idle_cycles == 0 {
+                return cycles;
+            }
+        }
+    }));
+    let Ok(cycles) = run else {
+        bail!("{}: the cpu panicked", test.name);
+    };
+
+    let expected = &test.final_state;
+    let mut differences = Vec::new();
+    let registers = [
+        ("pc", cpu.program_counter, expected.pc),
+        ("s", cpu.stack_pointer as u16, expected.s as u16),
+        ("a", cpu.register_a as u16, expected.a as u16),
+        ("x", cpu.register_x as u16, expected.x as u16),
+        ("y", cpu.register_y as u16, expected.y as u16),
+        ("p", cpu.status as u16, expected.p as u16),
+    ];
+    for (name, actual, expected) in registers {
+        if actual != expected {
+            differences.push(format!("{name} is {actual:#x} instead of {expected:#x}"));
+        }
+    }
+    for (addr, expected) in &expected.ram {
+        let actual = bus.bus.memory[*addr as usize];
+        if actual != *expected {
+            differences.push(format!(
+                "memory at {addr:#06x} is {actual:#04x} instead of {expected:#04x}"
+            ));
+        }
+    }
+    if cycles != test.cycles.len() {
+        differences.push(format!(
+            "took {cycles} cycles instead of {}",
+            test.cycles.len()
+        ));
+    }
+    // the first access that is different, the rest usually just follow it
+    let show = |access: Option<&(u16, u8, BusActivity)>| match access {
+        Some((addr, value, activity)) => format!("{activity:?} {value:#04x} at {addr:#06x}"),
+        None => "nothing".to_string(),
+    };
+    let (actual, expected) = (&bus.activity, &test.cycles);
+    if let Some(i) =
+        (0..actual.len().max(expected.len())).find(|&i| actual.get(i) != expected.get(i))
+    {
+        differences.push(format!(
+            "bus activity on cycle {} is {} instead of {}",
+            i + 1,
+            show(actual.get(i)),
+            show(expected.get(i))
+        ));
+    }
+
+    if !differences.is_empty() {
+        bail!("{}: {}", test.name, differences.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use crate::bus::memory::Mem;
+use crate::bus::Bus;
+use crate::cpu::interrupt_lines::{Interrupt, InterruptLines};
+use crate::cpu::CPU;
+
+const PPU_DATA: u16 = 0x2007;
+const PPU_REGISTERS_END: u16 = 0x3FFF;
+const PPU_ADDRESS_MASK: u16 = 0x3FFF;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AddressSpace {
+    Cpu,
+    Ppu, // what the cpu reads and writes through $2007
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct MemoryAccess {
+    pub space: AddressSpace,
+    pub kind: AccessKind,
+    pub address: u16,
+    pub value: u8,
+}
+
+// what a debugger gets to see while the nes runs. the nes only calls them from step_cycle_with_hooks, so
+// step_cycle (and a game without a debugger) doesn't pay anything for them
+pub trait DebugHooks {
+    fn memory_access(&mut self, _access: MemoryAccess) {}
+
+    fn interrupt(&mut self, _interrupt: Interrupt) {}
+
+    // between two instructions, the cpu is about to run the one at its pc (or to take an interrupt)
+    fn instruction_boundary(&mut self, _cpu: &CPU, _bus: &Bus) {}
+}
+
+// the nes bus, telling the hooks about everything the cpu does with it
+pub struct DebugBus<'a, H: DebugHooks> {
+    bus: &'a mut Bus,
+    hooks: &'a mut H,
+}
+
+impl<'a, H: DebugHooks> DebugBus<'a, H> {
+    pub fn new(bus: &'a mut Bus, hooks: &'a mut H) -> Self {
+        Self { bus, hooks }
+    }
+
+    pub fn bus(&self) -> &Bus {
+        self.bus
+    }
+
+    fn ppu_address(&self, address: u16) -> Option<u16> {
+        // the ppu address that an access to $2007 (or one of its mirrors) goes to
+        let is_ppu_data = address <= PPU_REGISTERS_END && address & 0x2007 == PPU_DATA;
+        is_ppu_data.then(|| {
+            self.bus.ppu_registers.address_register.get_address_as_u16() & PPU_ADDRESS_MASK
+        })
+    }
+
+    fn report(&mut self, kind: AccessKind, address: u16, ppu_address: Option<u16>, value: u8) {
+        self.hooks.memory_access(MemoryAccess {
+            space: AddressSpace::Cpu,
+            kind,
+            address,
+            value,
+        });
+        if let Some(address) = ppu_address {
+            self.hooks.memory_access(MemoryAccess {
+                space: AddressSpace::Ppu,
+                kind,
+                address,
+                value,
+            });
+        }
+    }
+}
+
+impl<H: DebugHooks> Mem for DebugBus<'_, H> {
+    fn write_memory(&mut self, addr: u16, data: u8) {
+        let ppu_address = self.ppu_address(addr);
+        self.bus.write_memory(addr, data);
+        self.report(AccessKind::Write, addr, ppu_address, data);
+    }
+
+    fn read_memory_2_bytes(&mut self, addr: u16) -> u16 {
+        let low = self.read_memory(addr) as u16;
+        let high = self.read_memory(addr.wrapping_add(1)) as u16;
+        (high << 8) | low
+    }
+
+    fn read_memory(&mut self, addr: u16) -> u8 {
+        let ppu_address = self.ppu_address(addr);
+        let value = self.bus.read_memory(addr);
+        self.report(AccessKind::Read, addr, ppu_address, value);
+        value
+    }
+}
+
+impl<H: DebugHooks> InterruptLines for DebugBus<'_, H> {
+    fn take_nmi(&mut self) -> bool {
+        self.bus.take_nmi()
+    }
+
+    fn irq_pending(&self) -> bool {
+        InterruptLines::irq_pending(self.bus)
+    }
+
+    fn take_stall_cycles(&mut self) -> u8 {
+        self.bus.take_stall_cycles()
+    }
+
+    fn dma_cycle(&mut self) -> bool {
+        self.bus.dma_cycle()
+    }
+
+    fn tick(&mut self) {
+        self.bus.tick()
+    }
+
+    fn interrupt_started(&mut self, interrupt: Interrupt) {
+        self.hooks.interrupt(interrupt);
+    }
+}
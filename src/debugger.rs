@@ -0,0 +1,300 @@
+#[cfg(test)]
+mod debugger_tests;
+
+use crate::breakpoints::{Breakpoints, Hit};
+use crate::bus::Bus;
+use crate::cpu::interrupt_lines::Interrupt;
+use crate::cpu::CPU;
+use crate::debug_hooks::{DebugHooks, MemoryAccess};
+use crate::disasm::Instruction;
+use crate::nes::Nes;
+use crate::symbols::{Labels, Symbols};
+
+const JSR: u8 = 0x20;
+const RTS: u8 = 0x60;
+const RTI: u8 = 0x40;
+const STACK_PAGE: u16 = 0x100;
+const MEMORY_ROW_BYTES: u16 = 16;
+const FLAG_NAMES: [char; 8] = ['N', 'V', '-', 'B', 'D', 'I', 'Z', 'C'];
+
+// when the cpu gives the control back to the debugger
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum StopAt {
+    NextInstruction,
+    // step over: the jsr returned to the next instruction, with the stack of the caller
+    Return { address: u16, stack_pointer: u8 },
+    // step out: a rts or rti took the stack above the current function
+    StackAbove(u8),
+    Address(u16),
+}
+
+// the part of a debugger that doesn't care about the screen: stepping, the breakpoints, and the text of the
+// views. while it isn't in control the nes runs with it as its hooks (Nes::step_cycle_with_hooks)
+pub struct Debugger {
+    in_control: bool,
+    stop_at: Option<StopAt>,
+    pub breakpoints: Breakpoints,
+    pub symbols: Symbols,
+    last_hit: Option<Hit>, // why the debugger took the control, if it was a breakpoint
+    last_opcode: u8,       // the instruction that ran last, step out waits for a rts or rti
+    cursor: u16,
+    view_start: u16, // the first instruction in the disassembly view
+    pub memory_address: u16,
+}
+
+impl Debugger {
+    pub fn new(nes: &Nes) -> Self {
+        // starts in control, with the cpu stopped at the next instruction
+        let pc = nes.cpu().program_counter;
+        Self {
+            in_control: true,
+            stop_at: None,
+            breakpoints: Breakpoints::new(),
+            symbols: Symbols::new(),
+            last_hit: None,
+            last_opcode: 0,
+            cursor: pc,
+            view_start: pc,
+            memory_address: 0,
+        }
+    }
+
+    pub fn in_control(&self) -> bool {
+        self.in_control
+    }
+
+    pub fn last_hit(&self) -> Option<Hit> {
+        self.last_hit
+    }
+
+    pub fn cursor(&self) -> u16 {
+        self.cursor
+    }
+
+    pub fn run(&mut self) {
+        self.resume(None);
+    }
+
+    pub fn step(&mut self) {
+        self.resume(Some(StopAt::NextInstruction));
+    }
+
+    pub fn step_over(&mut self, nes: &Nes) {
+        let cpu = nes.cpu();
+        let instruction = decode(nes, cpu.program_counter);
+        if instruction.opcode == JSR {
+            self.resume(Some(StopAt::Return {
+                address: instruction.next_address(),
+                stack_pointer: cpu.stack_pointer,
+            }));
+        } else {
+            self.step();
+        }
+    }
+
+    pub fn step_out(&mut self, nes: &Nes) {
+        // the instruction we are stopped on runs first, and it may already be the rts.
+        // last_opcode is only kept up to date while running
+        let cpu = nes.cpu();
+        self.last_opcode = nes.bus().peek_memory(cpu.program_counter);
+        self.resume(Some(StopAt::StackAbove(cpu.stack_pointer)));
+    }
+
+    pub fn run_to_cursor(&mut self) {
+        self.resume(Some(StopAt::Address(self.cursor)));
+    }
+
+    fn resume(&mut self, stop_at: Option<StopAt>) {
+        self.in_control = false;
+        self.stop_at = stop_at;
+        self.last_hit = None;
+    }
+
+    fn take_control(&mut self, pc: u16) {
+        self.in_control = true;
+        self.stop_at = None;
+        self.go_to(pc);
+    }
+
+    pub fn labels<'a>(&'a self, nes: &'a Nes) -> impl Labels + 'a {
+        // the symbols with the banks that are mapped in now
+        self.symbols
+            .mapped(|address| nes.bus().prg_rom_offset(address))
+    }
+
+    pub fn go_to(&mut self, address: u16) {
+        self.cursor = address;
+        self.view_start = address;
+    }
+
+    pub fn cursor_down(&mut self, nes: &Nes, rows: usize) {
+        self.cursor = decode(nes, self.cursor).next_address();
+        let view = self.view_addresses(nes, rows);
+        if !view.contains(&self.cursor) {
+            self.view_start = decode(nes, self.view_start).next_address();
+        }
+    }
+
+    pub fn cursor_up(&mut self, nes: &Nes, rows: usize) {
+        // the view knows where the instructions start, above it we have to guess
+        let view = self.view_addresses(nes, rows);
+        match view.iter().position(|address| *address == self.cursor) {
+            Some(i) if i > 0 => self.cursor = view[i - 1],
+            _ => {
+                self.cursor = previous_instruction(nes, self.cursor);
+                self.view_start = self.cursor;
+            }
+        }
+    }
+
+    fn view_addresses(&self, nes: &Nes, rows: usize) -> Vec<u16> {
+        let mut address = self.view_start;
+        (0..rows)
+            .map(|_| {
+                let this = address;
+                address = decode(nes, address).next_address();
+                this
+            })
+            .collect()
+    }
+
+    pub fn disassembly_view(&self, nes: &Nes, rows: usize) -> Vec<String> {
+        // > marks the next instruction of the cpu, * the cursor and o the breakpoints. the labels go at the end,
+        // so that every row is an instruction
+        let pc = nes.cpu().program_counter;
+        let labels = self.labels(nes);
+        self.view_addresses(nes, rows)
+            .into_iter()
+            .map(|address| {
+                let line = format!(
+                    "{}{}{} {}",
+                    if address == pc { '>' } else { ' ' },
+                    if address == self.cursor { '*' } else { ' ' },
+                    if self.breakpoints.has_address(address) {
+                        'o'
+                    } else {
+                        ' '
+                    },
+                    decode(nes, address).listing_line(Some(&labels))
+                );
+                match labels.label(address) {
+                    Some(label) => format!("{line:<36}{label}:"),
+                    None => line,
+                }
+            })
+            .collect()
+    }
+
+    pub fn memory_view(&self, nes: &Nes, rows: usize) -> Vec<String> {
+        // the labels in a row go after it, with the byte they name
+        let start = self.memory_address & !(MEMORY_ROW_BYTES - 1);
+        let labels = self.labels(nes);
+        (0..rows as u16)
+            .map(|row| {
+                let row_address = start.wrapping_add(row * MEMORY_ROW_BYTES);
+                let addresses = (0..MEMORY_ROW_BYTES).map(|i| row_address.wrapping_add(i));
+                let bytes: Vec<String> = addresses
+                    .clone()
+                    .map(|address| format!("{:02X}", nes.bus().peek_memory(address)))
+                    .collect();
+                let named: Vec<String> = addresses
+                    .filter_map(|address| {
+                        let label = labels.label(address)?;
+                        Some(format!("{label}={:02X}", nes.bus().peek_memory(address)))
+                    })
+                    .collect();
+                format!(
+                    "{row_address:04X}  {}  {}",
+                    bytes.join(" "),
+                    named.join(" ")
+                )
+                .trim_end()
+                .to_string()
+            })
+            .collect()
+    }
+
+    pub fn scroll_memory(&mut self, rows: i32) {
+        let bytes = rows * MEMORY_ROW_BYTES as i32;
+        self.memory_address = self.memory_address.wrapping_add_signed(bytes as i16);
+    }
+}
+
+impl DebugHooks for Debugger {
+    fn memory_access(&mut self, access: MemoryAccess) {
+        self.breakpoints.memory_access(access);
+    }
+
+    fn interrupt(&mut self, interrupt: Interrupt) {
+        self.breakpoints.interrupt(interrupt);
+    }
+
+    fn instruction_boundary(&mut self, cpu: &CPU, bus: &Bus) {
+        if self.in_control {
+            return;
+        }
+        self.breakpoints.instruction_boundary(cpu, bus);
+        let hit = self.breakpoints.take_hit();
+        let pc = cpu.program_counter;
+        let stop = hit.is_some()
+            || match self.stop_at {
+                None => false,
+                Some(StopAt::NextInstruction) => true,
+                Some(StopAt::Return {
+                    address,
+                    stack_pointer,
+                }) => pc == address && cpu.stack_pointer >= stack_pointer,
+                Some(StopAt::StackAbove(stack_pointer)) => {
+                    matches!(self.last_opcode, RTS | RTI) && cpu.stack_pointer > stack_pointer
+                }
+                Some(StopAt::Address(address)) => pc == address,
+            };
+        if stop {
+            self.take_control(pc);
+            self.last_hit = hit;
+        } else {
+            self.last_opcode = bus.peek_memory(pc);
+        }
+    }
+}
+
+fn decode(nes: &Nes, address: u16) -> Instruction {
+    Instruction::decode(address, |address| nes.bus().peek_memory(address))
+}
+
+fn previous_instruction(nes: &Nes, address: u16) -> u16 {
+    // the code can't be read backwards for sure, so we take the longest instruction that ends right here
+    (1..=3)
+        .rev()
+        .map(|length| address.wrapping_sub(length))
+        .find(|start| decode(nes, *start).next_address() == address)
+        .unwrap_or(address.wrapping_sub(1))
+}
+
+pub fn registers_view(cpu: &CPU) -> String {
+    format!(
+        "PC:{:04X} A:{:02X} X:{:02X} Y:{:02X} SP:{:02X}",
+        cpu.program_counter, cpu.register_a, cpu.register_x, cpu.register_y, cpu.stack_pointer
+    )
+}
+
+pub fn flags_view(status: u8) -> [String; 2] {
+    // the names, and under them 1 for the flags that are set
+    let names: Vec<String> = FLAG_NAMES.iter().map(|name| name.to_string()).collect();
+    let bits: Vec<String> = (0..8)
+        .map(|i| (status >> (7 - i) & 1).to_string())
+        .collect();
+    [names.join(" "), bits.join(" ")]
+}
+
+pub fn stack_view(nes: &Nes, rows: usize) -> Vec<String> {
+    // the bytes on the stack, the last one pushed first
+    let top = nes.cpu().stack_pointer as u16 + 1;
+    (top..=0xFF)
+        .take(rows)
+        .map(|offset| {
+            let address = STACK_PAGE + offset;
+            format!("{address:04X}  {:02X}", nes.bus().peek_memory(address))
+        })
+        .collect()
+}
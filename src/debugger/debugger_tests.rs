@@ -0,0 +1,154 @@
+use crate::breakpoints::{BreakOn, Breakpoint};
+use crate::debugger::{flags_view, registers_view, stack_view, Debugger};
+use crate::nes::Nes;
+use crate::symbols::{Labels, Symbols};
+
+const NESTEST: &[u8] = include_bytes!("../../tests/nestest.nes");
+
+fn nestest() -> Nes {
+    // like the nestest log, from 0xc000
+    let mut nes = Nes::from_rom(NESTEST);
+    nes.cpu_mut().program_counter = 0xC000;
+    // the reset sequence
+    nes.step_instruction();
+    nes
+}
+
+fn run_until_stopped(nes: &mut Nes, debugger: &mut Debugger) {
+    for _ in 0..100_000 {
+        nes.step_cycle_with_hooks(debugger);
+        if debugger.in_control() {
+            return;
+        }
+    }
+    panic!("the debugger didn't stop");
+}
+
+#[test]
+fn test_stepping() {
+    let mut nes = nestest();
+    let mut debugger = Debugger::new(&nes);
+    assert!(debugger.in_control());
+
+    debugger.step();
+    assert!(!debugger.in_control());
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC5F5);
+    assert_eq!(debugger.cursor(), 0xC5F5);
+
+    // C5FD  20 2D C7  JSR $C72D
+    debugger.go_to(0xC5FD);
+    debugger.run_to_cursor();
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC5FD);
+
+    debugger.step_over(&nes);
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC600);
+    assert_eq!(nes.cpu().stack_pointer, 0xFD);
+
+    // C600  20 DB C7  JSR $C7DB, which returns from another place (C884)
+    debugger.step();
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC7DB);
+    assert_eq!(stack_view(&nes, 2), ["01FC  02", "01FD  C6"]);
+    assert_eq!(stack_view(&nes, 8).len(), 4);
+    debugger.step();
+    run_until_stopped(&mut nes, &mut debugger);
+    debugger.step_out(&nes);
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC603);
+    assert_eq!(nes.cpu().stack_pointer, 0xFD);
+
+    debugger.run();
+    for _ in 0..1000 {
+        nes.step_cycle_with_hooks(&mut debugger);
+        assert!(!debugger.in_control());
+    }
+    // breaking in is a step while running
+    debugger.step();
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().cpu_idle_cycles, 0);
+}
+
+#[test]
+fn test_step_out_of_a_rts() {
+    let mut nes = nestest();
+    let mut debugger = Debugger::new(&nes);
+    // C600  20 DB C7  JSR $C7DB returns with C884  60  RTS, which the cpu reaches with a jmp
+    debugger.go_to(0xC884);
+    debugger.run_to_cursor();
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().stack_pointer, 0xFB);
+    // the rts is the last instruction of the function, step out stops right after it
+    debugger.step_out(&nes);
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC603);
+    assert_eq!(nes.cpu().stack_pointer, 0xFD);
+}
+
+#[test]
+fn test_views() {
+    let mut nes = nestest();
+    let mut debugger = Debugger::new(&nes);
+    assert_eq!(
+        debugger.disassembly_view(&nes, 2),
+        [">*  C000  4C F5 C5  JMP $C5F5", "    C003  60        RTS"]
+    );
+    debugger.cursor_down(&nes, 2);
+    debugger.cursor_down(&nes, 2);
+    assert_eq!(debugger.cursor(), 0xC004);
+    let view = debugger.disassembly_view(&nes, 2);
+    assert!(view[0].starts_with("    C003"), "{view:?}");
+    assert!(view[1].starts_with(" *  C004"), "{view:?}");
+    debugger.cursor_up(&nes, 2);
+    debugger.cursor_up(&nes, 2);
+    assert_eq!(debugger.cursor(), 0xC000);
+    assert!(debugger.disassembly_view(&nes, 1)[0].starts_with(">*  C000"));
+
+    assert_eq!(registers_view(nes.cpu()), "PC:C000 A:00 X:00 Y:00 SP:FD");
+    assert_eq!(flags_view(0x24), ["N V - B D I Z C", "0 0 1 0 0 1 0 0"]);
+
+    nes.ram_mut()[0x0312] = 0xAB;
+    debugger.memory_address = 0x0312;
+    assert_eq!(
+        debugger.memory_view(&nes, 1),
+        ["0310  00 00 AB 00 00 00 00 00 00 00 00 00 00 00 00 00"]
+    );
+    debugger.scroll_memory(-2);
+    assert!(debugger.memory_view(&nes, 1)[0].starts_with("02F0"));
+}
+
+#[test]
+fn test_symbols() {
+    // nestest has a single 16KB bank, the cpu sees it at C000 too
+    let mut nes = nestest();
+    let mut debugger = Debugger::new(&nes);
+    debugger.symbols = Symbols::from_mlb("P:0000:reset\nP:05F5:main\nR:0312:lives\n").unwrap();
+    assert_eq!(
+        debugger.disassembly_view(&nes, 2),
+        [
+            format!("{:<36}reset:", ">*  C000  4C F5 C5  JMP main"),
+            "    C003  60        RTS".to_string()
+        ]
+    );
+    nes.ram_mut()[0x0312] = 0xAB;
+    debugger.memory_address = 0x0312;
+    assert_eq!(
+        debugger.memory_view(&nes, 1),
+        ["0310  00 00 AB 00 00 00 00 00 00 00 00 00 00 00 00 00  lives=AB"]
+    );
+
+    let breakpoint = {
+        let labels = debugger.labels(&nes);
+        assert!(Breakpoint::parse("read nowhere", Some(&labels)).is_err());
+        Breakpoint::parse("main if [lives] == $AB", Some(&labels)).unwrap()
+    };
+    assert_eq!(breakpoint.on, BreakOn::Address(0xC5F5));
+    assert!(Breakpoint::parse("main", None).is_err());
+    debugger.breakpoints.add(breakpoint);
+    debugger.run();
+    run_until_stopped(&mut nes, &mut debugger);
+    assert_eq!(nes.cpu().program_counter, 0xC5F5);
+    assert_eq!(debugger.labels(&nes).label(0xC5F5), Some("main"));
+}
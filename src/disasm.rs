@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod disasm_tests;
+
+use crate::cpu::mem_utils::AddressingMode;
+use crate::cpu::opcodes::try_get_opcode_metadata;
+use crate::symbols::Labels;
+
+// what a byte that isn't a (supported) opcode shows as
+pub const UNKNOWN_OPCODE: &str = ".db";
+
+// one decoded instruction. decoding only reads the memory, so it can be done on a rom as well as on a running nes
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Instruction {
+    pub address: u16,
+    pub opcode: u8,
+    pub operand: u16, // the bytes after the opcode (little endian), 0 if there are none
+    pub length: u16,  // in bytes, with the opcode
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+}
+
+impl Instruction {
+    pub fn decode(address: u16, mut peek: impl FnMut(u16) -> u8) -> Self {
+        let opcode = peek(address);
+        let Some(metadata) = try_get_opcode_metadata(opcode) else {
+            return Self {
+                address,
+                opcode,
+                operand: 0,
+                length: 1,
+                mnemonic: UNKNOWN_OPCODE,
+                mode: AddressingMode::NoneAddressing,
+            };
+        };
+        let operand = match metadata.bytes {
+            2 => peek(address.wrapping_add(1)) as u16,
+            3 => u16::from_le_bytes([peek(address.wrapping_add(1)), peek(address.wrapping_add(2))]),
+            _ => 0,
+        };
+        Self {
+            address,
+            opcode,
+            operand,
+            length: metadata.bytes,
+            mnemonic: metadata.mnemonic,
+            mode: metadata.addressing_mode,
+        }
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        let [low, high] = self.operand.to_le_bytes();
+        [self.opcode, low, high][..self.length as usize].to_vec()
+    }
+
+    pub fn next_address(&self) -> u16 {
+        self.address.wrapping_add(self.length)
+    }
+
+    pub fn is_jump(&self) -> bool {
+        // the operand is where the code goes, not where the data is
+        matches!(self.mnemonic, "JMP" | "JSR")
+    }
+
+    pub fn target(&self) -> Option<u16> {
+        // the address the operand names (for branches it is the destination), if it names one
+        match self.mode {
+            AddressingMode::Relative => Some(
+                self.next_address()
+                    .wrapping_add(self.operand as u8 as i8 as u16),
+            ),
+            AddressingMode::Immediate
+            | AddressingMode::Accumulator
+            | AddressingMode::NoneAddressing => None,
+            _ => Some(self.operand),
+        }
+    }
+
+    pub fn text(&self, symbols: Option<&dyn Labels>) -> String {
+        // like an assembler would write it, with the label instead of the address when there is one
+        let name = |digits: usize| {
+            let address = self.target().unwrap_or_default();
+            match symbols.and_then(|symbols| symbols.label(address)) {
+                Some(label) => label.to_string(),
+                None => format!("${address:0digits$X}"),
+            }
+        };
+        let operand = match self.mode {
+            _ if self.mnemonic == UNKNOWN_OPCODE => format!("${:02X}", self.opcode),
+            AddressingMode::Immediate => format!("#${:02X}", self.operand),
+            AddressingMode::ZeroPage => name(2),
+            AddressingMode::ZeroPage_X => format!("{},X", name(2)),
+            AddressingMode::ZeroPage_Y => format!("{},Y", name(2)),
+            AddressingMode::Absolute | AddressingMode::Relative => name(4),
+            AddressingMode::Absolute_X => format!("{},X", name(4)),
+            AddressingMode::Absolute_Y => format!("{},Y", name(4)),
+            AddressingMode::Indirect => format!("({})", name(4)),
+            AddressingMode::Indirect_X => format!("({},X)", name(2)),
+            AddressingMode::Indirect_Y => format!("({}),Y", name(2)),
+            AddressingMode::Accumulator => "A".to_string(),
+            AddressingMode::NoneAddressing => String::new(),
+        };
+        if operand.is_empty() {
+            self.mnemonic.to_string()
+        } else {
+            format!("{} {operand}", self.mnemonic)
+        }
+    }
+
+    pub fn effective_address(&self, x: u8, y: u8, mut peek: impl FnMut(u16) -> u8) -> Option<u16> {
+        // the address the instruction will use with these registers (for jumps, where it goes).
+        // the pointers wrap around the zero page, and the indirect jump keeps the pointer in its page, like the cpu
+        let zero_page_pointer = |peek: &mut dyn FnMut(u16) -> u8, pointer: u8| {
+            u16::from_le_bytes([peek(pointer as u16), peek(pointer.wrapping_add(1) as u16)])
+        };
+        match self.mode {
+            AddressingMode::ZeroPage | AddressingMode::Absolute => Some(self.operand),
+            AddressingMode::ZeroPage_X => Some((self.operand as u8).wrapping_add(x) as u16),
+            AddressingMode::ZeroPage_Y => Some((self.operand as u8).wrapping_add(y) as u16),
+            AddressingMode::Absolute_X => Some(self.operand.wrapping_add(x as u16)),
+            AddressingMode::Absolute_Y => Some(self.operand.wrapping_add(y as u16)),
+            AddressingMode::Indirect => {
+                let high_address = (self.operand & 0xFF00) | (self.operand.wrapping_add(1) & 0xFF);
+                Some(u16::from_le_bytes([peek(self.operand), peek(high_address)]))
+            }
+            AddressingMode::Indirect_X => Some(zero_page_pointer(
+                &mut peek,
+                (self.operand as u8).wrapping_add(x),
+            )),
+            AddressingMode::Indirect_Y => {
+                Some(zero_page_pointer(&mut peek, self.operand as u8).wrapping_add(y as u16))
+            }
+            AddressingMode::Relative => self.target(),
+            _ => None,
+        }
+    }
+
+    pub fn annotated_text(
+        &self,
+        x: u8,
+        y: u8,
+        mut peek: impl FnMut(u16) -> u8,
+        symbols: Option<&dyn Labels>,
+    ) -> String {
+        // the text with where the operand points and what is there before the instruction runs, like nestest.log
+        let text = self.text(symbols);
+        let Some(address) = self.effective_address(x, y, &mut peek) else {
+            return text;
+        };
+        match self.mode {
+            AddressingMode::Absolute if self.is_jump() => text,
+            AddressingMode::ZeroPage | AddressingMode::Absolute => {
+                format!("{text} = {:02X}", peek(address))
+            }
+            AddressingMode::ZeroPage_X | AddressingMode::ZeroPage_Y => {
+                format!("{text} @ {address:02X} = {:02X}", peek(address))
+            }
+            AddressingMode::Absolute_X | AddressingMode::Absolute_Y => {
+                format!("{text} @ {address:04X} = {:02X}", peek(address))
+            }
+            AddressingMode::Indirect => format!("{text} = {address:04X}"),
+            AddressingMode::Indirect_X => {
+                let pointer = (self.operand as u8).wrapping_add(x);
+                format!(
+                    "{text} @ {pointer:02X} = {address:04X} = {:02X}",
+                    peek(address)
+                )
+            }
+            AddressingMode::Indirect_Y => {
+                let base = address.wrapping_sub(y as u16);
+                format!(
+                    "{text} = {base:04X} @ {address:04X} = {:02X}",
+                    peek(address)
+                )
+            }
+            _ => text,
+        }
+    }
+
+    pub fn listing_line(&self, symbols: Option<&dyn Labels>) -> String {
+        // the address, the bytes and the text, in nestest's columns
+        let bytes: Vec<String> = self
+            .bytes()
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        format!(
+            "{:04X}  {:<8}  {}",
+            self.address,
+            bytes.join(" "),
+            self.text(symbols)
+        )
+    }
+}
+
+pub fn disassemble(start: u16, end: u16, mut peek: impl FnMut(u16) -> u8) -> Vec<Instruction> {
+    // decodes one instruction after the other, from start up to end (including)
+    let mut instructions = Vec::new();
+    let mut address = start as u32;
+    while address <= end as u32 {
+        let instruction = Instruction::decode(address as u16, &mut peek);
+        address += instruction.length as u32;
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+pub fn listing(instructions: &[Instruction], symbols: Option<&dyn Labels>) -> String {
+    // a line for each instruction, and a "label:" line before the ones that have a label
+    let mut text = String::new();
+    for instruction in instructions {
+        if let Some(label) = symbols.and_then(|symbols| symbols.label(instruction.address)) {
+            text.push_str(&format!("{label}:\n"));
+        }
+        text.push_str(&instruction.listing_line(symbols));
+        text.push('\n');
+    }
+    text
+}
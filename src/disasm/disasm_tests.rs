@@ -0,0 +1,105 @@
+use crate::disasm::{disassemble, listing, Instruction};
+use crate::symbols::Symbols;
+
+const NESTEST: &[u8] = include_bytes!("../../tests/nestest.nes");
+const NESTEST_LOG: &str = include_str!("../../tests/nestest_result_good.log");
+
+fn peek_nestest(address: u16) -> u8 {
+    // the single prg bank is at 0x8000 and mirrored at 0xC000
+    NESTEST[16 + (address as usize & 0x3FFF)]
+}
+
+fn memory(bytes: &[(u16, u8)]) -> impl FnMut(u16) -> u8 + '_ {
+    |address| {
+        bytes
+            .iter()
+            .find(|(byte_address, _)| *byte_address == address)
+            .map(|(_, value)| *value)
+            .unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_nestest_log_instructions() {
+    // the log has the bytes and the instruction text of every instruction nestest runs (only the official ones
+    // until line 5003). the part of the text after " = " or " @ " needs the registers and the memory
+    for line in NESTEST_LOG.lines().take(5003) {
+        let address = u16::from_str_radix(&line[..4], 16).unwrap();
+        if address < 0x8000 {
+            // a few instructions that the test writes to the ram
+            continue;
+        }
+        let instruction = Instruction::decode(address, peek_nestest);
+        let text = line[16..48].trim_end();
+        let text = text
+            .split(" @ ")
+            .next()
+            .unwrap()
+            .split(" = ")
+            .next()
+            .unwrap();
+        assert_eq!(instruction.text(None), text, "{line}");
+        assert_eq!(instruction.listing_line(None)[..14], line[..14], "{line}");
+    }
+}
+
+#[test]
+fn test_annotated_text() {
+    // lines from nestest.log, with the memory they show
+    let lda = Instruction::decode(0, memory(&[(0, 0xA1), (1, 0x80)]));
+    let ram = [(0x80, 0x00), (0x81, 0x02), (0x0200, 0x5A)];
+    assert_eq!(
+        lda.annotated_text(0, 0x69, memory(&ram), None),
+        "LDA ($80,X) @ 80 = 0200 = 5A"
+    );
+
+    let lda = Instruction::decode(0, memory(&[(0, 0xB1), (1, 0x89)]));
+    let ram = [(0x89, 0x00), (0x8A, 0x03), (0x0300, 0x89)];
+    assert_eq!(
+        lda.annotated_text(0, 0, memory(&ram), None),
+        "LDA ($89),Y = 0300 @ 0300 = 89"
+    );
+
+    // the pointer of the indirect jump doesn't leave its page
+    let jmp = Instruction::decode(0, memory(&[(0, 0x6C), (1, 0xFF), (2, 0x02)]));
+    let ram = [(0x02FF, 0x00), (0x0200, 0x03), (0x0300, 0x01)];
+    assert_eq!(
+        jmp.annotated_text(0, 0, memory(&ram), None),
+        "JMP ($02FF) = 0300"
+    );
+
+    let lda = Instruction::decode(0, memory(&[(0, 0xB9), (1, 0xFF), (2, 0xFF)]));
+    assert_eq!(
+        lda.annotated_text(0, 0x34, memory(&[(0x33, 0xA3)]), None),
+        "LDA $FFFF,Y @ 0033 = A3"
+    );
+
+    let jsr = Instruction::decode(0, memory(&[(0, 0x20), (1, 0x2D), (2, 0xC7)]));
+    assert_eq!(jsr.annotated_text(0, 0, memory(&[]), None), "JSR $C72D");
+}
+
+#[test]
+fn test_listing_with_labels() {
+    let code = [
+        0xA2, 0x00, // LDX #$00
+        0xBD, 0x00, 0x02, // LDA $0200,X
+        0xD0, 0xF9, // BNE to the start
+        0x02, // not an opcode
+    ];
+    let peek = |address: u16| code[(address - 0xC000) as usize];
+    let instructions = disassemble(0xC000, 0xC007, peek);
+    assert_eq!(instructions.len(), 4);
+    assert_eq!(instructions[2].target(), Some(0xC000));
+
+    let mut symbols = Symbols::new();
+    symbols.insert(0xC000, "loop");
+    symbols.insert(0x0200, "table");
+    assert_eq!(
+        listing(&instructions, Some(&symbols)),
+        "loop:\n\
+         C000  A2 00     LDX #$00\n\
+         C002  BD 00 02  LDA table,X\n\
+         C005  D0 F9     BNE loop\n\
+         C007  02        .db $02\n"
+    );
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod frame_limiter_tests;
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// if we fall behind by more than this (a slow frame, a dragged window), we start counting again instead of rushing
+const MAX_LAG: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Region {
+    #[default]
+    NTSC,
+    PAL,
+}
+
+impl Region {
+    pub fn frame_rate(&self) -> f64 {
+        // see https://www.nesdev.org/wiki/Cycle_reference_chart
+        match self {
+            Region::NTSC => 60.0988,
+            Region::PAL => 50.0070,
+        }
+    }
+}
+
+// the emulation speed settings, as written in the config file
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SpeedConfig {
+    pub region: Region,
+    pub fast_forward_multiplier: f64, // 0 means as fast as possible
+    pub slow_motion_multiplier: f64,
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        Self {
+            region: Region::NTSC,
+            fast_forward_multiplier: 4.0,
+            slow_motion_multiplier: 0.5,
+        }
+    }
+}
+
+// keeps the frames at a steady rate using the clock, so it doesn't matter what the monitor refresh rate is
+#[derive(Debug)]
+pub struct FrameLimiter {
+    frame_rate: f64,
+    speed: Option<f64>, // None means unlimited
+    next_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    pub fn new(region: Region) -> Self {
+        Self {
+            frame_rate: region.frame_rate(),
+            speed: Some(1.0),
+            next_frame: None,
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: Option<f64>) {
+        // a multiplier of the normal speed, None (or 0) for unlimited
+        self.speed = speed.filter(|speed| *speed > 0.0);
+    }
+
+    pub fn speed(&self) -> Option<f64> {
+        self.speed
+    }
+
+    pub fn frame_done(&mut self, now: Instant) -> Duration {
+        // call once every frame, returns how long to wait before starting the next one
+        let Some(speed) = self.speed else {
+            self.next_frame = None;
+            return Duration::ZERO;
+        };
+        let frame_duration = Duration::from_secs_f64(1.0 / (self.frame_rate * speed));
+        let next_frame = match self.next_frame {
+            Some(next_frame) if now <= next_frame + MAX_LAG => next_frame + frame_duration,
+            _ => now + frame_duration,
+        };
+        self.next_frame = Some(next_frame);
+        next_frame.saturating_duration_since(now)
+    }
+
+    pub fn wait_for_next_frame(&mut self) {
+        let wait = self.frame_done(Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::frame_limiter::{FrameLimiter, Region};
+use std::time::{Duration, Instant};
+
+fn ntsc_frame() -> Duration {
+    Duration::from_secs_f64(1.0 / Region::NTSC.frame_rate())
+}
+
+#[test]
+fn test_steady_rate() {
+    let mut limiter = FrameLimiter::new(Region::NTSC);
+    let start = Instant::now();
+    assert_eq!(limiter.frame_done(start), ntsc_frame());
+
+    // a frame that took 5ms waits the rest of the frame, the deadlines don't drift
+    let now = start + ntsc_frame() + Duration::from_millis(5);
+    assert_eq!(
+        limiter.frame_done(now),
+        ntsc_frame() - Duration::from_millis(5)
+    );
+
+    // a slightly late frame is caught up on the next one
+    let now = start + 3 * ntsc_frame() + Duration::from_millis(2);
+    assert_eq!(limiter.frame_done(now), Duration::ZERO);
+    assert_eq!(
+        limiter.frame_done(start + 3 * ntsc_frame() + Duration::from_millis(3)),
+        ntsc_frame() - Duration::from_millis(3)
+    );
+}
+
+#[test]
+fn test_long_stall_resets_the_clock() {
+    let mut limiter = FrameLimiter::new(Region::PAL);
+    let start = Instant::now();
+    limiter.frame_done(start);
+    let now = start + Duration::from_secs(2);
+    let pal_frame = Duration::from_secs_f64(1.0 / Region::PAL.frame_rate());
+    assert_eq!(limiter.frame_done(now), pal_frame);
+}
+
+#[test]
+fn test_speed() {
+    let mut limiter = FrameLimiter::new(Region::NTSC);
+    let start = Instant::now();
+
+    limiter.set_speed(Some(2.0));
+    let wait = limiter.frame_done(start);
+    assert!(wait.abs_diff(ntsc_frame() / 2) < Duration::from_micros(1));
+
+    limiter.set_speed(Some(0.5));
+    let wait = limiter.frame_done(start + wait);
+    assert!(wait.abs_diff(ntsc_frame() * 2) < Duration::from_micros(1));
+
+    limiter.set_speed(Some(0.0));
+    assert_eq!(limiter.speed(), None);
+    assert_eq!(limiter.frame_done(start), Duration::ZERO);
+}
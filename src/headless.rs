@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod headless_tests;
+
+use crate::input::InputProvider;
+use crate::nes::Nes;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+// fingerprints of a run, to tell if something changed since the last one
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct HeadlessReport {
+    pub frames: u64,
+    pub frame_sha256: [u8; 32],         // the rgb pixels of the last frame
+    pub ram_sha256: [u8; 32],           // the cpu ram after the last frame
+    pub audio_sha256: Option<[u8; 32]>, // all the samples (as little endian f32), if there were any
+}
+
+impl fmt::Display for HeadlessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "frames: {}", self.frames)?;
+        writeln!(f, "frame sha256: {}", to_hex(&self.frame_sha256))?;
+        writeln!(f, "ram sha256: {}", to_hex(&self.ram_sha256))?;
+        match &self.audio_sha256 {
+            Some(hash) => write!(f, "audio sha256: {}", to_hex(hash)),
+            None => write!(f, "audio sha256: none"),
+        }
+    }
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+pub fn to_hex(hash: &[u8]) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn run_headless(
+    nes: &mut Nes,
+    frames: u64,
+    mut input: Option<&mut dyn InputProvider>,
+) -> HeadlessReport {
+    // runs the given number of frames as fast as possible, without a screen or a sound card.
+    // without an input provider, no button is pressed
+    let mut audio = Sha256::new();
+    let mut any_audio = false;
+    for _ in 0..frames {
+        if let Some(input) = input.as_deref_mut() {
+            nes.bus_mut().set_controllers(input.next_frame());
+        }
+        nes.run_frame();
+        for sample in nes.bus_mut().apu.take_samples() {
+            audio.update(sample.to_le_bytes());
+            any_audio = true;
+        }
+    }
+    HeadlessReport {
+        frames,
+        frame_sha256: sha256(&nes.frame().screen_state),
+        ram_sha256: sha256(nes.ram()),
+        audio_sha256: any_audio.then(|| audio.finalize().into()),
+    }
+}
@@ -0,0 +1,51 @@
+use crate::headless::{run_headless, sha256, to_hex};
+use crate::input::script::InputScript;
+use crate::nes::Nes;
+
+static NESTEST: &[u8] = include_bytes!("../../tests/nestest.nes");
+
+#[test]
+fn test_headless_runs_are_reproducible() {
+    let mut nes = Nes::from_rom(NESTEST);
+    let report = run_headless(&mut nes, 10, None);
+    assert_eq!(report.frames, 10);
+    assert_eq!(nes.frame_count(), 10);
+    assert_eq!(report.frame_sha256, sha256(&nes.frame().screen_state));
+    assert_eq!(report.ram_sha256, sha256(nes.ram()));
+    assert!(report.audio_sha256.is_some());
+
+    let mut other_nes = Nes::from_rom(NESTEST);
+    assert_eq!(run_headless(&mut other_nes, 10, None), report);
+}
+
+#[test]
+fn test_headless_run_with_an_input_script() {
+    // the nestest menu moves its cursor with down, which changes the ram
+    let mut script = InputScript::parse("5 p1 down").unwrap();
+    let mut nes = Nes::from_rom(NESTEST);
+    let report = run_headless(&mut nes, 10, Some(&mut script));
+    assert!(script.is_finished());
+
+    let mut nes_without_input = Nes::from_rom(NESTEST);
+    assert_ne!(
+        run_headless(&mut nes_without_input, 10, None).ram_sha256,
+        report.ram_sha256
+    );
+}
+
+#[test]
+fn test_report_format() {
+    let mut nes = Nes::from_rom(NESTEST);
+    let report = run_headless(&mut nes, 0, None);
+    let text = report.to_string();
+    assert!(text.starts_with("frames: 0\nframe sha256: "));
+    assert!(text.ends_with("audio sha256: none"));
+}
+
+#[test]
+fn test_hashes_in_hex() {
+    assert_eq!(
+        to_hex(&sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
@@ -0,0 +1,407 @@
+use crate::bus::controller::{
+    Player, BUTTON_A, BUTTON_B, BUTTON_DOWN, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_SELECT,
+    BUTTON_START, BUTTON_UP,
+};
+use crate::input::sdl2_gamepad_serde;
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::GameControllerSubsystem;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+const DEFAULT_AXIS_THRESHOLD: i16 = 16384; // half way
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum GamepadInput {
+    Button(Button),
+    Axis { axis: Axis, positive: bool }, // pressed when the axis passes the threshold in this direction
+}
+
+// the gamepad inputs of a single nes controller. every nes button can be bound to several inputs
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct GamepadBindings {
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub up: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub down: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub left: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub right: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub a: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub b: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub select: Vec<GamepadInput>,
+    #[serde(with = "sdl2_gamepad_serde")]
+    pub start: Vec<GamepadInput>,
+    // how far (out of 32767) a stick should be pushed to count as pressed
+    pub axis_threshold: i16,
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        // both the d-pad and the left stick move, and the nes buttons are where they are on the nes controller
+        let axis = |axis, positive| GamepadInput::Axis { axis, positive };
+        Self {
+            up: vec![
+                GamepadInput::Button(Button::DPadUp),
+                axis(Axis::LeftY, false),
+            ],
+            down: vec![
+                GamepadInput::Button(Button::DPadDown),
+                axis(Axis::LeftY, true),
+            ],
+            left: vec![
+                GamepadInput::Button(Button::DPadLeft),
+                axis(Axis::LeftX, false),
+            ],
+            right: vec![
+                GamepadInput::Button(Button::DPadRight),
+                axis(Axis::LeftX, true),
+            ],
+            a: vec![GamepadInput::Button(Button::B)],
+            b: vec![GamepadInput::Button(Button::A)],
+            select: vec![GamepadInput::Button(Button::Back)],
+            start: vec![GamepadInput::Button(Button::Start)],
+            axis_threshold: DEFAULT_AXIS_THRESHOLD,
+        }
+    }
+}
+
+impl GamepadBindings {
+    fn is_input_active(&self, input: &GamepadInput, state: &GamepadState) -> bool {
+        match input {
+            GamepadInput::Button(button) => state.buttons.contains(button),
+            GamepadInput::Axis { axis, positive } => {
+                let value = state.axes.get(axis).copied().unwrap_or(0) as i32;
+                let threshold = self.axis_threshold as i32;
+                match positive {
+                    true => value > threshold,
+                    false => value < -threshold,
+                }
+            }
+        }
+    }
+
+    fn pressed_buttons(&self, state: &GamepadState) -> u8 {
+        // the nes buttons (see controller::BUTTON_*) held by the gamepad
+        let bindings = [
+            (&self.a, BUTTON_A),
+            (&self.b, BUTTON_B),
+            (&self.select, BUTTON_SELECT),
+            (&self.start, BUTTON_START),
+            (&self.up, BUTTON_UP),
+            (&self.down, BUTTON_DOWN),
+            (&self.left, BUTTON_LEFT),
+            (&self.right, BUTTON_RIGHT),
+        ];
+        bindings
+            .iter()
+            .filter(|(inputs, _)| inputs.iter().any(|i| self.is_input_active(i, state)))
+            .fold(0, |pressed, (_, button)| pressed | button)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct GamepadConfig {
+    pub p1: GamepadBindings,
+    pub p2: GamepadBindings,
+    // by default the gamepads go to the players by the order they connect.
+    // a gamepad whose name contains this text (ignoring case) always goes to this player
+    pub p1_device: Option<String>,
+    pub p2_device: Option<String>,
+}
+
+impl GamepadConfig {
+    fn bindings(&self, player: Player) -> &GamepadBindings {
+        match player {
+            Player::One => &self.p1,
+            Player::Two => &self.p2,
+        }
+    }
+
+    fn device_override(&self, player: Player) -> Option<&String> {
+        match player {
+            Player::One => self.p1_device.as_ref(),
+            Player::Two => self.p2_device.as_ref(),
+        }
+    }
+}
+
+// the nes buttons a player should press and release because of a gamepad
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct ButtonsChange {
+    pub player: Player,
+    pub released: u8,
+    pub pressed: u8,
+}
+
+// what happened to the gamepads, for the frontend to show (the library doesn't print)
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GamepadEvent {
+    Assigned { name: String, player: Player },
+    NoFreePlayer { name: String },
+    Disconnected { name: String },
+    OpenFailed { joystick_index: u32, error: String },
+}
+
+impl fmt::Display for GamepadEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GamepadEvent::Assigned { name, player } => {
+                write!(f, "Gamepad {name} is now player {player:?}")
+            }
+            GamepadEvent::NoFreePlayer { name } => {
+                write!(f, "Gamepad {name} connected, but all the players are taken")
+            }
+            GamepadEvent::Disconnected { name } => write!(f, "Gamepad {name} disconnected"),
+            GamepadEvent::OpenFailed {
+                joystick_index,
+                error,
+            } => write!(f, "Failed to open gamepad {joystick_index} - {error}"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct GamepadState {
+    buttons: HashSet<Button>,
+    axes: HashMap<Axis, i16>,
+}
+
+struct ConnectedGamepad {
+    instance_id: u32,
+    name: String,
+    player: Option<Player>,
+    state: GamepadState,
+    held: u8,                            // the nes buttons this gamepad currently holds
+    _controller: Option<GameController>, // sdl closes the gamepad when this is dropped
+}
+
+// the connected sdl gamepads, and which player each of them controls
+#[derive(Default)]
+pub struct Gamepads {
+    subsystem: Option<GameControllerSubsystem>,
+    pads: Vec<ConnectedGamepad>,
+    events: Vec<GamepadEvent>,
+}
+
+impl fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.pads.iter().map(|pad| (&pad.name, pad.player)))
+            .finish()
+    }
+}
+
+impl PartialEq for Gamepads {
+    fn eq(&self, other: &Self) -> bool {
+        let assignments = |gamepads: &Self| -> Vec<(u32, Option<Player>)> {
+            gamepads
+                .pads
+                .iter()
+                .map(|pad| (pad.instance_id, pad.player))
+                .collect()
+        };
+        assignments(self) == assignments(other)
+    }
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_subsystem(&mut self, subsystem: GameControllerSubsystem) {
+        // without the subsystem, connected gamepads are ignored.
+        // sdl sends an added event for every gamepad that is already connected, so nothing is lost
+        self.subsystem = Some(subsystem);
+    }
+
+    pub fn take_events(&mut self) -> Vec<GamepadEvent> {
+        // the connections and disconnections since the last call
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn open(&mut self, joystick_index: u32, config: &GamepadConfig) -> Vec<ButtonsChange> {
+        let Some(subsystem) = &self.subsystem else {
+            return vec![];
+        };
+        match subsystem.open(joystick_index) {
+            Ok(controller) => {
+                let (instance_id, name) = (controller.instance_id(), controller.name());
+                self.connect(instance_id, name, Some(controller), config)
+            }
+            Err(e) => {
+                self.events.push(GamepadEvent::OpenFailed {
+                    joystick_index,
+                    error: e.to_string(),
+                });
+                vec![]
+            }
+        }
+    }
+
+    pub(crate) fn connect(
+        &mut self,
+        instance_id: u32,
+        name: String,
+        controller: Option<GameController>,
+        config: &GamepadConfig,
+    ) -> Vec<ButtonsChange> {
+        if self.pads.iter().any(|pad| pad.instance_id == instance_id) {
+            return vec![];
+        }
+        self.pads.push(ConnectedGamepad {
+            instance_id,
+            name,
+            player: None,
+            state: GamepadState::default(),
+            held: 0,
+            _controller: controller,
+        });
+        let changes = self.assign_free_players(config);
+        let pad = self.pads.last().unwrap();
+        if pad.player.is_none() {
+            let name = pad.name.clone();
+            self.events.push(GamepadEvent::NoFreePlayer { name });
+        }
+        changes
+    }
+
+    pub fn disconnect(&mut self, instance_id: u32, config: &GamepadConfig) -> Vec<ButtonsChange> {
+        let Some(index) = self.pads.iter().position(|p| p.instance_id == instance_id) else {
+            return vec![];
+        };
+        let pad = self.pads.remove(index);
+        self.events.push(GamepadEvent::Disconnected {
+            name: pad.name.clone(),
+        });
+        let mut changes: Vec<ButtonsChange> = pad
+            .player
+            .map(|player| ButtonsChange {
+                player,
+                released: pad.held,
+                pressed: 0,
+            })
+            .into_iter()
+            .collect();
+        // a waiting gamepad can take its place
+        changes.extend(self.assign_free_players(config));
+        changes
+    }
+
+    pub fn swap_players(&mut self, config: &GamepadConfig) -> Vec<ButtonsChange> {
+        // the manual override while playing, the gamepad of player 1 goes to player 2 and the other way around
+        let mut changes = vec![];
+        for pad in self.pads.iter_mut() {
+            if let Some(player) = pad.player {
+                changes.push(ButtonsChange {
+                    player,
+                    released: pad.held,
+                    pressed: 0,
+                });
+                pad.held = 0;
+                pad.player = Some(match player {
+                    Player::One => Player::Two,
+                    Player::Two => Player::One,
+                });
+                self.events.push(GamepadEvent::Assigned {
+                    name: pad.name.clone(),
+                    player: pad.player.unwrap(),
+                });
+            }
+        }
+        changes.extend(self.refresh_all(config));
+        changes
+    }
+
+    pub fn update_button(
+        &mut self,
+        instance_id: u32,
+        button: Button,
+        pressed: bool,
+        config: &GamepadConfig,
+    ) -> Vec<ButtonsChange> {
+        let Some(pad) = self.pads.iter_mut().find(|p| p.instance_id == instance_id) else {
+            return vec![];
+        };
+        match pressed {
+            true => pad.state.buttons.insert(button),
+            false => pad.state.buttons.remove(&button),
+        };
+        Self::refresh(pad, config).into_iter().collect()
+    }
+
+    pub fn update_axis(
+        &mut self,
+        instance_id: u32,
+        axis: Axis,
+        value: i16,
+        config: &GamepadConfig,
+    ) -> Vec<ButtonsChange> {
+        let Some(pad) = self.pads.iter_mut().find(|p| p.instance_id == instance_id) else {
+            return vec![];
+        };
+        pad.state.axes.insert(axis, value);
+        Self::refresh(pad, config).into_iter().collect()
+    }
+
+    fn refresh(pad: &mut ConnectedGamepad, config: &GamepadConfig) -> Option<ButtonsChange> {
+        // recomputes the nes buttons the gamepad holds, and returns what changed
+        let player = pad.player?;
+        let held = config.bindings(player).pressed_buttons(&pad.state);
+        let change = ButtonsChange {
+            player,
+            released: pad.held & !held,
+            pressed: held & !pad.held,
+        };
+        pad.held = held;
+        (change.released != 0 || change.pressed != 0).then_some(change)
+    }
+
+    fn refresh_all(&mut self, config: &GamepadConfig) -> Vec<ButtonsChange> {
+        self.pads
+            .iter_mut()
+            .filter_map(|pad| Self::refresh(pad, config))
+            .collect()
+    }
+
+    fn is_player_free(&self, player: Player) -> bool {
+        self.pads.iter().all(|pad| pad.player != Some(player))
+    }
+
+    fn choose_player(&self, name: &str, config: &GamepadConfig) -> Option<Player> {
+        let players = [Player::One, Player::Two];
+        // first the players that asked for this gamepad, then the players that didn't ask for any gamepad
+        let requested = players.into_iter().find(|player| {
+            config
+                .device_override(*player)
+                .is_some_and(|device| name.to_lowercase().contains(&device.to_lowercase()))
+                && self.is_player_free(*player)
+        });
+        requested.or(players.into_iter().find(|player| {
+            config.device_override(*player).is_none() && self.is_player_free(*player)
+        }))
+    }
+
+    fn assign_free_players(&mut self, config: &GamepadConfig) -> Vec<ButtonsChange> {
+        for index in 0..self.pads.len() {
+            if self.pads[index].player.is_some() {
+                continue;
+            }
+            let player = self.choose_player(&self.pads[index].name, config);
+            let pad = &mut self.pads[index];
+            pad.player = player;
+            if let Some(player) = player {
+                let name = pad.name.clone();
+                self.events.push(GamepadEvent::Assigned { name, player });
+            }
+        }
+        self.refresh_all(config)
+    }
+}
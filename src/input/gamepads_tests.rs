@@ -0,0 +1,191 @@
+use crate::bus::controller::{Player, BUTTON_A, BUTTON_B, BUTTON_LEFT, BUTTON_UP};
+use crate::config::Config;
+use crate::input::gamepads::{ButtonsChange, GamepadConfig, GamepadEvent, GamepadInput, Gamepads};
+use sdl2::controller::{Axis, Button};
+
+fn change(player: Player, released: u8, pressed: u8) -> Vec<ButtonsChange> {
+    vec![ButtonsChange {
+        player,
+        released,
+        pressed,
+    }]
+}
+
+#[test]
+fn test_gamepads_are_assigned_by_connection_order() {
+    let config = GamepadConfig::default();
+    let mut gamepads = Gamepads::new();
+    gamepads.connect(10, "pad one".to_string(), None, &config);
+    gamepads.connect(20, "pad two".to_string(), None, &config);
+    gamepads.connect(30, "pad three".to_string(), None, &config);
+
+    assert_eq!(
+        gamepads.update_button(20, Button::A, true, &config),
+        change(Player::Two, 0, BUTTON_B)
+    );
+    // the third gamepad has no player
+    assert_eq!(gamepads.update_button(30, Button::A, true, &config), vec![]);
+
+    // until the first one disconnects
+    gamepads.update_button(10, Button::B, true, &config);
+    let changes = gamepads.disconnect(10, &config);
+    assert_eq!(
+        changes,
+        vec![
+            ButtonsChange {
+                player: Player::One,
+                released: BUTTON_A,
+                pressed: 0
+            },
+            // the third gamepad holds its button as soon as it becomes player 1
+            ButtonsChange {
+                player: Player::One,
+                released: 0,
+                pressed: BUTTON_B
+            }
+        ]
+    );
+
+    // the frontend is told what happened
+    let assigned = |name: &str, player| GamepadEvent::Assigned {
+        name: name.to_string(),
+        player,
+    };
+    let events = gamepads.take_events();
+    assert_eq!(
+        events,
+        vec![
+            assigned("pad one", Player::One),
+            assigned("pad two", Player::Two),
+            GamepadEvent::NoFreePlayer {
+                name: "pad three".to_string()
+            },
+            GamepadEvent::Disconnected {
+                name: "pad one".to_string()
+            },
+            assigned("pad three", Player::One),
+        ]
+    );
+    assert_eq!(events[4].to_string(), "Gamepad pad three is now player One");
+    assert!(gamepads.take_events().is_empty());
+}
+
+#[test]
+fn test_gamepad_device_override() {
+    let config = GamepadConfig {
+        p1_device: Some("arcade STICK".to_string()),
+        ..Default::default()
+    };
+    let mut gamepads = Gamepads::new();
+    gamepads.connect(1, "Xbox Controller".to_string(), None, &config);
+    assert_eq!(
+        gamepads.update_button(1, Button::A, true, &config),
+        change(Player::Two, 0, BUTTON_B)
+    );
+
+    // player 1 waits for its gamepad
+    gamepads.connect(2, "Another Controller".to_string(), None, &config);
+    assert_eq!(gamepads.update_button(2, Button::A, true, &config), vec![]);
+    gamepads.connect(3, "Fancy Arcade Stick".to_string(), None, &config);
+    assert_eq!(
+        gamepads.update_button(3, Button::A, true, &config),
+        change(Player::One, 0, BUTTON_B)
+    );
+}
+
+#[test]
+fn test_gamepad_axis_threshold() {
+    let config = GamepadConfig::default();
+    let mut gamepads = Gamepads::new();
+    gamepads.connect(1, "pad".to_string(), None, &config);
+
+    assert_eq!(
+        gamepads.update_axis(1, Axis::LeftY, -10000, &config),
+        vec![]
+    );
+    assert_eq!(
+        gamepads.update_axis(1, Axis::LeftY, -20000, &config),
+        change(Player::One, 0, BUTTON_UP)
+    );
+    // the d-pad holds up too, so it is only released when both are released
+    assert_eq!(
+        gamepads.update_button(1, Button::DPadUp, true, &config),
+        vec![]
+    );
+    assert_eq!(gamepads.update_axis(1, Axis::LeftY, 0, &config), vec![]);
+    assert_eq!(
+        gamepads.update_button(1, Button::DPadUp, false, &config),
+        change(Player::One, BUTTON_UP, 0)
+    );
+}
+
+#[test]
+fn test_gamepads_swap_players() {
+    let config = GamepadConfig::default();
+    let mut gamepads = Gamepads::new();
+    gamepads.connect(1, "pad".to_string(), None, &config);
+    gamepads.update_button(1, Button::DPadLeft, true, &config);
+
+    assert_eq!(
+        gamepads.swap_players(&config),
+        vec![
+            ButtonsChange {
+                player: Player::One,
+                released: BUTTON_LEFT,
+                pressed: 0
+            },
+            ButtonsChange {
+                player: Player::Two,
+                released: 0,
+                pressed: BUTTON_LEFT
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_gamepad_bindings_in_config() {
+    let config = r#"
+        up = "W"
+        down = "S"
+        left = "A"
+        right = "D"
+        a = "N"
+        b = "M"
+        select = "U"
+        start = "I"
+
+        [gamepads]
+        p2_device = "8BitDo"
+
+        [gamepads.p1]
+        a = ["x", "righttrigger"]
+        up = "righty-"
+        axis_threshold = 8000
+    "#;
+    let conf: Config = toml::from_str(config).unwrap();
+    assert_eq!(
+        conf.gamepads.p1.a,
+        vec![
+            GamepadInput::Button(Button::X),
+            GamepadInput::Axis {
+                axis: Axis::TriggerRight,
+                positive: true
+            }
+        ]
+    );
+    assert_eq!(
+        conf.gamepads.p1.up,
+        vec![GamepadInput::Axis {
+            axis: Axis::RightY,
+            positive: false
+        }]
+    );
+    assert_eq!(conf.gamepads.p1.axis_threshold, 8000);
+    assert_eq!(conf.gamepads.p1.b, GamepadConfig::default().p1.b);
+    assert_eq!(conf.gamepads.p2_device.as_deref(), Some("8BitDo"));
+
+    let serialized = toml::to_string(&conf).unwrap();
+    assert_eq!(toml::from_str::<Config>(&serialized).unwrap(), conf);
+    assert!(toml::from_str::<Config>(&config.replace("\"x\"", "\"z\"")).is_err());
+}
@@ -0,0 +1,100 @@
+use crate::apu::mixer::MixerChannel;
+use crate::input::sdl2_keycode_serde;
+use sdl2::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
+
+// keys that control the emulator itself (and not the nes controllers)
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Hotkeys {
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_pulse_1: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_pulse_2: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_triangle: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_noise: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_dmc: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub toggle_expansion: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub swap_gamepads: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub save_state: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub load_state: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub rewind: Keycode, // rewinds while held
+    #[serde(with = "sdl2_keycode_serde")]
+    pub fast_forward: Keycode, // fast forwards while held
+    #[serde(with = "sdl2_keycode_serde")]
+    pub slow_motion: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub pause: Keycode,
+    #[serde(with = "sdl2_keycode_serde")]
+    pub advance_frame: Keycode, // runs one frame and pauses
+    #[serde(with = "sdl2_keycode_serde")]
+    pub step_instruction: Keycode, // runs one cpu instruction and pauses
+    #[serde(with = "sdl2_keycode_serde")]
+    pub soft_reset: Keycode, // the console's reset button
+    #[serde(with = "sdl2_keycode_serde")]
+    pub hard_reset: Keycode, // turns the console off and on again
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Self {
+            toggle_pulse_1: Keycode::F1,
+            toggle_pulse_2: Keycode::F2,
+            toggle_triangle: Keycode::F3,
+            toggle_noise: Keycode::F4,
+            toggle_dmc: Keycode::F5,
+            toggle_expansion: Keycode::F6,
+            swap_gamepads: Keycode::F7,
+            save_state: Keycode::F9,
+            load_state: Keycode::F10,
+            rewind: Keycode::Backspace,
+            fast_forward: Keycode::Tab,
+            slow_motion: Keycode::F8,
+            pause: Keycode::Space,
+            advance_frame: Keycode::Period,
+            step_instruction: Keycode::Comma,
+            soft_reset: Keycode::F11,
+            hard_reset: Keycode::F12,
+        }
+    }
+}
+
+impl Hotkeys {
+    pub fn mixer_channel(&self, kc: Keycode) -> Option<MixerChannel> {
+        // the channel muted / unmuted by this key, if any
+        match kc {
+            val if val == self.toggle_pulse_1 => Some(MixerChannel::Pulse1),
+            val if val == self.toggle_pulse_2 => Some(MixerChannel::Pulse2),
+            val if val == self.toggle_triangle => Some(MixerChannel::Triangle),
+            val if val == self.toggle_noise => Some(MixerChannel::Noise),
+            val if val == self.toggle_dmc => Some(MixerChannel::DMC),
+            val if val == self.toggle_expansion => Some(MixerChannel::Expansion),
+            _ => None,
+        }
+    }
+
+    pub fn save_slot(kc: Keycode) -> Option<u8> {
+        // the number keys select the save state slot
+        match kc {
+            Keycode::Num0 => Some(0),
+            Keycode::Num1 => Some(1),
+            Keycode::Num2 => Some(2),
+            Keycode::Num3 => Some(3),
+            Keycode::Num4 => Some(4),
+            Keycode::Num5 => Some(5),
+            Keycode::Num6 => Some(6),
+            Keycode::Num7 => Some(7),
+            Keycode::Num8 => Some(8),
+            Keycode::Num9 => Some(9),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(feature = "sdl")]
+pub mod gamepads;
+#[cfg(all(test, feature = "sdl"))]
+mod gamepads_tests;
+#[cfg(feature = "sdl")]
+pub mod hotkeys;
+pub mod movie;
+#[cfg(test)]
+mod movie_tests;
+pub mod script;
+#[cfg(test)]
+mod script_tests;
+#[cfg(feature = "sdl")]
+pub mod sdl;
+#[cfg(feature = "sdl")]
+pub(crate) mod sdl2_gamepad_serde;
+#[cfg(feature = "sdl")]
+pub(crate) mod sdl2_keycode_serde;
+#[cfg(all(test, feature = "sdl"))]
+mod sdl_tests;
+
+use crate::apu::mixer::MixerChannel;
+
+pub const NUMBER_OF_PORTS: usize = 2;
+
+// the buttons held on the controller in each port (see Player::port) during one frame, as controller::BUTTON_* bits
+pub type ControllerStates = [u8; NUMBER_OF_PORTS];
+
+// where the controllers get their buttons from. it knows nothing about the emulation,
+// the frontend asks it for the next frame's buttons and hands them to the bus
+pub trait InputProvider {
+    // called once before every frame runs
+    fn next_frame(&mut self) -> ControllerStates;
+
+    // what the user asked the frontend to do since the last call.
+    // unlike next_frame, it can be called at any time (for example while paused)
+    fn poll_requests(&mut self) -> Vec<FrontendRequest> {
+        Vec::new()
+    }
+}
+
+// things the user asks for that the bus can't do on its own (they need the cpu, the ppu, the file system or the window).
+// the input providers collect them, and the frontend handles them between cycles
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FrontendRequest {
+    Quit,
+    ToggleMute(MixerChannel),
+    SelectSaveSlot(u8),
+    SaveState,
+    LoadState,
+    StartRewind,
+    StopRewind,
+    StartFastForward,
+    StopFastForward,
+    ToggleSlowMotion,
+    TogglePause,
+    AdvanceFrame,
+    StepInstruction,
+    SoftReset,
+    HardReset,
+}
+
+impl FrontendRequest {
+    pub fn repeats_while_held(&self) -> bool {
+        // holding the key down keeps stepping
+        matches!(
+            self,
+            FrontendRequest::AdvanceFrame | FrontendRequest::StepInstruction
+        )
+    }
+}
@@ -0,0 +1,100 @@
+use crate::input::{ControllerStates, InputProvider, NUMBER_OF_PORTS};
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+// the buttons of an fm2 port field, from left to right. the first one is the highest bit (see controller::BUTTON_*)
+const FM2_BUTTONS: &str = "RLDUTSBA";
+
+// a recording of the controllers (an fceux .fm2 movie), played back frame by frame.
+// only the text format with standard controllers is supported. the reset commands are ignored
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Movie {
+    frames: Vec<ControllerStates>,
+    position: usize,
+}
+
+impl Movie {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut frames = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            let result = match line.starts_with('|') {
+                true => parse_input_line(line).map(|states| frames.push(states)),
+                false => check_header_line(line),
+            };
+            result.with_context(|| format!("line {} of the movie", i + 1))?;
+        }
+        Ok(Self {
+            frames,
+            position: 0,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the movie {path:?}"))?;
+        Self::parse(&text).with_context(|| format!("failed to load the movie {path:?}"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.frames.len()
+    }
+}
+
+impl InputProvider for Movie {
+    fn next_frame(&mut self) -> ControllerStates {
+        // after the movie ends, nothing is held
+        let states = self.frames.get(self.position).copied().unwrap_or_default();
+        self.position = (self.position + 1).min(self.frames.len());
+        states
+    }
+}
+
+fn check_header_line(line: &str) -> Result<()> {
+    // the header is "key value" lines. most of it is about the recording, we only refuse what we can't play
+    let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+    match (key, value.trim()) {
+        ("binary", "1") => bail!("binary movies are not supported"),
+        ("fourscore", "1") => bail!("four score movies are not supported"),
+        _ => Ok(()),
+    }
+}
+
+fn parse_input_line(line: &str) -> Result<ControllerStates> {
+    // |commands|port 0|port 1|port 2|
+    let fields: Vec<&str> = line.split('|').collect();
+    if fields.len() < 2 + NUMBER_OF_PORTS {
+        bail!("expected {NUMBER_OF_PORTS} controller ports in {line:?}");
+    }
+    fields[1]
+        .parse::<u8>()
+        .with_context(|| format!("bad commands field {:?}", fields[1]))?;
+    let mut states = ControllerStates::default();
+    for (port, field) in fields[2..2 + NUMBER_OF_PORTS].iter().enumerate() {
+        states[port] = parse_port(field)?;
+    }
+    Ok(states)
+}
+
+fn parse_port(field: &str) -> Result<u8> {
+    // an empty field is an unplugged port. otherwise every button that isn't ' ' or '.' is held
+    if field.is_empty() {
+        return Ok(0);
+    }
+    if field.chars().count() != FM2_BUTTONS.len() {
+        bail!("expected {FM2_BUTTONS:?} style buttons, got {field:?}");
+    }
+    Ok(field
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c != ' ' && *c != '.')
+        .fold(0, |buttons, (i, _)| buttons | (0x80 >> i)))
+}
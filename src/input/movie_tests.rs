@@ -0,0 +1,48 @@
+use crate::bus::controller::{BUTTON_A, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_START, BUTTON_UP};
+use crate::input::movie::Movie;
+use crate::input::InputProvider;
+
+static MOVIE: &str = "version 3
+emuVersion 22020
+romFilename mario
+guid 5BBD2E4E-1E04-4A4B-A1D8-4CF3E6C2B8A5
+port0 1
+port1 1
+port2 0
+|0|........|........||
+|0|....T...|........||
+|0|R......A|.L......||
+|1|...U....|........||
+";
+
+#[test]
+fn test_movie_playback() {
+    let mut movie = Movie::parse(MOVIE).unwrap();
+    assert_eq!(movie.len(), 4);
+    assert_eq!(movie.next_frame(), [0, 0]);
+    assert_eq!(movie.next_frame(), [BUTTON_START, 0]);
+    assert_eq!(movie.next_frame(), [BUTTON_RIGHT | BUTTON_A, BUTTON_LEFT]);
+    assert!(!movie.is_finished());
+    assert_eq!(movie.next_frame(), [BUTTON_UP, 0]);
+    assert!(movie.is_finished());
+    // nothing is held after the movie ends
+    assert_eq!(movie.next_frame(), [0, 0]);
+}
+
+#[test]
+fn test_movie_with_an_unplugged_port() {
+    let mut movie = Movie::parse("port1 0\n|0|R  UT  A|||\n").unwrap();
+    assert_eq!(
+        movie.next_frame(),
+        [BUTTON_RIGHT | BUTTON_UP | BUTTON_START | BUTTON_A, 0]
+    );
+}
+
+#[test]
+fn test_unsupported_movies_are_refused() {
+    assert!(Movie::parse("binary 1\n").is_err());
+    assert!(Movie::parse("fourscore 1\n").is_err());
+    let error = Movie::parse("port0 1\n|0|........||\n|0|RLD|........||\n").unwrap_err();
+    assert!(format!("{error:#}").contains("line 3"));
+    assert!(Movie::parse("|x|........|........||\n").is_err());
+}
@@ -0,0 +1,121 @@
+use crate::bus::controller::{
+    BUTTON_A, BUTTON_B, BUTTON_DOWN, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_SELECT, BUTTON_START,
+    BUTTON_UP,
+};
+use crate::input::{ControllerStates, InputProvider};
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+static BUTTON_NAMES: [(&str, u8); 8] = [
+    ("a", BUTTON_A),
+    ("b", BUTTON_B),
+    ("select", BUTTON_SELECT),
+    ("start", BUTTON_START),
+    ("up", BUTTON_UP),
+    ("down", BUTTON_DOWN),
+    ("left", BUTTON_LEFT),
+    ("right", BUTTON_RIGHT),
+];
+
+// buttons held on one port for a range of frames (both ends included)
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+struct ScriptLine {
+    first_frame: u64,
+    last_frame: u64,
+    port: usize,
+    buttons: u8,
+}
+
+// hand written input, for tests and headless runs. every line holds buttons for some frames:
+//   # comments and empty lines are skipped
+//   60 p1 start            <- frame 60 only
+//   100-160 p1 right b     <- frames 100 to 160
+// the frames count from 0, and lines that cover the same frame add up
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct InputScript {
+    lines: Vec<ScriptLine>,
+    frame: u64,
+}
+
+impl InputScript {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let script_line =
+                parse_line(line).with_context(|| format!("line {} of the input script", i + 1))?;
+            lines.push(script_line);
+        }
+        Ok(Self { lines, frame: 0 })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the input script {path:?}"))?;
+        Self::parse(&text).with_context(|| format!("failed to load the input script {path:?}"))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        // true when no line holds anything from this frame on
+        self.lines.iter().all(|line| line.last_frame < self.frame)
+    }
+}
+
+impl InputProvider for InputScript {
+    fn next_frame(&mut self) -> ControllerStates {
+        let mut states = ControllerStates::default();
+        for line in &self.lines {
+            if (line.first_frame..=line.last_frame).contains(&self.frame) {
+                states[line.port] |= line.buttons;
+            }
+        }
+        self.frame += 1;
+        states
+    }
+}
+
+fn parse_line(line: &str) -> Result<ScriptLine> {
+    let mut words = line.split_whitespace();
+    let frames = words.next().unwrap();
+    let (first_frame, last_frame) = match frames.split_once('-') {
+        Some((first, last)) => (parse_frame(first)?, parse_frame(last)?),
+        None => (parse_frame(frames)?, parse_frame(frames)?),
+    };
+    if last_frame < first_frame {
+        bail!("the frames {frames:?} go backwards");
+    }
+
+    let port = match words.next() {
+        Some("p1") => 0,
+        Some("p2") => 1,
+        Some(player) => bail!("unknown player {player:?}, expected p1 or p2"),
+        None => bail!("missing the player"),
+    };
+
+    let mut buttons = 0;
+    for word in words {
+        let name = word.to_lowercase();
+        match BUTTON_NAMES
+            .iter()
+            .find(|(button_name, _)| *button_name == name)
+        {
+            Some((_, button)) => buttons |= button,
+            None => bail!("unknown button {word:?}"),
+        }
+    }
+    Ok(ScriptLine {
+        first_frame,
+        last_frame,
+        port,
+        buttons,
+    })
+}
+
+fn parse_frame(frame: &str) -> Result<u64> {
+    frame
+        .parse()
+        .with_context(|| format!("bad frame number {frame:?}"))
+}
@@ -0,0 +1,34 @@
+use crate::bus::controller::{BUTTON_A, BUTTON_B, BUTTON_RIGHT, BUTTON_START};
+use crate::input::script::InputScript;
+use crate::input::InputProvider;
+
+#[test]
+fn test_script_holds_buttons_for_frame_ranges() {
+    let mut script = InputScript::parse(
+        "# press start, then run right while jumping
+        1 p1 start
+        2-4 p1 right   # both lines add up
+        3 p1 B
+        4 p2 a
+        ",
+    )
+    .unwrap();
+    assert_eq!(script.next_frame(), [0, 0]);
+    assert_eq!(script.next_frame(), [BUTTON_START, 0]);
+    assert_eq!(script.next_frame(), [BUTTON_RIGHT, 0]);
+    assert_eq!(script.next_frame(), [BUTTON_RIGHT | BUTTON_B, 0]);
+    assert!(!script.is_finished());
+    assert_eq!(script.next_frame(), [BUTTON_RIGHT, BUTTON_A]);
+    assert!(script.is_finished());
+    assert_eq!(script.next_frame(), [0, 0]);
+}
+
+#[test]
+fn test_bad_scripts_are_refused() {
+    assert!(InputScript::parse("5 p3 a").is_err());
+    assert!(InputScript::parse("5 p1 turbo").is_err());
+    assert!(InputScript::parse("5").is_err());
+    assert!(InputScript::parse("9-5 p1 a").is_err());
+    let error = InputScript::parse("1 p1 a\nfive p1 a").unwrap_err();
+    assert!(format!("{error:#}").contains("line 2"));
+}
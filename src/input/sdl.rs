@@ -0,0 +1,222 @@
+use crate::config::{Config, ControllerBindings};
+use crate::input::gamepads::{ButtonsChange, GamepadConfig, GamepadEvent, Gamepads};
+use crate::input::hotkeys::Hotkeys;
+use crate::input::{ControllerStates, FrontendRequest, InputProvider, NUMBER_OF_PORTS};
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::{EventPump, GameControllerSubsystem};
+
+// the keyboard and the gamepads, as the user bound them in the config
+pub struct SdlInput {
+    event_pump: EventPump,
+    handler: EventHandler,
+}
+
+impl SdlInput {
+    pub fn new(event_pump: EventPump, config: &Config) -> Self {
+        Self {
+            event_pump,
+            handler: EventHandler::new(config),
+        }
+    }
+
+    pub fn set_gamepad_subsystem(&mut self, subsystem: GameControllerSubsystem) {
+        self.handler.gamepads.set_subsystem(subsystem);
+    }
+
+    pub fn take_gamepad_events(&mut self) -> Vec<GamepadEvent> {
+        // gamepads that connected, disconnected or changed players, for the frontend to show
+        self.handler.gamepads.take_events()
+    }
+
+    fn pump_events(&mut self) {
+        while let Some(event) = self.event_pump.poll_event() {
+            self.handler.handle_event(event);
+        }
+    }
+}
+
+impl InputProvider for SdlInput {
+    fn next_frame(&mut self) -> ControllerStates {
+        self.pump_events();
+        self.handler.buttons
+    }
+
+    fn poll_requests(&mut self) -> Vec<FrontendRequest> {
+        self.pump_events();
+        std::mem::take(&mut self.handler.requests)
+    }
+}
+
+// turns sdl events into the held buttons and the frontend requests.
+// it's apart from the event pump, so it can be fed made up events
+pub(crate) struct EventHandler {
+    p1: ControllerBindings,
+    p2: ControllerBindings,
+    hotkeys: Hotkeys,
+    gamepad_config: GamepadConfig,
+    pub(crate) gamepads: Gamepads,
+    pub(crate) buttons: ControllerStates,
+    pub(crate) requests: Vec<FrontendRequest>,
+}
+
+impl EventHandler {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            p1: config.p1.clone(),
+            p2: config.p2.clone(),
+            hotkeys: config.hotkeys.clone(),
+            gamepad_config: config.gamepads.clone(),
+            gamepads: Gamepads::new(),
+            buttons: [0; NUMBER_OF_PORTS],
+            requests: Vec::new(),
+        }
+    }
+
+    pub(crate) fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Quit { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => self.requests.push(FrontendRequest::Quit),
+
+            Event::KeyDown {
+                keycode: Some(kc),
+                repeat,
+                ..
+            } => {
+                if kc == self.hotkeys.swap_gamepads {
+                    if !repeat {
+                        let changes = self.gamepads.swap_players(&self.gamepad_config);
+                        self.apply_buttons_changes(changes);
+                    }
+                    return;
+                }
+                if let Some(request) = self.frontend_request(kc) {
+                    if !repeat || request.repeats_while_held() {
+                        self.requests.push(request);
+                    }
+                    return;
+                }
+                self.set_controllers_key(kc, true);
+            }
+
+            Event::KeyUp {
+                keycode: Some(kc), ..
+            } => {
+                if let Some(request) = self.frontend_release_request(kc) {
+                    self.requests.push(request);
+                    return;
+                }
+                self.set_controllers_key(kc, false)
+            }
+
+            Event::ControllerDeviceAdded { which, .. } => {
+                let changes = self.gamepads.open(which, &self.gamepad_config);
+                self.apply_buttons_changes(changes);
+            }
+
+            Event::ControllerDeviceRemoved { which, .. } => {
+                let changes = self.gamepads.disconnect(which, &self.gamepad_config);
+                self.apply_buttons_changes(changes);
+            }
+
+            Event::ControllerButtonDown { which, button, .. } => {
+                let changes =
+                    self.gamepads
+                        .update_button(which, button, true, &self.gamepad_config);
+                self.apply_buttons_changes(changes);
+            }
+
+            Event::ControllerButtonUp { which, button, .. } => {
+                let changes =
+                    self.gamepads
+                        .update_button(which, button, false, &self.gamepad_config);
+                self.apply_buttons_changes(changes);
+            }
+
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                let changes = self
+                    .gamepads
+                    .update_axis(which, axis, value, &self.gamepad_config);
+                self.apply_buttons_changes(changes);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn frontend_request(&self, kc: Keycode) -> Option<FrontendRequest> {
+        if let Some(channel) = self.hotkeys.mixer_channel(kc) {
+            return Some(FrontendRequest::ToggleMute(channel));
+        }
+        if kc == self.hotkeys.save_state {
+            return Some(FrontendRequest::SaveState);
+        }
+        if kc == self.hotkeys.load_state {
+            return Some(FrontendRequest::LoadState);
+        }
+        if kc == self.hotkeys.rewind {
+            return Some(FrontendRequest::StartRewind);
+        }
+        if kc == self.hotkeys.fast_forward {
+            return Some(FrontendRequest::StartFastForward);
+        }
+        if kc == self.hotkeys.slow_motion {
+            return Some(FrontendRequest::ToggleSlowMotion);
+        }
+        if kc == self.hotkeys.pause {
+            return Some(FrontendRequest::TogglePause);
+        }
+        if kc == self.hotkeys.advance_frame {
+            return Some(FrontendRequest::AdvanceFrame);
+        }
+        if kc == self.hotkeys.step_instruction {
+            return Some(FrontendRequest::StepInstruction);
+        }
+        if kc == self.hotkeys.soft_reset {
+            return Some(FrontendRequest::SoftReset);
+        }
+        if kc == self.hotkeys.hard_reset {
+            return Some(FrontendRequest::HardReset);
+        }
+        // the number keys may also be bound to a controller, which wins
+        let bound_to_controller = self.p1.button(kc).is_some() || self.p2.button(kc).is_some();
+        match Hotkeys::save_slot(kc) {
+            Some(slot) if !bound_to_controller => Some(FrontendRequest::SelectSaveSlot(slot)),
+            _ => None,
+        }
+    }
+
+    fn frontend_release_request(&self, kc: Keycode) -> Option<FrontendRequest> {
+        // the hotkeys that work while held
+        match kc {
+            val if val == self.hotkeys.rewind => Some(FrontendRequest::StopRewind),
+            val if val == self.hotkeys.fast_forward => Some(FrontendRequest::StopFastForward),
+            _ => None,
+        }
+    }
+
+    fn set_controllers_key(&mut self, kc: Keycode, pressed: bool) {
+        // a key can be bound in both controllers, so we check both of them
+        for (port, bindings) in [&self.p1, &self.p2].into_iter().enumerate() {
+            if let Some(button) = bindings.button(kc) {
+                match pressed {
+                    true => self.buttons[port] |= button,
+                    false => self.buttons[port] &= !button,
+                }
+            }
+        }
+    }
+
+    fn apply_buttons_changes(&mut self, changes: Vec<ButtonsChange>) {
+        for change in changes {
+            let buttons = &mut self.buttons[change.player.port()];
+            *buttons &= !change.released;
+            *buttons |= change.pressed;
+        }
+    }
+}
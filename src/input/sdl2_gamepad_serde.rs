@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::input::gamepads::GamepadInput;
+use sdl2::controller::{Axis, Button};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserializer, Serializer,
+};
+
+// the names are the ones sdl uses in its controller mapping strings, so they are familiar
+// we don't ask sdl for them, since it doesn't need to be initialized to read a config
+static BUTTON_NAMES: [(Button, &str); 21] = [
+    (Button::A, "a"),
+    (Button::B, "b"),
+    (Button::X, "x"),
+    (Button::Y, "y"),
+    (Button::Back, "back"),
+    (Button::Guide, "guide"),
+    (Button::Start, "start"),
+    (Button::LeftStick, "leftstick"),
+    (Button::RightStick, "rightstick"),
+    (Button::LeftShoulder, "leftshoulder"),
+    (Button::RightShoulder, "rightshoulder"),
+    (Button::DPadUp, "dpup"),
+    (Button::DPadDown, "dpdown"),
+    (Button::DPadLeft, "dpleft"),
+    (Button::DPadRight, "dpright"),
+    (Button::Misc1, "misc1"),
+    (Button::Paddle1, "paddle1"),
+    (Button::Paddle2, "paddle2"),
+    (Button::Paddle3, "paddle3"),
+    (Button::Paddle4, "paddle4"),
+    (Button::Touchpad, "touchpad"),
+];
+
+static AXIS_NAMES: [(Axis, &str); 6] = [
+    (Axis::LeftX, "leftx"),
+    (Axis::LeftY, "lefty"),
+    (Axis::RightX, "rightx"),
+    (Axis::RightY, "righty"),
+    (Axis::TriggerLeft, "lefttrigger"),
+    (Axis::TriggerRight, "righttrigger"),
+];
+
+pub fn input_to_string(input: &GamepadInput) -> String {
+    // buttons are just their name, and axes have a + or - for the direction
+    match input {
+        GamepadInput::Button(button) => {
+            let (_, name) = BUTTON_NAMES.iter().find(|(b, _)| b == button).unwrap();
+            name.to_string()
+        }
+        GamepadInput::Axis { axis, positive } => {
+            let (_, name) = AXIS_NAMES.iter().find(|(a, _)| a == axis).unwrap();
+            format!("{name}{}", if *positive { '+' } else { '-' })
+        }
+    }
+}
+
+pub fn input_from_string(s: &str) -> Option<GamepadInput> {
+    let s = s.trim().to_ascii_lowercase();
+    if let Some((button, _)) = BUTTON_NAMES.iter().find(|(_, name)| *name == s) {
+        return Some(GamepadInput::Button(*button));
+    }
+    // an axis without a direction is positive (useful for the triggers)
+    let (name, positive) = match s.strip_suffix('-') {
+        Some(name) => (name, false),
+        None => (s.strip_suffix('+').unwrap_or(&s), true),
+    };
+    AXIS_NAMES
+        .iter()
+        .find(|(_, axis_name)| *axis_name == name)
+        .map(|(axis, _)| GamepadInput::Axis {
+            axis: *axis,
+            positive,
+        })
+}
+
+pub fn serialize<S>(inputs: &[GamepadInput], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(Some(inputs.len()))?;
+    for input in inputs {
+        seq.serialize_element(&input_to_string(input))?;
+    }
+    seq.end()
+}
+
+pub struct GamepadInputsVisitor;
+
+impl<'de> Visitor<'de> for GamepadInputsVisitor {
+    type Value = Vec<GamepadInput>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of SDL2 controller buttons/axes (like \"dpup\" or \"lefty-\")")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // a single input doesn't have to be in a list
+        input_from_string(v)
+            .map(|input| vec![input])
+            .ok_or(E::custom(format!("controller input {v} not recognized")))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut inputs = Vec::new();
+        while let Some(v) = seq.next_element::<String>()? {
+            inputs.push(input_from_string(&v).ok_or(de::Error::custom(format!(
+                "controller input {v} not recognized"
+            )))?);
+        }
+        Ok(inputs)
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<GamepadInput>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(GamepadInputsVisitor)
+}
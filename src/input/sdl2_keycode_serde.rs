@@ -63,5 +63,7 @@ pub fn deserialize<'de, D>(deserializer: D) -> Result<Keycode, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_i64(KeycodeVisitor)
+    // any and not i64, since the player 1 keys are flattened into the config, and a flattened
+    // field is buffered first, and then can only be deserialized according to its actual type
+    deserializer.deserialize_any(KeycodeVisitor)
 }
@@ -0,0 +1,80 @@
+use crate::apu::mixer::MixerChannel;
+use crate::bus::controller::{BUTTON_A, BUTTON_LEFT};
+use crate::config::Config;
+use crate::input::sdl::EventHandler;
+use crate::input::FrontendRequest;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+
+fn key_down(kc: Keycode, repeat: bool) -> Event {
+    Event::KeyDown {
+        timestamp: 0,
+        window_id: 0,
+        keycode: Some(kc),
+        scancode: None,
+        keymod: Mod::NOMOD,
+        repeat,
+    }
+}
+
+fn key_up(kc: Keycode) -> Event {
+    Event::KeyUp {
+        timestamp: 0,
+        window_id: 0,
+        keycode: Some(kc),
+        scancode: None,
+        keymod: Mod::NOMOD,
+        repeat: false,
+    }
+}
+
+#[test]
+fn test_keys_drive_both_controllers() {
+    let conf = Config::default();
+    assert_eq!(conf.p1.button(Keycode::A), Some(BUTTON_A));
+    assert_eq!(conf.p2.button(Keycode::A), None);
+    assert_eq!(conf.p2.button(Keycode::KP_4), Some(BUTTON_LEFT));
+
+    let mut handler = EventHandler::new(&conf);
+    handler.handle_event(key_down(Keycode::KP_4, false));
+    handler.handle_event(key_down(Keycode::A, false));
+    assert_eq!(handler.buttons, [BUTTON_A, BUTTON_LEFT]);
+    handler.handle_event(key_up(Keycode::KP_4));
+    assert_eq!(handler.buttons, [BUTTON_A, 0]);
+    assert!(handler.requests.is_empty());
+}
+
+#[test]
+fn test_hotkeys_become_requests() {
+    let mut handler = EventHandler::new(&Config::default());
+    handler.handle_event(key_down(Keycode::F1, false));
+    handler.handle_event(key_down(Keycode::F1, true));
+    handler.handle_event(key_down(Keycode::Period, false));
+    handler.handle_event(key_down(Keycode::Period, true));
+    handler.handle_event(key_down(Keycode::Tab, false));
+    handler.handle_event(key_up(Keycode::Tab));
+    handler.handle_event(key_down(Keycode::Num3, false));
+    assert_eq!(
+        handler.requests,
+        vec![
+            FrontendRequest::ToggleMute(MixerChannel::Pulse1),
+            FrontendRequest::AdvanceFrame,
+            FrontendRequest::AdvanceFrame,
+            FrontendRequest::StartFastForward,
+            FrontendRequest::StopFastForward,
+            FrontendRequest::SelectSaveSlot(3),
+        ]
+    );
+    assert_eq!(handler.buttons, [0, 0]);
+}
+
+#[test]
+fn test_quitting_is_a_request() {
+    let mut handler = EventHandler::new(&Config::default());
+    handler.handle_event(Event::Quit { timestamp: 0 });
+    handler.handle_event(key_down(Keycode::Escape, false));
+    assert_eq!(
+        handler.requests,
+        vec![FrontendRequest::Quit, FrontendRequest::Quit]
+    );
+}
@@ -1,8 +1,26 @@
 #![feature(inline_const_pat)]
 
+pub mod apu;
+pub mod breakpoints;
 pub mod bus;
+#[cfg(feature = "sdl")]
+pub mod config;
 pub mod cpu;
+pub mod debug_hooks;
+pub mod debugger;
+pub mod disasm;
+pub mod frame_limiter;
+pub mod headless;
+pub mod input;
+pub mod nes;
+pub mod nsf;
 pub mod ppu;
 pub mod prelude;
+pub mod save_state;
+pub mod symbols;
+pub mod test_roms;
+pub mod trace_diff;
+pub mod tracer;
+pub mod video;
 #[cfg(test)]
 pub(crate) use prelude::internal_macros::*;
@@ -0,0 +1,267 @@
+#[cfg(test)]
+mod nes_tests;
+
+use crate::bus::memory::Mem;
+use crate::bus::memory_mapping_constants::CPU_RAM_MEM_UNIQUE_SIZE;
+use crate::bus::ram_init::RamInit;
+use crate::bus::Bus;
+use crate::cpu::interrupt_lines::InterruptLines;
+use crate::cpu::CPU;
+use crate::debug_hooks::{DebugBus, DebugHooks};
+use crate::ppu::frame::Frame;
+use crate::ppu::PPU;
+use crate::save_state;
+use crate::tracer::{TracePosition, Tracer};
+use anyhow::Result;
+use std::ops::{Deref, DerefMut};
+
+// the whole console. the cpu and the ppu both work on the bus, but only one of them can hold it at a time,
+// so the console owns the bus and hands it to whoever runs. between calls neither of them holds it
+pub struct Nes {
+    // only the registers, the cpu runs on a copy that borrows the bus (see CPU::with_bus)
+    cpu: CPU<'static>,
+    // the ppu takes the box while it runs
+    ppu: PPU<Box<Bus>>,
+    bus: Option<Box<Bus>>,
+    cycles: u64, // cpu cycles since the power on (not part of the save state, like the frame count)
+    tracer: Option<Tracer>,
+}
+
+impl Nes {
+    pub fn new(bus: Bus) -> Self {
+        let mut ppu = PPU::new(Box::new(bus));
+        let bus = ppu.bus.take();
+        Self {
+            cpu: CPU::without_bus(),
+            ppu,
+            bus,
+            cycles: 0,
+            tracer: None,
+        }
+    }
+
+    pub fn from_rom(rom: &[u8]) -> Self {
+        Self::from_rom_with_ram_init(rom, RamInit::default())
+    }
+
+    pub fn from_rom_with_ram_init(rom: &[u8], ram_init: RamInit) -> Self {
+        let mut bus = Bus::default();
+        bus.cartridge.load_from_dump(&rom.to_vec());
+        bus.ram_init = ram_init;
+        let mut nes = Self::new(bus);
+        nes.power_on();
+        nes
+    }
+
+    pub fn power_on(&mut self) {
+        // a hard reset. only the cartridge survives, and the reset sequence brings the cpu to its known
+        // power on state (the stack pointer at 0xFD, and irqs masked).
+        // see https://www.nesdev.org/wiki/CPU_power_up_state
+        self.bus_mut().power_on();
+        self.cpu.register_a = 0;
+        self.cpu.register_x = 0;
+        self.cpu.register_y = 0;
+        self.cpu.stack_pointer = 0;
+        self.cpu.status = 0x20;
+        self.cycles = 0;
+        self.reset_cpu_and_ppu();
+    }
+
+    pub fn reset(&mut self) {
+        // the reset button. the memory and most of the cpu registers keep their values
+        self.bus_mut().reset();
+        self.reset_cpu_and_ppu();
+    }
+
+    fn reset_cpu_and_ppu(&mut self) {
+        // the cpu runs the 7 cycles of the reset sequence before the first instruction
+        self.ppu.reset();
+        self.cpu_mut().reset();
+    }
+
+    pub fn step_cycle(&mut self) -> bool {
+        // one cpu cycle, and the three ppu cycles that run with it.
+        // returns true when a frame was completed, it can be taken from frame() until the next step
+        let position = self.trace_position();
+        let mut cpu = self.cpu.with_bus(self.bus.as_deref_mut().unwrap());
+        run_cpu_cycle(&mut cpu, self.tracer.as_mut(), position);
+        self.cpu.copy_registers_from(&cpu);
+        self.run_ppu_cycles()
+    }
+
+    pub fn step_cycle_with_hooks(&mut self, hooks: &mut impl DebugHooks) -> bool {
+        // like step_cycle, and the hooks see the memory accesses and the interrupts of the cpu, and every
+        // instruction boundary. the cpu runs on a bus that tells the hooks, so step_cycle stays as fast as it was
+        let position = self.trace_position();
+        let mut debug_bus = DebugBus::new(self.bus.as_deref_mut().unwrap(), hooks);
+        let mut cpu = self.cpu.with_bus(&mut debug_bus);
+        run_cpu_cycle(&mut cpu, self.tracer.as_mut(), position);
+        self.cpu.copy_registers_from(&cpu);
+        let frame_done = self.run_ppu_cycles();
+        if self.cpu.cpu_idle_cycles == 0 {
+            hooks.instruction_boundary(&self.cpu, self.bus());
+        }
+        frame_done
+    }
+
+    fn trace_position(&self) -> TracePosition {
+        TracePosition {
+            scanline: self.ppu.scanline(),
+            dot: self.ppu.dot(),
+            cycles: self.cycles,
+            frame: self.ppu.frame_count(),
+        }
+    }
+
+    fn run_ppu_cycles(&mut self) -> bool {
+        self.cycles += 1;
+        self.ppu.bus = self.bus.take();
+        let mut frame_done = false;
+        for _ in 0..3 {
+            frame_done |= self.ppu.run_one_ppu_cycle();
+        }
+        self.bus = self.ppu.bus.take();
+        frame_done
+    }
+
+    pub fn step_instruction(&mut self) -> bool {
+        // runs until the cpu is done with the current instruction (or interrupt, or dma)
+        let mut frame_done = false;
+        loop {
+            frame_done |= self.step_cycle();
+            if self.cpu().cpu_idle_cycles == 0 {
+                return frame_done;
+            }
+        }
+    }
+
+    pub fn run_frame(&mut self) {
+        // runs until the ppu completes a frame
+        while !self.step_cycle() {}
+    }
+
+    pub fn frame(&self) -> &Frame {
+        self.ppu.frame()
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.ppu.frame_count()
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub fn set_tracer(&mut self, tracer: Option<Tracer>) -> Option<Tracer> {
+        // the tracer writes a line before every instruction, returns the one it replaced
+        std::mem::replace(&mut self.tracer, tracer)
+    }
+
+    pub fn ram(&self) -> &[u8; CPU_RAM_MEM_UNIQUE_SIZE as usize] {
+        &self.bus().cpu_ram
+    }
+
+    pub fn ram_mut(&mut self) -> &mut [u8; CPU_RAM_MEM_UNIQUE_SIZE as usize] {
+        &mut self.bus_mut().cpu_ram
+    }
+
+    pub fn cpu(&self) -> &CPU<'_> {
+        // the registers are the cpu's public fields
+        &self.cpu
+    }
+
+    pub fn cpu_mut(&mut self) -> CpuMut<'_> {
+        // the cpu on the bus, so it can also run (like an interrupt in a test). the registers are kept when it is dropped
+        CpuMut {
+            cpu: self.cpu.with_bus(self.bus.as_deref_mut().unwrap()),
+            registers: &mut self.cpu,
+        }
+    }
+
+    pub fn ppu(&self) -> &PPU<Box<Bus>> {
+        &self.ppu
+    }
+
+    pub fn bus(&self) -> &Bus {
+        self.bus.as_deref().unwrap()
+    }
+
+    pub fn bus_mut(&mut self) -> &mut Bus {
+        self.bus.as_deref_mut().unwrap()
+    }
+
+    pub fn save_state(&self) -> Vec<u8> {
+        save_state::save(&self.cpu, &self.ppu, self.bus())
+    }
+
+    pub fn load_state(&mut self, data: &[u8]) -> Result<()> {
+        let bus = self.bus.as_deref_mut().unwrap();
+        save_state::load(&mut self.cpu, &mut self.ppu, bus, data)
+    }
+}
+
+// the buses the cpu runs on in the console, the tracer reads the memory through them
+trait ConsoleBus: Mem + InterruptLines {
+    fn console_bus(&self) -> &Bus;
+}
+
+impl ConsoleBus for Bus {
+    fn console_bus(&self) -> &Bus {
+        self
+    }
+}
+
+impl<H: DebugHooks> ConsoleBus for DebugBus<'_, H> {
+    fn console_bus(&self) -> &Bus {
+        self.bus()
+    }
+}
+
+fn run_cpu_cycle<B: ConsoleBus>(
+    cpu: &mut CPU<B>,
+    tracer: Option<&mut Tracer>,
+    position: TracePosition,
+) {
+    match tracer {
+        Some(tracer) => {
+            cpu.run_one_cycle_with(|cpu| {
+                let bus = cpu.bus.as_deref().unwrap().console_bus();
+                tracer.trace(
+                    cpu,
+                    |address| bus.peek_memory(address),
+                    |address| bus.prg_rom_offset(address),
+                    position,
+                );
+            });
+        }
+        None => {
+            cpu.run_one_cycle();
+        }
+    }
+}
+
+// the cpu lent by Nes::cpu_mut
+pub struct CpuMut<'a> {
+    cpu: CPU<'a>,
+    registers: &'a mut CPU<'static>,
+}
+
+impl<'a> Deref for CpuMut<'a> {
+    type Target = CPU<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cpu
+    }
+}
+
+impl DerefMut for CpuMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cpu
+    }
+}
+
+impl Drop for CpuMut<'_> {
+    fn drop(&mut self) {
+        self.registers.copy_registers_from(&self.cpu);
+    }
+}
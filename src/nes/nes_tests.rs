@@ -0,0 +1,139 @@
+use crate::bus::memory::Mem;
+use crate::bus::PPU_WARM_UP_CYCLES;
+use crate::nes::Nes;
+use crate::prelude::test_fixtures::{nop_cartridge_bus, COUNTER_PROGRAM};
+
+fn counter_nes() -> Nes {
+    // the ppu runs too, so it needs a cartridge to draw from
+    let mut nes = Nes::new(nop_cartridge_bus());
+    nes.ram_mut()[..COUNTER_PROGRAM.len()].copy_from_slice(&COUNTER_PROGRAM);
+    nes.cpu_mut().program_counter = 0;
+    // the apu frame counter irq would take the cpu away from the counter
+    nes.cpu_mut().set_interrupt(true);
+    nes
+}
+
+#[test]
+fn test_step_instruction() {
+    let mut nes = counter_nes();
+    nes.step_instruction();
+    assert_eq!(nes.cpu().program_counter, 2);
+    assert_eq!(nes.cpu().register_a, 0x3F);
+    nes.step_instruction();
+    nes.step_instruction();
+    assert_eq!(nes.cpu().program_counter, 6);
+    assert_eq!(nes.cpu().register_x, 1);
+    nes.step_instruction();
+    assert_eq!(nes.ram()[0x10], 1);
+}
+
+#[test]
+fn test_run_frame() {
+    let mut nes = counter_nes();
+    nes.run_frame();
+    assert_eq!(nes.frame_count(), 1);
+    let counter = nes.ram()[0x10];
+    assert_ne!(counter, 0);
+    nes.run_frame();
+    assert_eq!(nes.frame_count(), 2);
+    assert_ne!(nes.ram()[0x10], counter);
+}
+
+#[test]
+fn test_save_and_load_state() {
+    let mut nes = counter_nes();
+    nes.run_frame();
+    let state = nes.save_state();
+    let counter = nes.ram()[0x10];
+    nes.run_frame();
+    let state_after = nes.save_state();
+
+    nes.load_state(&state).unwrap();
+    assert_eq!(nes.ram()[0x10], counter);
+    nes.run_frame();
+    assert_eq!(nes.save_state(), state_after);
+}
+
+#[test]
+fn test_power_on_starts_from_the_reset_vector() {
+    let mut bus = nop_cartridge_bus();
+    bus.cartridge.prg_rom[0x7FFC] = 0x34;
+    bus.cartridge.prg_rom[0x7FFD] = 0x92;
+    let mut nes = Nes::new(bus);
+    nes.power_on();
+    assert_eq!(nes.cpu().program_counter, 0x9234);
+}
+
+fn reset_vector_nes() -> Nes {
+    // the reset vector points to an endless loop: JMP $8000
+    let mut bus = nop_cartridge_bus();
+    bus.cartridge.prg_rom[..3].copy_from_slice(&[0x4C, 0x00, 0x80]);
+    bus.cartridge.prg_rom[0x7FFC] = 0x00;
+    bus.cartridge.prg_rom[0x7FFD] = 0x80;
+    Nes::new(bus)
+}
+
+#[test]
+fn test_power_on_state() {
+    let mut nes = reset_vector_nes();
+    nes.ram_mut()[0x10] = 0x55;
+    nes.power_on();
+    assert_eq!(nes.cpu().stack_pointer, 0xFD);
+    assert_eq!(nes.cpu().status, 0x24);
+    assert_eq!(nes.cpu().register_a, 0);
+    assert_eq!(nes.ram()[0x10], 0);
+    // the reset sequence takes 7 cycles before the first instruction
+    for _ in 0..7 {
+        assert_eq!(nes.cpu().program_counter, 0x8000);
+        nes.step_cycle();
+    }
+    assert_eq!(nes.cpu().cpu_idle_cycles, 0);
+}
+
+#[test]
+fn test_soft_reset_keeps_the_memory() {
+    let mut nes = reset_vector_nes();
+    nes.power_on();
+    nes.run_frame();
+    nes.ram_mut()[0x10] = 0x55;
+    nes.cpu_mut().register_a = 0x12;
+    nes.cpu_mut().status = 0x20;
+    nes.reset();
+    assert_eq!(nes.cpu().stack_pointer, 0xFA);
+    assert!(nes.cpu().get_status_i());
+    assert_eq!(nes.cpu().register_a, 0x12);
+    assert_eq!(nes.cpu().program_counter, 0x8000);
+    assert_eq!(nes.ram()[0x10], 0x55);
+}
+
+#[test]
+fn test_soft_reset_silences_the_apu() {
+    let mut nes = reset_vector_nes();
+    nes.power_on();
+    // pulse 1 enabled, with a long length counter
+    nes.bus_mut().write_memory(0x4015, 0x01);
+    nes.bus_mut().write_memory(0x4003, 0x08);
+    assert_eq!(nes.bus_mut().read_memory(0x4015) & 0x01, 0x01);
+    nes.reset();
+    assert_eq!(nes.bus_mut().read_memory(0x4015) & 0x01, 0);
+}
+
+#[test]
+fn test_ppu_ignores_writes_while_warming_up() {
+    let mut nes = reset_vector_nes();
+    nes.power_on();
+    nes.bus_mut().write_memory(0x2000, 0x80);
+    assert!(!nes.bus().ppu_registers.control_register.get_vblank_nmi());
+    for _ in 0..PPU_WARM_UP_CYCLES {
+        nes.step_cycle();
+    }
+    nes.bus_mut().write_memory(0x2000, 0x80);
+    assert!(nes.bus().ppu_registers.control_register.get_vblank_nmi());
+}
+
+#[test]
+fn test_nes_is_send() {
+    // a frontend can run the console on its own thread
+    fn assert_send<T: Send>() {}
+    assert_send::<Nes>();
+}
@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod nsf_tests;
+mod player;
+
+pub use player::NSFPlayer;
+
+use crate::bus::cartridge::Mirroring;
+use crate::bus::mappers::{Mapper, NSFBanks, NSF_BANK_SIZE};
+use crate::bus::memory_mapping_constants::PRG_ROM_START;
+use crate::bus::Bus;
+use anyhow::{bail, Context, Result};
+
+static NSF_TAG: [u8; 5] = *b"NESM\x1A";
+static NSFE_TAG: [u8; 4] = *b"NSFE";
+const NSF_HEADER_SIZE: usize = 0x80;
+const DEFAULT_NTSC_PLAY_SPEED: u16 = 16639; // in microseconds, about 60.1hz
+
+// a parsed nsf (or nsfe) music file. see https://www.nesdev.org/wiki/NSF and https://www.nesdev.org/wiki/NSFe
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NSF {
+    pub total_songs: u8,
+    pub starting_song: u8, // 1 based, like the nsf header
+    pub load_address: u16,
+    pub init_address: u16,
+    pub play_address: u16,
+    pub title: String,
+    pub artist: String,
+    pub copyright: String,
+    pub ntsc_play_speed: u16, // microseconds between play calls
+    pub bankswitch_init: Option<[u8; 8]>,
+    pub extra_sound_chips: u8,
+    pub track_titles: Vec<String>, // only in nsfe files
+    pub data: Vec<u8>,
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_string(bytes: &[u8]) -> String {
+    // strings in the header are null terminated (or fill the whole field)
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+fn bankswitch_from_bytes(bytes: &[u8]) -> Option<[u8; 8]> {
+    // bankswitching is used iff one of the init values is not zero
+    let mut banks = [0; 8];
+    banks[..bytes.len()].copy_from_slice(bytes);
+    if banks.iter().any(|b| *b != 0) {
+        Some(banks)
+    } else {
+        None
+    }
+}
+
+impl NSF {
+    pub fn parse(raw_file: &[u8]) -> Result<Self> {
+        let nsf = if raw_file.starts_with(&NSF_TAG) {
+            Self::parse_nsf(raw_file)?
+        } else if raw_file.starts_with(&NSFE_TAG) {
+            Self::parse_nsfe(raw_file)?
+        } else {
+            bail!("not an nsf or nsfe file - missing the NESM/NSFE tag")
+        };
+
+        if nsf.load_address < PRG_ROM_START {
+            bail!("load address {:#06x} is not in the rom", nsf.load_address);
+        }
+        if nsf.total_songs == 0 {
+            bail!("nsf file has no songs");
+        }
+        Ok(nsf)
+    }
+
+    fn parse_nsf(raw_file: &[u8]) -> Result<Self> {
+        if raw_file.len() <= NSF_HEADER_SIZE {
+            bail!("nsf file is too short ({} bytes)", raw_file.len());
+        }
+        let header = &raw_file[..NSF_HEADER_SIZE];
+        let ntsc_play_speed = match read_u16(header, 0x6E) {
+            0 => DEFAULT_NTSC_PLAY_SPEED,
+            speed => speed,
+        };
+        Ok(Self {
+            total_songs: header[0x06],
+            starting_song: header[0x07].max(1),
+            load_address: read_u16(header, 0x08),
+            init_address: read_u16(header, 0x0A),
+            play_address: read_u16(header, 0x0C),
+            title: read_string(&header[0x0E..0x2E]),
+            artist: read_string(&header[0x2E..0x4E]),
+            copyright: read_string(&header[0x4E..0x6E]),
+            ntsc_play_speed,
+            bankswitch_init: bankswitch_from_bytes(&header[0x70..0x78]),
+            extra_sound_chips: header[0x7B],
+            track_titles: vec![],
+            data: raw_file[NSF_HEADER_SIZE..].to_vec(),
+        })
+    }
+
+    fn parse_nsfe(raw_file: &[u8]) -> Result<Self> {
+        // nsfe is a list of chunks - 4 bytes length, 4 bytes id, and then the data
+        let mut nsf = Self {
+            total_songs: 1,
+            starting_song: 1,
+            load_address: 0,
+            init_address: 0,
+            play_address: 0,
+            title: String::new(),
+            artist: String::new(),
+            copyright: String::new(),
+            ntsc_play_speed: DEFAULT_NTSC_PLAY_SPEED,
+            bankswitch_init: None,
+            extra_sound_chips: 0,
+            track_titles: vec![],
+            data: vec![],
+        };
+        let mut found_info = false;
+        let mut offset = NSFE_TAG.len();
+
+        while offset + 8 <= raw_file.len() {
+            let length = u32::from_le_bytes(raw_file[offset..offset + 4].try_into()?) as usize;
+            let id = &raw_file[offset + 4..offset + 8];
+            let chunk = raw_file
+                .get(offset + 8..offset + 8 + length)
+                .context("nsfe chunk is longer than the file")?;
+            offset += 8 + length;
+
+            match id {
+                b"INFO" => {
+                    if chunk.len() < 8 {
+                        bail!("nsfe INFO chunk is too short");
+                    }
+                    nsf.load_address = read_u16(chunk, 0);
+                    nsf.init_address = read_u16(chunk, 2);
+                    nsf.play_address = read_u16(chunk, 4);
+                    nsf.extra_sound_chips = chunk[7];
+                    if let Some(total_songs) = chunk.get(8) {
+                        nsf.total_songs = *total_songs;
+                    }
+                    // the starting song is 0 based in nsfe
+                    if let Some(starting_song) = chunk.get(9) {
+                        nsf.starting_song = starting_song + 1;
+                    }
+                    found_info = true;
+                }
+                b"DATA" => nsf.data = chunk.to_vec(),
+                b"BANK" => {
+                    nsf.bankswitch_init = bankswitch_from_bytes(&chunk[..chunk.len().min(8)])
+                }
+                b"RATE" => {
+                    if chunk.len() >= 2 && read_u16(chunk, 0) != 0 {
+                        nsf.ntsc_play_speed = read_u16(chunk, 0);
+                    }
+                }
+                b"auth" => {
+                    let mut strings = chunk.split(|b| *b == 0).map(read_string);
+                    nsf.title = strings.next().unwrap_or_default();
+                    nsf.artist = strings.next().unwrap_or_default();
+                    nsf.copyright = strings.next().unwrap_or_default();
+                }
+                b"tlbl" => {
+                    nsf.track_titles = chunk.split(|b| *b == 0).map(read_string).collect();
+                    nsf.track_titles.truncate(nsf.total_songs as usize);
+                }
+                b"NEND" => break,
+                // chunks that start with upper case are required to play the file correctly
+                _ if id[0].is_ascii_uppercase() => {
+                    bail!("unsupported nsfe chunk {}", String::from_utf8_lossy(id))
+                }
+                _ => {}
+            }
+        }
+
+        if !found_info {
+            bail!("nsfe file has no INFO chunk");
+        }
+        if nsf.data.is_empty() {
+            bail!("nsfe file has no DATA chunk");
+        }
+        Ok(nsf)
+    }
+
+    pub fn track_title(&self, song: u8) -> Option<&str> {
+        // gets a 1 based song number
+        self.track_titles
+            .get((song as usize).checked_sub(1)?)
+            .map(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn initial_banks(&self) -> [u8; 8] {
+        // without bankswitching the data is loaded as is, so the banks are just consecutive
+        self.bankswitch_init.unwrap_or([0, 1, 2, 3, 4, 5, 6, 7])
+    }
+
+    pub fn load_into(&self, bus: &mut Bus) {
+        // sets up a virtual cartridge, with the nsf data loaded into the prg rom
+        let padding = match self.bankswitch_init {
+            Some(_) => self.load_address as usize % NSF_BANK_SIZE,
+            None => (self.load_address - PRG_ROM_START) as usize,
+        };
+        let mut prg_rom = vec![0; padding];
+        prg_rom.extend_from_slice(&self.data);
+
+        bus.cartridge.prg_rom = prg_rom;
+        bus.cartridge.chr_rom = vec![0; 0x2000];
+        bus.cartridge.screen_mirroring = Mirroring::Horizontal;
+        bus.cartridge.mapper =
+            Mapper::NSF(NSFBanks::new(self.initial_banks(), self.extra_sound_chips));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::bus::memory::Mem;
+use crate::bus::Bus;
+use crate::nsf::{NSFPlayer, NSF};
+
+// init (0x8000): STA $00, RTS
+// play (0x8003): INC $01, RTS
+static PROGRAM: [u8; 6] = [0x85, 0x00, 0x60, 0xE6, 0x01, 0x60];
+
+fn build_nsf_file(banks: [u8; 8], data: &[u8]) -> Vec<u8> {
+    let mut file = vec![0; 0x80];
+    file[0..5].copy_from_slice(&[b'N', b'E', b'S', b'M', 0x1A]);
+    file[0x05] = 1; // version
+    file[0x06] = 3; // total songs
+    file[0x07] = 2; // starting song
+    file[0x08..0x0A].copy_from_slice(&0x8000_u16.to_le_bytes());
+    file[0x0A..0x0C].copy_from_slice(&0x8000_u16.to_le_bytes());
+    file[0x0C..0x0E].copy_from_slice(&0x8003_u16.to_le_bytes());
+    file[0x0E..0x0E + 10].copy_from_slice(b"Test Title");
+    file[0x2E..0x2E + 11].copy_from_slice(b"Test Artist");
+    file[0x4E..0x4E + 4].copy_from_slice(b"2025");
+    file[0x6E..0x70].copy_from_slice(&16639_u16.to_le_bytes());
+    file[0x70..0x78].copy_from_slice(&banks);
+    file.extend_from_slice(data);
+    file
+}
+
+fn nsfe_chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = (data.len() as u32).to_le_bytes().to_vec();
+    chunk.extend_from_slice(id);
+    chunk.extend_from_slice(data);
+    chunk
+}
+
+#[test]
+fn test_parse_nsf_header() {
+    let nsf = NSF::parse(&build_nsf_file([0; 8], &PROGRAM)).unwrap();
+    assert_eq!(nsf.total_songs, 3);
+    assert_eq!(nsf.starting_song, 2);
+    assert_eq!(nsf.load_address, 0x8000);
+    assert_eq!(nsf.init_address, 0x8000);
+    assert_eq!(nsf.play_address, 0x8003);
+    assert_eq!(nsf.title, "Test Title");
+    assert_eq!(nsf.artist, "Test Artist");
+    assert_eq!(nsf.copyright, "2025");
+    assert_eq!(nsf.ntsc_play_speed, 16639);
+    assert_eq!(nsf.bankswitch_init, None);
+    assert_eq!(nsf.data, PROGRAM);
+}
+
+#[test]
+fn test_parse_bad_files() {
+    assert!(NSF::parse(b"NES\x1a not an nsf").is_err());
+    // too short
+    assert!(NSF::parse(&build_nsf_file([0; 8], &[])).is_err());
+    // load address outside the rom
+    let mut file = build_nsf_file([0; 8], &PROGRAM);
+    file[0x09] = 0x60;
+    assert!(NSF::parse(&file).is_err());
+}
+
+#[test]
+fn test_parse_nsfe() {
+    let mut info = vec![];
+    info.extend_from_slice(&0x8000_u16.to_le_bytes());
+    info.extend_from_slice(&0x8000_u16.to_le_bytes());
+    info.extend_from_slice(&0x8003_u16.to_le_bytes());
+    info.extend_from_slice(&[0, 0, 2, 1]); // ntsc, no chips, 2 songs, first song is 1 (0 based)
+
+    let mut file = b"NSFE".to_vec();
+    file.extend(nsfe_chunk(b"INFO", &info));
+    file.extend(nsfe_chunk(b"DATA", &PROGRAM));
+    file.extend(nsfe_chunk(b"auth", b"Title\0Artist\0Copyright\0Ripper\0"));
+    file.extend(nsfe_chunk(b"tlbl", b"First\0Second\0"));
+    file.extend(nsfe_chunk(b"RATE", &10000_u16.to_le_bytes()));
+    file.extend(nsfe_chunk(b"plst", &[1, 0])); // unknown optional chunk
+    file.extend(nsfe_chunk(b"NEND", &[]));
+
+    let nsf = NSF::parse(&file).unwrap();
+    assert_eq!(nsf.total_songs, 2);
+    assert_eq!(nsf.starting_song, 2);
+    assert_eq!(nsf.play_address, 0x8003);
+    assert_eq!(nsf.title, "Title");
+    assert_eq!(nsf.artist, "Artist");
+    assert_eq!(nsf.copyright, "Copyright");
+    assert_eq!(nsf.track_title(2), Some("Second"));
+    assert_eq!(nsf.track_title(3), None);
+    assert_eq!(nsf.ntsc_play_speed, 10000);
+    assert_eq!(nsf.data, PROGRAM);
+
+    // unknown required chunk
+    let mut bad_file = file.clone();
+    bad_file.truncate(bad_file.len() - 8);
+    bad_file.extend(nsfe_chunk(b"ABCD", &[]));
+    assert!(NSF::parse(&bad_file).is_err());
+}
+
+#[test]
+fn test_nsf_bankswitching() {
+    // bank 0 is filled with 0x10, bank 1 with 0x11 and bank 2 with 0x12
+    let mut data = vec![];
+    for bank in 0..3 {
+        data.extend(vec![0x10 + bank; 0x1000]);
+    }
+    let nsf = NSF::parse(&build_nsf_file([0, 1, 2, 0, 0, 0, 0, 0], &data)).unwrap();
+    assert_eq!(nsf.bankswitch_init, Some([0, 1, 2, 0, 0, 0, 0, 0]));
+
+    let mut bus = Bus::default();
+    nsf.load_into(&mut bus);
+    assert_eq!(bus.read_memory(0x8000), 0x10);
+    assert_eq!(bus.read_memory(0x9FFF), 0x11);
+    assert_eq!(bus.read_memory(0xA123), 0x12);
+    assert_eq!(bus.read_memory(0xF000), 0x10);
+
+    bus.write_memory(0x5FFF, 2);
+    assert_eq!(bus.read_memory(0xF000), 0x12);
+    // banks that are out of the file read as zeros
+    bus.write_memory(0x5FF8, 7);
+    assert_eq!(bus.read_memory(0x8000), 0x00);
+}
+
+#[test]
+fn test_nsf_player_calls_init_and_play() {
+    let nsf = NSF::parse(&build_nsf_file([0; 8], &PROGRAM)).unwrap();
+    let mut bus = Bus::default();
+    let mut player = NSFPlayer::new(&mut bus, nsf);
+
+    player.start_song(3);
+    assert_eq!(player.current_song(), 3);
+    assert_eq!(player.cpu.read_memory(0x00), 2); // init gets the 0 based song in A
+
+    player.run_play_period();
+    player.run_play_period();
+    assert_eq!(player.cpu.read_memory(0x01), 2);
+
+    // starting a song clears the ram
+    player.start_song(1);
+    assert_eq!(player.cpu.read_memory(0x00), 0);
+    assert_eq!(player.cpu.read_memory(0x01), 0);
+}
+
+#[test]
+fn test_nsf_player_runs_the_apu() {
+    let nsf = NSF::parse(&build_nsf_file([0; 8], &PROGRAM)).unwrap();
+    let mut bus = Bus::default();
+    let mut player = NSFPlayer::new(&mut bus, nsf);
+    player.start_song(1);
+    player.cpu.bus.as_mut().unwrap().apu.take_samples();
+
+    player.run_play_period();
+    // 16639 microseconds at 44100hz
+    let samples = player.cpu.bus.as_mut().unwrap().apu.number_of_samples();
+    assert!((733..=734).contains(&samples));
+}
@@ -0,0 +1,94 @@
+use crate::apu::CPU_CLOCK_RATE;
+use crate::bus::mappers::NSF_BANK_REGISTERS_START;
+use crate::bus::memory_mapping_constants::*;
+use crate::bus::Bus;
+use crate::bus_mut;
+use crate::cpu::CPU;
+use crate::nsf::NSF;
+
+// the init and play routines end with RTS, so we push a fake return address and stop when we reach it.
+// this address is just before the bank registers, so no nsf code should ever run from it
+pub const NSF_RETURN_ADDRESS: u16 = 0x5FF6;
+// some routines never return, so we give up on them after a second
+const MAX_ROUTINE_CYCLES: u32 = CPU_CLOCK_RATE;
+
+pub struct NSFPlayer<'a> {
+    pub cpu: CPU<'a>,
+    pub nsf: NSF,
+    current_song: u8, // 1 based
+    play_period_cycles: u32,
+}
+
+impl<'a> NSFPlayer<'a> {
+    pub fn new(bus: &'a mut Bus, nsf: NSF) -> Self {
+        nsf.load_into(bus);
+        let play_period_cycles =
+            (nsf.ntsc_play_speed as u64 * CPU_CLOCK_RATE as u64 / 1_000_000) as u32;
+        Self {
+            cpu: CPU::new(bus),
+            current_song: nsf.starting_song,
+            nsf,
+            play_period_cycles,
+        }
+    }
+
+    pub fn current_song(&self) -> u8 {
+        self.current_song
+    }
+
+    pub fn start_song(&mut self, song: u8) {
+        // initialize the memory and apu the way https://www.nesdev.org/wiki/NSF#Initializing_a_tune describes,
+        // and then call the init routine with the (0 based) song number in A
+        let song = song.clamp(1, self.nsf.total_songs);
+        self.current_song = song;
+
+        for addr in CPU_RAM_MEM_START..CPU_RAM_MEM_UNIQUE_SIZE {
+            self.cpu.write_memory(addr, 0);
+        }
+        for addr in PRG_RAM_START..=PRG_RAM_END {
+            self.cpu.write_memory(addr, 0);
+        }
+        for addr in APU_REGISTERS_START..=APU_REGISTERS_END {
+            self.cpu.write_memory(addr, 0);
+        }
+        self.cpu.write_memory(APU_STATUS, 0x0F);
+        self.cpu.write_memory(APU_FRAME_COUNTER, 0x40);
+
+        for (i, bank) in self.nsf.initial_banks().into_iter().enumerate() {
+            self.cpu
+                .write_memory(NSF_BANK_REGISTERS_START + i as u16, bank);
+        }
+
+        self.cpu.register_a = song - 1;
+        self.cpu.register_x = 0; // ntsc
+        self.cpu.register_y = 0;
+        self.cpu.stack_pointer = 0xFD;
+        self.cpu.status = 0x24;
+        self.call_routine(self.nsf.init_address);
+    }
+
+    fn call_routine(&mut self, address: u16) -> u32 {
+        // runs the routine until it returns, and returns the number of cycles it took
+        let cpu = &mut self.cpu;
+        cpu.stack_push_u16(NSF_RETURN_ADDRESS - 1);
+        cpu.program_counter = address;
+        let mut cycles = 0;
+        while cycles < MAX_ROUTINE_CYCLES {
+            if cpu.program_counter == NSF_RETURN_ADDRESS && cpu.cpu_idle_cycles == 0 {
+                break;
+            }
+            cpu.run_one_cycle();
+            cycles += 1;
+        }
+        cycles
+    }
+
+    pub fn run_play_period(&mut self) {
+        // calls the play routine once, and keeps the apu running until the next call should happen
+        let cycles = self.call_routine(self.nsf.play_address);
+        let cpu = &mut self.cpu;
+        for _ in cycles..self.play_period_cycles {
+            bus_mut!(cpu).tick_apu();
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use super::render_nes::ppu_render_constants::*;
-use sdl2::render::{Texture, WindowCanvas};
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct Frame {
@@ -35,10 +34,3 @@ impl Frame {
         )
     }
 }
-
-pub fn update_texture_from_frame(texture: &mut Texture, frame: &Frame, canvas: &mut WindowCanvas) {
-    texture
-        .update(None, &frame.screen_state, SCREEN_WIDTH * 3)
-        .unwrap();
-    canvas.copy(texture, None, None).unwrap();
-}
@@ -4,12 +4,12 @@ pub use render_nes::ppu_render_constants::{SCREEN_HEIGHT, SCREEN_WIDTH};
 pub mod colors_palette;
 pub mod frame;
 mod render_nes;
-mod user_input;
 
 use crate::bus::Bus;
 use crate::ppu::frame::Frame;
-use sdl2::render::{Texture, WindowCanvas};
-use sdl2::EventPump;
+use crate::save_state::{SaveState, StateReader, StateWriter};
+use anyhow::Result;
+use std::ops::DerefMut;
 
 const MAX_SPRITES_PER_LINE: usize = 8;
 
@@ -20,7 +20,8 @@ pub struct SpritePixel {
     sprite_index: u8,
 }
 
-pub struct PPU<'a> {
+// the ppu holds the bus while it runs. it can be borrowed, or the box the console keeps it in (see Nes)
+pub struct PPU<B: DerefMut<Target = Bus>> {
     ppu_cycles_in_current_scanline: usize, // the scanline lasts for 341 ppu cycles
     scanlines_in_current_frame: usize,     // each frame has 262 scanlines, with NMI in scanline 240
     cur_scanline_x_offset: usize, // we will compute the offset for the left pixel in the current scanline once per scanline
@@ -29,7 +30,9 @@ pub struct PPU<'a> {
     sprites_original_numbers: [u8; MAX_SPRITES_PER_LINE], // to remember where each sprite in the secondary oam came from
     next_line_sprite_pixels: [Option<SpritePixel>; SCREEN_WIDTH],
     number_of_sprites_in_scanline: usize,
-    pub bus: Option<&'a mut Bus>,
+    frame: Frame,
+    frame_count: u64, // frames completed since the ppu was created (not part of the save state)
+    pub bus: Option<B>,
 }
 
 #[macro_export]
@@ -46,8 +49,8 @@ macro_rules! palette {
     };
 }
 
-impl<'a> PPU<'a> {
-    pub fn new(bus: &'a mut Bus) -> Self {
+impl<B: DerefMut<Target = Bus>> PPU<B> {
+    pub fn new(bus: B) -> Self {
         PPU {
             ppu_cycles_in_current_scanline: 0,
             scanlines_in_current_frame: 0,
@@ -57,6 +60,8 @@ impl<'a> PPU<'a> {
             sprites_original_numbers: [0; MAX_SPRITES_PER_LINE],
             next_line_sprite_pixels: [None; SCREEN_WIDTH],
             number_of_sprites_in_scanline: 0,
+            frame: Frame::new(),
+            frame_count: 0,
             bus: Some(bus),
         }
     }
@@ -68,41 +73,91 @@ impl<'a> PPU<'a> {
         }
     }
 
-    pub fn run_one_ppu_cycle(
-        &mut self,
-        texture: &mut Texture,
-        frame: &mut Frame,
-        canvas: &mut WindowCanvas,
-        event_pump: &mut EventPump,
-    ) {
+    pub fn run_one_ppu_cycle(&mut self) -> bool {
+        // returns true when a frame was completed, it can be taken from frame() until the next cycle
         self.handle_sprites_one_cycle();
-        self.handle_background_one_cycle(frame);
+        self.handle_background_one_cycle();
 
         self.ppu_cycles_in_current_scanline += 1;
         self.trigger_new_scanline_if_needed();
 
         if self.scanlines_in_current_frame >= SCANLINES_PER_FRAME {
-            self.trigger_new_frame(texture, frame, canvas, event_pump);
+            self.trigger_new_frame();
+            return true;
         }
+        false
     }
 
-    fn trigger_new_frame(
-        &mut self,
-        texture: &mut Texture,
-        frame: &mut Frame,
-        canvas: &mut WindowCanvas,
-        event_pump: &mut EventPump,
-    ) {
+    pub fn reset(&mut self) {
+        // starts drawing a new frame from the top, the frame count goes on
+        self.ppu_cycles_in_current_scanline = 0;
+        self.scanlines_in_current_frame = 0;
+    }
+
+    pub fn frame(&self) -> &Frame {
+        // the frame being drawn, it is only whole right after run_one_ppu_cycle returns true
+        &self.frame
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn scanline(&self) -> usize {
+        // 0 is the first visible line, the vblank starts at 241 and 261 is the pre render line
+        self.scanlines_in_current_frame
+    }
+
+    pub fn dot(&self) -> usize {
+        // the ppu cycle in the scanline, 0 to 340
+        self.ppu_cycles_in_current_scanline
+    }
+
+    fn trigger_new_frame(&mut self) {
         self.scanlines_in_current_frame -= SCANLINES_PER_FRAME;
-        update_texture_from_frame(texture, frame, canvas);
-        canvas.present();
-        self.handle_user_input(event_pump);
+        self.frame_count += 1;
     }
 }
 
-fn update_texture_from_frame(texture: &mut Texture, frame: &Frame, canvas: &mut WindowCanvas) {
-    texture
-        .update(None, &frame.screen_state, SCREEN_WIDTH * 3)
-        .unwrap();
-    canvas.copy(texture, None, None).unwrap();
+impl<B: DerefMut<Target = Bus>> SaveState for PPU<B> {
+    // only the ppu internals, the registers and the ppu memory live in the bus
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.ppu_cycles_in_current_scanline as u16);
+        state.write_u16(self.scanlines_in_current_frame as u16);
+        state.write_u16(self.cur_scanline_x_offset as u16);
+        state.write_u16(self.cur_scanline_y_offset as u16);
+        state.write_bytes(&self.secondary_oam);
+        state.write_bytes(&self.sprites_original_numbers);
+        for pixel in &self.next_line_sprite_pixels {
+            state.write_bool(pixel.is_some());
+            if let Some(pixel) = pixel {
+                let (r, g, b) = pixel.color;
+                state.write_bytes(&[r, g, b]);
+                state.write_bool(pixel.is_background);
+                state.write_u8(pixel.sprite_index);
+            }
+        }
+        state.write_u8(self.number_of_sprites_in_scanline as u8);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()> {
+        self.ppu_cycles_in_current_scanline = state.read_u16()? as usize;
+        self.scanlines_in_current_frame = state.read_u16()? as usize;
+        self.cur_scanline_x_offset = state.read_u16()? as usize;
+        self.cur_scanline_y_offset = state.read_u16()? as usize;
+        state.read_bytes(&mut self.secondary_oam)?;
+        state.read_bytes(&mut self.sprites_original_numbers)?;
+        for pixel in self.next_line_sprite_pixels.iter_mut() {
+            *pixel = match state.read_bool()? {
+                true => Some(SpritePixel {
+                    color: (state.read_u8()?, state.read_u8()?, state.read_u8()?),
+                    is_background: state.read_bool()?,
+                    sprite_index: state.read_u8()?,
+                }),
+                false => None,
+            };
+        }
+        self.number_of_sprites_in_scanline = state.read_u8()? as usize;
+        Ok(())
+    }
 }
@@ -1,11 +1,11 @@
 use crate::ppu::render_nes::ppu_render_constants::*;
 
-use crate::bus::{PPU_NAMETABLE_SIZE, PPU_NAMETABLE_START};
+use crate::bus::{Bus, PPU_NAMETABLE_SIZE, PPU_NAMETABLE_START};
 use crate::ppu::colors_palette::SYSTEM_PALETTE;
 
-use crate::ppu::frame::Frame;
 use crate::ppu::PPU;
 use crate::{bus, bus_mut, palette};
+use std::ops::DerefMut;
 
 macro_rules! status_reg {
     ($ppu: ident) => {
@@ -55,7 +55,7 @@ fn tile_y_in_nametable(tile_y: usize) -> usize {
     tile_y % SCREEN_HEIGHT_TILE
 }
 
-impl<'bus> PPU<'bus> {
+impl<B: DerefMut<Target = Bus>> PPU<B> {
     fn get_current_abs_x(&self) -> usize {
         let abs_x = self.ppu_cycles_in_current_scanline + self.cur_scanline_x_offset + TILE_WIDTH; // we add tile width because we render the first two line in the previous scanline
         abs_x % (2 * SCREEN_WIDTH)
@@ -90,8 +90,8 @@ impl<'bus> PPU<'bus> {
         let tile_start = (bank_start + current_tile_number * 16) as usize;
 
         (
-            bus!(self).cartridge.chr_rom[tile_start + y_offset_in_tile + TILE_HEIGHT],
-            bus!(self).cartridge.chr_rom[tile_start + y_offset_in_tile],
+            bus!(self).cartridge.read_chr((tile_start + y_offset_in_tile + TILE_HEIGHT) as u16),
+            bus!(self).cartridge.read_chr((tile_start + y_offset_in_tile) as u16),
         )
     }
 
@@ -150,7 +150,6 @@ impl<'bus> PPU<'bus> {
 
     fn set_background_pixel(
         &mut self,
-        frame: &mut Frame,
         x: usize,
         y: usize,
         bkg_color: (u8, u8, u8),
@@ -158,14 +157,14 @@ impl<'bus> PPU<'bus> {
         // this function compares the pixel with the sprites pixel
         // and handles background/foreground correctly
         match self.next_line_sprite_pixels[x] {
-            None => frame.set_pixel(x, y, bkg_color),
+            None => self.frame.set_pixel(x, y, bkg_color),
             Some(sprite_pixel) => {
                 match sprite_pixel.is_background {
                     true => match bkg_color != SYSTEM_PALETTE[palette!(self)[0] as usize] {
-                        true => frame.set_pixel(x, y, bkg_color),
-                        false => frame.set_pixel(x, y, sprite_pixel.color),
+                        true => self.frame.set_pixel(x, y, bkg_color),
+                        false => self.frame.set_pixel(x, y, sprite_pixel.color),
                     },
-                    false => frame.set_pixel(x, y, sprite_pixel.color),
+                    false => self.frame.set_pixel(x, y, sprite_pixel.color),
                 }
 
                 match sprite_pixel.sprite_index == 0 {
@@ -178,7 +177,7 @@ impl<'bus> PPU<'bus> {
         }
     }
 
-    fn handle_visible_scanline(&mut self, frame: &mut Frame) {
+    fn handle_visible_scanline(&mut self) {
         let x_pos_in_frame = self.ppu_cycles_in_current_scanline;
         match x_pos_in_frame {
             0 => {} // I think we can treat it as a nop?
@@ -201,7 +200,6 @@ impl<'bus> PPU<'bus> {
                             break;
                         }
                         self.set_background_pixel(
-                            frame,
                             tile_left_position_in_frame + i,
                             row_number,
                             row_tile_pixels[i],
@@ -231,7 +229,6 @@ impl<'bus> PPU<'bus> {
                 let fine_x_shift = self.cur_scanline_x_offset % TILE_WIDTH;
                 for i in 0..TILE_WIDTH - fine_x_shift {
                     self.set_background_pixel(
-                        frame,
                         i,
                         row_number,
                         row_tile_pixels[i + fine_x_shift],
@@ -251,7 +248,6 @@ impl<'bus> PPU<'bus> {
                 let fine_x_shift = self.cur_scanline_x_offset % TILE_WIDTH;
                 for i in 0..TILE_WIDTH {
                     self.set_background_pixel(
-                        frame,
                         8 - fine_x_shift + i,
                         row_number,
                         row_tile_pixels[i],
@@ -278,7 +274,7 @@ impl<'bus> PPU<'bus> {
         }
     }
 
-    fn handle_pre_render_scanline(&mut self, frame: &mut Frame) {
+    fn handle_pre_render_scanline(&mut self) {
         // mostly the same as a visible frame, but we copy vert v to vert t
         if self.ppu_cycles_in_current_scanline == 1 {
             // clear vblank and sprite 0 hit
@@ -296,19 +292,19 @@ impl<'bus> PPU<'bus> {
             self.cur_scanline_y_offset = bus!(self).ppu_registers.get_abs_y_offset()
         }
         if self.ppu_cycles_in_current_scanline > SCREEN_WIDTH {
-            self.handle_visible_scanline(frame);
+            self.handle_visible_scanline();
         }
     }
 
-    pub fn handle_background_one_cycle(&mut self, frame: &mut Frame) {
+    pub fn handle_background_one_cycle(&mut self) {
         match self.scanlines_in_current_frame {
             0..SCREEN_HEIGHT => {
-                self.handle_visible_scanline(frame);
+                self.handle_visible_scanline();
             }
             SCREEN_HEIGHT => {}
             NMI_SCANLINE => self.handle_post_render_scanline(),
             const { NMI_SCANLINE + 1 }..const { SCANLINES_PER_FRAME - 1 } => {}
-            const { SCANLINES_PER_FRAME - 1 } => self.handle_pre_render_scanline(frame),
+            const { SCANLINES_PER_FRAME - 1 } => self.handle_pre_render_scanline(),
             _ => panic!("Shouldn't be here!"),
         }
     }
@@ -1,5 +1,5 @@
 use super::PPU;
-use crate::bus::NUMBER_OF_SPRITE;
+use crate::bus::{Bus, NUMBER_OF_SPRITE};
 use crate::ppu::colors_palette::SYSTEM_PALETTE;
 use crate::ppu::render_nes::ppu_render_constants::{
     DOT_TO_START_FETCH_NEXT_LINE_TILES, SCANLINE_LENGTH_PIXELS, SPRITES_FETCH_START_DOT,
@@ -7,8 +7,9 @@ use crate::ppu::render_nes::ppu_render_constants::{
 };
 use crate::ppu::{SpritePixel, MAX_SPRITES_PER_LINE, SCREEN_HEIGHT, SCREEN_WIDTH};
 use crate::{bus, bus_mut, ppu_mem};
+use std::ops::DerefMut;
 
-impl<'bus> PPU<'bus> {
+impl<B: DerefMut<Target = Bus>> PPU<B> {
     fn clear_secondary_oam(&mut self) {
         // happens in cycles 1 - 64
         if self.ppu_cycles_in_current_scanline % 2 == 0 {
@@ -70,8 +71,8 @@ impl<'bus> PPU<'bus> {
         let tile_start = bank_start + tile_number * 16;
 
         (
-            bus!(self).cartridge.chr_rom[tile_start + y_offset_in_tile + TILE_HEIGHT],
-            bus!(self).cartridge.chr_rom[tile_start + y_offset_in_tile],
+            bus!(self).cartridge.read_chr((tile_start + y_offset_in_tile + TILE_HEIGHT) as u16),
+            bus!(self).cartridge.read_chr((tile_start + y_offset_in_tile) as u16),
         )
     }
 
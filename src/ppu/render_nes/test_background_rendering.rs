@@ -1,11 +1,9 @@
 use crate::bus::cartridge::Mirroring;
 use crate::ppu::colors_palette::SYSTEM_PALETTE;
-use crate::ppu::frame::Frame;
 use crate::ppu::render_nes::assert_screen_state;
 use crate::ppu::render_nes::ppu_render_constants::SCREEN_WIDTH_TILE;
 use crate::ppu::{SCANLINES_PER_FRAME, SCANLINE_LENGTH_PIXELS};
-use crate::{bus_mut, generate_ppu, generate_texture_canvas_event_pump};
-use serial_test::serial;
+use crate::{bus_mut, generate_ppu};
 
 fn get_half_tile() -> [u8; 16] {
     // Bit Planes            Pixel Pattern
@@ -52,12 +50,10 @@ fn get_color_index_for_half_tile(x: i32, y: i32) -> usize {
 }
 
 #[test]
-#[serial]
 fn background_drawing() {
     // this tests the drawing of the background
     // the result frame should have one tile that kinda draws the symbol for 1/2
     generate_ppu!(ppu);
-    generate_texture_canvas_event_pump!(texture, canvas, event_pump);
 
     bus_mut!(ppu).cartridge.screen_mirroring = Mirroring::Horizontal;
 
@@ -81,22 +77,21 @@ fn background_drawing() {
         SYSTEM_PALETTE[24],
     ];
 
-    let mut frame = Frame::new();
     for _ in 0..SCANLINE_LENGTH_PIXELS {
         for _ in 0..SCANLINES_PER_FRAME {
-            ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
+            ppu.run_one_ppu_cycle();
         }
     }
 
+    let frame = ppu.frame();
+
     assert_screen_state!(frame, 72, 32, get_color_index_for_half_tile, wanted_palette);
 }
 
 #[test]
-#[serial]
 fn test_scrolling() {
     // this tests the scrolling
     generate_ppu!(ppu);
-    generate_texture_canvas_event_pump!(texture, canvas, event_pump);
 
     bus_mut!(ppu).cartridge.screen_mirroring = Mirroring::Vertical;
 
@@ -135,13 +130,11 @@ fn test_scrolling() {
         .control_register
         .write_byte(0x03); // use the second nametable both in x and in y
 
-    let mut frame = Frame::new();
-
     for _ in 0..2 {
         // the y scroll only kicks into action after 1 full cycle
         for _ in 0..SCANLINE_LENGTH_PIXELS {
             for _ in 0..SCANLINES_PER_FRAME {
-                ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
+                ppu.run_one_ppu_cycle();
             }
         }
     }
@@ -150,6 +143,7 @@ fn test_scrolling() {
     // when we looked at it as a tile in the bottom right nametable (mirroring!)
     // so now it should be in y = 17*8 + 240 - 333 = 43
     // and in x = 23 * 8 + 256 - 350 = 90
+    let frame = ppu.frame();
     assert_screen_state!(frame, 90, 43, get_color_index_for_half_tile, wanted_palette);
 
     // as an extra cherry on top (and due to a previous bug): we check that adding y nametable
@@ -162,9 +156,10 @@ fn test_scrolling() {
     for _ in 0..2 {
         for _ in 0..SCANLINE_LENGTH_PIXELS {
             for _ in 0..SCANLINES_PER_FRAME {
-                ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
+                ppu.run_one_ppu_cycle();
             }
         }
     }
+    let frame = ppu.frame();
     assert_screen_state!(frame, 90, 43, get_color_index_for_half_tile, wanted_palette);
 }
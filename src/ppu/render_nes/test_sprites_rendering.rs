@@ -1,13 +1,12 @@
 use crate::bus::cartridge::Mirroring;
+use crate::bus::Bus;
 use crate::ppu::colors_palette::SYSTEM_PALETTE;
-use crate::ppu::frame::Frame;
 use crate::ppu::render_nes::assert_screen_state;
 use crate::ppu::render_nes::ppu_render_constants::SCREEN_WIDTH_TILE;
 use crate::ppu::{PPU, SCANLINES_PER_FRAME, SCANLINE_LENGTH_PIXELS};
-use crate::{bus_mut, generate_ppu, generate_texture_canvas_event_pump};
-use serial_test::serial;
+use crate::{bus_mut, generate_ppu};
 
-fn prepare_diamond_sprite(ppu: &mut PPU, tile_number: usize, bank_start: usize) {
+fn prepare_diamond_sprite(ppu: &mut PPU<&mut Bus>, tile_number: usize, bank_start: usize) {
     { // set sprite 5 to be our tile
          // this has its own scope to be collapsable in the IDE
          // Bit Planes            Pixel Pattern
@@ -39,7 +38,7 @@ fn prepare_diamond_sprite(ppu: &mut PPU, tile_number: usize, bank_start: usize)
         .copy_from_slice(&our_tile);
 }
 
-fn set_diamond_sprites(ppu: &mut PPU, center_x: u8, center_y: u8, is_background: bool) {
+fn set_diamond_sprites(ppu: &mut PPU<&mut Bus>, center_x: u8, center_y: u8, is_background: bool) {
     let top_right_attr = prepare_attribute_byte(false, false, is_background, 1);
     set_sprite(ppu, 12, 5, center_x, center_y - 8, top_right_attr);
 
@@ -97,7 +96,14 @@ fn prepare_attribute_byte(flip_h: bool, flip_v: bool, is_bkg: bool, palette: u8)
     ((flip_v as u8) << 7) + ((flip_h as u8) << 6) + ((is_bkg as u8) << 5) + palette
 }
 
-fn set_sprite(ppu: &mut PPU, sprite_number: usize, tile_number: u8, x: u8, y: u8, attr: u8) {
+fn set_sprite(
+    ppu: &mut PPU<&mut Bus>,
+    sprite_number: usize,
+    tile_number: u8,
+    x: u8,
+    y: u8,
+    attr: u8,
+) {
     bus_mut!(ppu).ppu_memory.oam_data[4 * sprite_number] = y;
     bus_mut!(ppu).ppu_memory.oam_data[4 * sprite_number + 1] = tile_number;
     bus_mut!(ppu).ppu_memory.oam_data[4 * sprite_number + 2] = attr;
@@ -105,12 +111,10 @@ fn set_sprite(ppu: &mut PPU, sprite_number: usize, tile_number: u8, x: u8, y: u8
 }
 
 #[test]
-#[serial]
 fn sprites_rendering() {
     // this tests the drawing of the character sprites
     // the result frame should have one tile that kinda looks like a diamond
     generate_ppu!(ppu);
-    generate_texture_canvas_event_pump!(texture, canvas, event_pump);
 
     bus_mut!(ppu).cartridge.screen_mirroring = Mirroring::Horizontal;
 
@@ -139,15 +143,15 @@ fn sprites_rendering() {
     // the center will be at 121.5, 69.5
     set_diamond_sprites(&mut ppu, 122, 70, false);
 
-    let mut frame = Frame::new();
     for _ in 0..SCANLINE_LENGTH_PIXELS {
         for _ in 0..SCANLINES_PER_FRAME {
-            ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
+            ppu.run_one_ppu_cycle();
         }
     }
 
     // in the end, we should have a pretty diamond shape
     // with the center at 121.5, 69.5
+    let frame = ppu.frame();
     assert_screen_state!(
         frame,
         122,
@@ -158,11 +162,9 @@ fn sprites_rendering() {
 }
 
 #[test]
-#[serial]
 fn sprites_background() {
     // a test to check we handle the background process correctly
     generate_ppu!(ppu);
-    generate_texture_canvas_event_pump!(texture, canvas, event_pump);
 
     bus_mut!(ppu).cartridge.screen_mirroring = Mirroring::Horizontal;
 
@@ -198,10 +200,9 @@ fn sprites_background() {
     bus_mut!(ppu).ppu_memory.vram[960 + 2 * 8 + 3] = 0x0C; // mask of our tile meta tile
     bus_mut!(ppu).ppu_memory.palette_table[13] = 35;
 
-    let mut frame = Frame::new();
     for _ in 0..SCANLINE_LENGTH_PIXELS {
         for _ in 0..SCANLINES_PER_FRAME {
-            ppu.run_one_ppu_cycle(&mut texture, &mut frame, &mut canvas, &mut event_pump);
+            ppu.run_one_ppu_cycle();
         }
     }
 
@@ -227,6 +228,8 @@ fn sprites_background() {
         SYSTEM_PALETTE[35],
     ];
 
+    let frame = ppu.frame();
+
     assert_screen_state!(
         frame,
         122,
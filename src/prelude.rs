@@ -35,16 +35,51 @@ pub(crate) mod internal_macros {
     pub(crate) use generate_cpu_and_set_horizontal_mirroring;
 }
 
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use crate::bus::cartridge::Mirroring;
+    use crate::bus::Bus;
+
+    // LDA #$3F; STA $4000; INX; STX $10; JMP $0005
+    pub(crate) static COUNTER_PROGRAM: [u8; 11] = [
+        0xA9, 0x3F, 0x8D, 0x00, 0x40, 0xE8, 0x86, 0x10, 0x4C, 0x05, 0x00,
+    ];
+
+    pub(crate) fn nop_cartridge_bus() -> Bus {
+        // a cartridge full of NOPs, with chr for the ppu to draw from
+        let mut bus = Bus::default();
+        bus.cartridge.screen_mirroring = Mirroring::Vertical;
+        bus.cartridge.prg_rom = vec![0xEA; 0x8000];
+        bus.cartridge.chr_rom = vec![0; 0x2000];
+        bus
+    }
+}
+
+#[cfg(feature = "sdl")]
 #[macro_export]
 macro_rules! generate_texture_canvas_event_pump {
     ($texture: ident, $canvas: ident, $event_pump: ident) => {
+        $crate::generate_texture_canvas_event_pump!($texture, $canvas, $event_pump, sdl_context);
+    };
+    // the sdl context is exposed for those who need other subsystems (like audio)
+    ($texture: ident, $canvas: ident, $event_pump: ident, $sdl_context: ident) => {
+        $crate::generate_texture_canvas_event_pump!(
+            $texture,
+            $canvas,
+            $event_pump,
+            $sdl_context,
+            true
+        );
+    };
+    // vsync can be turned off, for those who pace the frames themselves
+    ($texture: ident, $canvas: ident, $event_pump: ident, $sdl_context: ident, $vsync: expr) => {
         let screen_width = $crate::ppu::SCREEN_WIDTH;
         let screen_height = $crate::ppu::SCREEN_HEIGHT;
 
         const SCREEN_FACTOR: usize = 2;
 
-        let sdl_context = sdl2::init().unwrap();
-        let video_subsystem = sdl_context.video().unwrap();
+        let $sdl_context = sdl2::init().unwrap();
+        let video_subsystem = $sdl_context.video().unwrap();
         let window = video_subsystem
             .window(
                 "Test Frame",
@@ -55,9 +90,13 @@ macro_rules! generate_texture_canvas_event_pump {
             .build()
             .unwrap();
 
-        let mut $event_pump = sdl_context.event_pump().unwrap();
+        let mut $event_pump = $sdl_context.event_pump().unwrap();
 
-        let mut $canvas = window.into_canvas().present_vsync().build().unwrap();
+        let mut canvas_builder = window.into_canvas();
+        if $vsync {
+            canvas_builder = canvas_builder.present_vsync();
+        }
+        let mut $canvas = canvas_builder.build().unwrap();
         $canvas
             .set_scale(SCREEN_FACTOR as f32, SCREEN_FACTOR as f32)
             .unwrap();
@@ -0,0 +1,233 @@
+pub mod rewind;
+#[cfg(test)]
+mod save_state_tests;
+
+use crate::bus::Bus;
+use crate::cpu::CPU;
+use crate::ppu::PPU;
+use anyhow::{bail, Result};
+use std::ops::DerefMut;
+
+static SAVE_STATE_TAG: [u8; 8] = *b"NESSTATE";
+// bump this whenever the layout of any component changes, old states are refused instead of loaded wrong
+pub const SAVE_STATE_VERSION: u32 = 3;
+
+// a component that can write all of its emulation state into a save state, and read it back.
+// things that are not emulation state (the rom, the config, the sdl handles) are not saved
+pub trait SaveState {
+    fn save_state(&self, state: &mut StateWriter);
+    fn load_state(&mut self, state: &mut StateReader) -> Result<()>;
+}
+
+// the save state is a flat little endian binary blob, each component writes its fields in a fixed order
+#[derive(Debug, Default)]
+pub struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i16(&mut self, value: i16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        // fixed size buffers, the reader must know the length
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn write_vec(&mut self, bytes: &[u8]) {
+        // buffers with a size that depends on the game
+        self.write_u32(bytes.len() as u32);
+        self.write_bytes(bytes);
+    }
+}
+
+#[derive(Debug)]
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.data.len() - self.position < length {
+            bail!(
+                "save state is truncated (needed {length} more bytes at offset {})",
+                self.position
+            );
+        }
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => bail!(
+                "save state is corrupted (bool with value {other} at offset {})",
+                self.position - 1
+            ),
+        }
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.take_array()?))
+    }
+
+    pub fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<()> {
+        buffer.copy_from_slice(self.take(buffer.len())?);
+        Ok(())
+    }
+
+    pub fn read_vec(&mut self, buffer: &mut [u8]) -> Result<()> {
+        // the size must match the current buffer, a different size means a different game
+        let length = self.read_u32()? as usize;
+        if length != buffer.len() {
+            bail!(
+                "save state has a buffer of {length} bytes where {} were expected",
+                buffer.len()
+            );
+        }
+        self.read_bytes(buffer)
+    }
+}
+
+fn rom_fingerprint(bus: &Bus) -> u64 {
+    // fnv-1a of the prg rom, so we don't load a state of one game into another
+    bus.cartridge
+        .prg_rom
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+pub fn save<B: DerefMut<Target = Bus>>(cpu: &CPU, ppu: &PPU<B>, bus: &Bus) -> Vec<u8> {
+    // the bus is passed separately, since only one of the cpu and the ppu holds it at a time
+    let mut state = StateWriter::new();
+    state.write_bytes(&SAVE_STATE_TAG);
+    state.write_u32(SAVE_STATE_VERSION);
+    state.write_u64(rom_fingerprint(bus));
+    cpu.save_state(&mut state);
+    ppu.save_state(&mut state);
+    bus.save_state(&mut state);
+    state.into_bytes()
+}
+
+pub fn check_header(data: &[u8], bus: &Bus) -> Result<()> {
+    // validates everything we can before touching the emulator, so a bad file doesn't leave it half loaded
+    let mut state = StateReader::new(data);
+    let mut tag = [0; SAVE_STATE_TAG.len()];
+    if state.read_bytes(&mut tag).is_err() || tag != SAVE_STATE_TAG {
+        bail!("not a save state file");
+    }
+    let version = state.read_u32()?;
+    if version < SAVE_STATE_VERSION {
+        bail!("save state version {version} is older than the supported version {SAVE_STATE_VERSION}, and can't be loaded");
+    }
+    if version > SAVE_STATE_VERSION {
+        bail!("save state version {version} is newer than the supported version {SAVE_STATE_VERSION}, and can't be loaded");
+    }
+    if state.read_u64()? != rom_fingerprint(bus) {
+        bail!("save state was made with a different game");
+    }
+    Ok(())
+}
+
+pub fn load<B: DerefMut<Target = Bus>>(
+    cpu: &mut CPU,
+    ppu: &mut PPU<B>,
+    bus: &mut Bus,
+    data: &[u8],
+) -> Result<()> {
+    check_header(data, bus)?;
+    // a broken body is only found while it is being applied, so we keep the current state to go back to
+    let backup = save(cpu, ppu, bus);
+    let result = load_body(cpu, ppu, bus, data);
+    if result.is_err() {
+        load_body(cpu, ppu, bus, &backup).expect("the state we just saved should load");
+    }
+    result
+}
+
+fn load_body<B: DerefMut<Target = Bus>>(
+    cpu: &mut CPU,
+    ppu: &mut PPU<B>,
+    bus: &mut Bus,
+    data: &[u8],
+) -> Result<()> {
+    let mut state = StateReader::new(&data[SAVE_STATE_TAG.len() + 4 + 8..]);
+    cpu.load_state(&mut state)?;
+    ppu.load_state(&mut state)?;
+    bus.load_state(&mut state)?;
+    if !state.is_at_end() {
+        bail!("save state has trailing data");
+    }
+    Ok(())
+}
@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod rewind_tests;
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// the rewind settings, as written in the config file
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RewindConfig {
+    pub enabled: bool,
+    pub memory_budget_mb: u32,
+    pub snapshot_interval_frames: u32, // a snapshot every N frames, rewinding goes back N frames per frame
+}
+
+impl Default for RewindConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            memory_budget_mb: 64,
+            snapshot_interval_frames: 2,
+        }
+    }
+}
+
+// a history of save states that can be walked backwards.
+// only the newest snapshot is kept whole. every older snapshot is kept as the difference from the snapshot after it,
+// so going back is applying the newest difference, and forgetting the oldest snapshot is just dropping its difference
+#[derive(Debug, Eq, PartialEq)]
+pub struct RewindBuffer {
+    memory_budget: usize,
+    snapshot_interval: u32,
+    frames_since_snapshot: u32,
+    newest: Vec<u8>,
+    older: VecDeque<Vec<u8>>,
+    older_size: usize,
+}
+
+impl RewindBuffer {
+    pub fn new(config: &RewindConfig) -> Self {
+        Self {
+            memory_budget: config.memory_budget_mb as usize * 1024 * 1024,
+            snapshot_interval: config.snapshot_interval_frames.max(1),
+            frames_since_snapshot: 0,
+            newest: Vec::new(),
+            older: VecDeque::new(),
+            older_size: 0,
+        }
+    }
+
+    pub fn number_of_snapshots(&self) -> usize {
+        self.older.len() + !self.newest.is_empty() as usize
+    }
+
+    pub fn memory_used(&self) -> usize {
+        self.newest.len() + self.older_size
+    }
+
+    pub fn snapshot_due(&mut self) -> bool {
+        // should be called once every frame, returns true when it's time to push a snapshot
+        self.frames_since_snapshot += 1;
+        if self.frames_since_snapshot >= self.snapshot_interval {
+            self.frames_since_snapshot = 0;
+            return true;
+        }
+        false
+    }
+
+    pub fn push(&mut self, snapshot: Vec<u8>) {
+        if !self.newest.is_empty() {
+            let delta = encode_delta(&snapshot, &self.newest);
+            self.older_size += delta.len();
+            self.older.push_back(delta);
+        }
+        self.newest = snapshot;
+
+        while self.memory_used() > self.memory_budget {
+            match self.older.pop_front() {
+                Some(delta) => self.older_size -= delta.len(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn rewind(&mut self) -> Option<&[u8]> {
+        // steps one snapshot back, and returns it. when there's nothing older, we stay on the oldest snapshot
+        if let Some(delta) = self.older.pop_back() {
+            self.older_size -= delta.len();
+            self.newest = decode_delta(&self.newest, &delta);
+        }
+        self.frames_since_snapshot = 0;
+        (!self.newest.is_empty()).then_some(self.newest.as_slice())
+    }
+}
+
+// the deltas are the xor of the two snapshots, where the runs of zeros (the unchanged bytes) are squashed.
+// the encoding is a list of (number of unchanged bytes, number of changed bytes, the changed bytes xored).
+// snapshots of different sizes (which shouldn't happen in one game) are just kept whole
+
+const WHOLE_SNAPSHOT: u8 = 0;
+const XOR_DELTA: u8 = 1;
+// a short run of unchanged bytes costs more to encode than to just copy
+const MIN_UNCHANGED_RUN: usize = 4;
+
+fn write_length(output: &mut Vec<u8>, mut length: usize) {
+    // leb128, most lengths fit in one or two bytes
+    loop {
+        let byte = (length & 0x7F) as u8;
+        length >>= 7;
+        if length == 0 {
+            output.push(byte);
+            return;
+        }
+        output.push(byte | 0x80);
+    }
+}
+
+fn read_length(input: &[u8], position: &mut usize) -> usize {
+    let mut length = 0;
+    let mut shift = 0;
+    loop {
+        let byte = input[*position];
+        *position += 1;
+        length |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return length;
+        }
+        shift += 7;
+    }
+}
+
+fn encode_delta(base: &[u8], target: &[u8]) -> Vec<u8> {
+    // returns the delta that turns base into target
+    let mut output = Vec::new();
+    if base.len() != target.len() {
+        output.push(WHOLE_SNAPSHOT);
+        output.extend_from_slice(target);
+        return output;
+    }
+    output.push(XOR_DELTA);
+
+    let unchanged_run = |from: usize| {
+        (from..base.len())
+            .take_while(|i| base[*i] == target[*i])
+            .count()
+    };
+    let mut position = 0;
+    while position < base.len() {
+        let unchanged = unchanged_run(position);
+        let changed_start = position + unchanged;
+        let mut changed_end = changed_start;
+        while changed_end < base.len() {
+            let run = unchanged_run(changed_end);
+            if run >= MIN_UNCHANGED_RUN || changed_end + run == base.len() {
+                break;
+            }
+            changed_end += run.max(1);
+        }
+        write_length(&mut output, unchanged);
+        write_length(&mut output, changed_end - changed_start);
+        output.extend((changed_start..changed_end).map(|i| base[i] ^ target[i]));
+        position = changed_end;
+    }
+    output
+}
+
+fn decode_delta(base: &[u8], delta: &[u8]) -> Vec<u8> {
+    if delta[0] == WHOLE_SNAPSHOT {
+        return delta[1..].to_vec();
+    }
+    let mut output = base.to_vec();
+    let mut position = 1;
+    let mut offset = 0;
+    while position < delta.len() {
+        offset += read_length(delta, &mut position);
+        let changed = read_length(delta, &mut position);
+        for byte in &delta[position..position + changed] {
+            output[offset] ^= byte;
+            offset += 1;
+        }
+        position += changed;
+    }
+    output
+}
@@ -0,0 +1,88 @@
+use crate::save_state::rewind::{decode_delta, encode_delta, RewindBuffer, RewindConfig};
+
+fn snapshot(frame: u8) -> Vec<u8> {
+    // mostly the same from frame to frame, like the real save states
+    let mut snapshot = vec![0x55; 4096];
+    snapshot[10] = frame;
+    snapshot[11] = frame.wrapping_mul(3);
+    snapshot[2000..2010].fill(frame);
+    snapshot[4095] = frame ^ 0xFF;
+    snapshot
+}
+
+#[test]
+fn test_delta_round_trip() {
+    let base = snapshot(1);
+    let target = snapshot(2);
+    let delta = encode_delta(&base, &target);
+    assert!(delta.len() < 64);
+    assert_eq!(decode_delta(&base, &delta), target);
+
+    // nothing changed
+    let delta = encode_delta(&base, &base);
+    assert_eq!(decode_delta(&base, &delta), base);
+
+    // everything changed
+    let inverted: Vec<u8> = base.iter().map(|b| !b).collect();
+    assert_eq!(
+        decode_delta(&base, &encode_delta(&base, &inverted)),
+        inverted
+    );
+
+    // a different size is kept whole
+    let smaller = vec![1, 2, 3];
+    assert_eq!(decode_delta(&base, &encode_delta(&base, &smaller)), smaller);
+}
+
+#[test]
+fn test_rewind_goes_back_in_order() {
+    let mut buffer = RewindBuffer::new(&RewindConfig::default());
+    assert_eq!(buffer.rewind(), None);
+    for frame in 0..10 {
+        buffer.push(snapshot(frame));
+    }
+    assert_eq!(buffer.number_of_snapshots(), 10);
+
+    for frame in (0..9).rev() {
+        assert_eq!(buffer.rewind().unwrap(), snapshot(frame).as_slice());
+    }
+    // stays on the oldest snapshot
+    assert_eq!(buffer.rewind().unwrap(), snapshot(0).as_slice());
+    assert_eq!(buffer.number_of_snapshots(), 1);
+
+    // the history continues from where we rewound to
+    buffer.push(snapshot(42));
+    assert_eq!(buffer.rewind().unwrap(), snapshot(0).as_slice());
+}
+
+#[test]
+fn test_rewind_memory_budget() {
+    let mut buffer = RewindBuffer::new(&RewindConfig {
+        memory_budget_mb: 1,
+        ..Default::default()
+    });
+    // random-ish snapshots, so the deltas are as big as the snapshots
+    let mut value: u32 = 1;
+    for _ in 0..1000 {
+        let snapshot: Vec<u8> = (0..4096)
+            .map(|_| {
+                value = value.wrapping_mul(1103515245).wrapping_add(12345);
+                (value >> 16) as u8
+            })
+            .collect();
+        buffer.push(snapshot);
+        assert!(buffer.memory_used() <= 1024 * 1024);
+    }
+    assert!(buffer.number_of_snapshots() > 200);
+    assert!(buffer.number_of_snapshots() < 1000);
+}
+
+#[test]
+fn test_snapshot_interval() {
+    let mut buffer = RewindBuffer::new(&RewindConfig {
+        snapshot_interval_frames: 3,
+        ..Default::default()
+    });
+    let due: Vec<bool> = (0..7).map(|_| buffer.snapshot_due()).collect();
+    assert_eq!(due, [false, false, true, false, false, true, false]);
+}
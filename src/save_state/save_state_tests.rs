@@ -0,0 +1,126 @@
+use crate::bus::Bus;
+use crate::cpu::CPU;
+use crate::nes::Nes;
+use crate::ppu::PPU;
+use crate::prelude::test_fixtures::COUNTER_PROGRAM;
+use crate::save_state::{self, StateReader, StateWriter, SAVE_STATE_VERSION};
+
+fn run_cycles(cpu: &mut CPU, cycles: usize) {
+    for _ in 0..cycles {
+        cpu.run_one_cycle();
+    }
+}
+
+fn save_with_cpu_holding_bus(cpu: &mut CPU, ppu: &PPU<&mut Bus>) -> Vec<u8> {
+    let bus = cpu.bus.take().unwrap();
+    let state = save_state::save(cpu, ppu, bus);
+    cpu.bus = Some(bus);
+    state
+}
+
+#[test]
+fn test_writer_and_reader_round_trip() {
+    let mut writer = StateWriter::new();
+    writer.write_u8(0xAB);
+    writer.write_bool(true);
+    writer.write_u16(0x1234);
+    writer.write_i16(-341);
+    writer.write_u32(0xDEADBEEF);
+    writer.write_u64(u64::MAX - 1);
+    writer.write_f32(-0.25);
+    writer.write_vec(&[1, 2, 3]);
+    let bytes = writer.into_bytes();
+
+    let mut reader = StateReader::new(&bytes);
+    assert_eq!(reader.read_u8().unwrap(), 0xAB);
+    assert!(reader.read_bool().unwrap());
+    assert_eq!(reader.read_u16().unwrap(), 0x1234);
+    assert_eq!(reader.read_i16().unwrap(), -341);
+    assert_eq!(reader.read_u32().unwrap(), 0xDEADBEEF);
+    assert_eq!(reader.read_u64().unwrap(), u64::MAX - 1);
+    assert_eq!(reader.read_f32().unwrap(), -0.25);
+    let mut buffer = vec![0; 3];
+    reader.read_vec(&mut buffer).unwrap();
+    assert_eq!(buffer, vec![1, 2, 3]);
+    assert!(reader.is_at_end());
+    assert!(reader.read_u8().is_err());
+}
+
+#[test]
+fn test_load_restores_the_emulation() {
+    let mut bus = Bus::default();
+    bus.cpu_ram[..COUNTER_PROGRAM.len()].copy_from_slice(&COUNTER_PROGRAM);
+    let mut cpu = CPU::new(&mut bus);
+    let bus_ref = cpu.bus.take().unwrap();
+    let mut ppu = PPU::new(bus_ref);
+    cpu.bus = ppu.bus.take();
+    cpu.program_counter = 0;
+
+    run_cycles(&mut cpu, 1000);
+    let state = save_with_cpu_holding_bus(&mut cpu, &ppu);
+    let register_x = cpu.register_x;
+
+    run_cycles(&mut cpu, 500);
+    let state_after = save_with_cpu_holding_bus(&mut cpu, &ppu);
+    assert_ne!(cpu.register_x, register_x);
+
+    let bus = cpu.bus.take().unwrap();
+    save_state::load(&mut cpu, &mut ppu, bus, &state).unwrap();
+    cpu.bus = Some(bus);
+    assert_eq!(cpu.register_x, register_x);
+    assert_eq!(save_with_cpu_holding_bus(&mut cpu, &ppu), state);
+
+    // the emulation continues exactly like it did the first time
+    run_cycles(&mut cpu, 500);
+    assert_eq!(save_with_cpu_holding_bus(&mut cpu, &ppu), state_after);
+}
+
+#[test]
+fn test_refuse_other_versions_and_games() {
+    let mut bus = Bus::default();
+    let mut cpu = CPU::new(&mut bus);
+    let bus_ref = cpu.bus.take().unwrap();
+    let mut ppu = PPU::new(bus_ref);
+    let bus = ppu.bus.take().unwrap();
+    let state = save_state::save(&cpu, &ppu, bus);
+
+    let mut old_state = state.clone();
+    old_state[8..12].copy_from_slice(&(SAVE_STATE_VERSION - 1).to_le_bytes());
+    let error = save_state::load(&mut cpu, &mut ppu, bus, &old_state).unwrap_err();
+    assert!(error.to_string().contains("is older than"));
+
+    let mut new_state = state.clone();
+    new_state[8..12].copy_from_slice(&(SAVE_STATE_VERSION + 1).to_le_bytes());
+    let error = save_state::load(&mut cpu, &mut ppu, bus, &new_state).unwrap_err();
+    assert!(error.to_string().contains("is newer than"));
+
+    assert!(save_state::load(&mut cpu, &mut ppu, bus, b"not a state").is_err());
+    assert!(save_state::load(&mut cpu, &mut ppu, bus, &state[..state.len() - 1]).is_err());
+
+    bus.cartridge.prg_rom = vec![0xEA; 0x8000];
+    let error = save_state::load(&mut cpu, &mut ppu, bus, &state).unwrap_err();
+    assert!(error.to_string().contains("different game"));
+}
+
+#[test]
+fn test_broken_state_leaves_the_emulator_alone() {
+    let mut nes = Nes::from_rom(include_bytes!("../../tests/nestest.nes"));
+    for _ in 0..10_000 {
+        nes.step_cycle();
+    }
+    let state = nes.save_state();
+    for _ in 0..10_000 {
+        nes.step_cycle();
+    }
+    let before = nes.save_state();
+
+    let mut trailing = state.clone();
+    trailing.push(0);
+    // cut in the cpu, in the bus, and one byte too many
+    for broken in [&state[..30], &state[..state.len() - 100], &trailing[..]] {
+        assert!(nes.load_state(broken).is_err());
+        assert_eq!(nes.save_state(), before);
+    }
+    nes.load_state(&state).unwrap();
+    assert_eq!(nes.save_state(), state);
+}
@@ -0,0 +1,348 @@
+#[cfg(test)]
+mod symbols_tests;
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const PRG_ROM_START: u16 = 0x8000;
+const PRG_RAM_START: u16 = 0x6000;
+const PRG_BANK_SIZE: usize = 0x4000;
+const INES_HEADER_SIZE: usize = 16;
+
+// the labels of addresses, by the address the cpu sees, or by the offset in the prg rom
+pub trait Labels {
+    fn label(&self, address: u16) -> Option<&str>;
+
+    fn address_of(&self, name: &str) -> Option<u16>;
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct RomLabel {
+    name: String,
+    address: Option<u16>, // where the code was assembled to, if the file says
+}
+
+impl RomLabel {
+    fn address_or_guess(&self, offset: usize) -> u16 {
+        self.address
+            .unwrap_or(PRG_ROM_START + (offset % 0x8000) as u16)
+    }
+}
+
+// names for addresses, from the assembler or the debugger of another emulator. the labels in the rom are kept
+// by their offset in the prg rom, so that the banks of a mapper that share the same addresses don't mix
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Symbols {
+    labels: BTreeMap<u16, String>,
+    rom_labels: BTreeMap<usize, RomLabel>,
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, address: u16, name: &str) {
+        self.labels.insert(address, name.to_string());
+    }
+
+    pub fn insert_rom(&mut self, offset: usize, address: Option<u16>, name: &str) {
+        // a label in the prg rom at the offset, that the cpu sees at the address (if it is known) when its bank is
+        // mapped in
+        let name = name.to_string();
+        self.rom_labels.insert(offset, RomLabel { name, address });
+    }
+
+    pub fn extend(&mut self, other: Symbols) {
+        self.labels.extend(other.labels);
+        self.rom_labels.extend(other.rom_labels);
+    }
+
+    pub fn rom_label(&self, offset: usize) -> Option<&str> {
+        self.rom_labels
+            .get(&offset)
+            .map(|label| label.name.as_str())
+    }
+
+    pub fn mapped<F: Fn(u16) -> Option<usize>>(&self, prg_rom_offset: F) -> Mapped<'_, F> {
+        // the labels the cpu sees with the banks that prg_rom_offset maps in (like Bus::prg_rom_offset)
+        Mapped {
+            symbols: self,
+            prg_rom_offset,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len() + self.rom_labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {path:?}"))?;
+        let symbols = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("nl") => match nl_bank(path) {
+                Some(bank) => Self::from_nl_bank(&text, bank),
+                None => Self::from_nl(&text),
+            },
+            Some("mlb") => Self::from_mlb(&text),
+            Some("dbg") => Self::from_dbg(&text),
+            _ => bail!("unknown symbol file {path:?}, try a .nl, .mlb or .dbg file"),
+        };
+        symbols.with_context(|| format!("in {path:?}"))
+    }
+
+    pub fn from_nl(text: &str) -> Result<Self> {
+        // fceux's format, a line for each address: $C000#name#comment (the comment is optional)
+        let mut symbols = Self::new();
+        for (address, name) in parse_nl(text)? {
+            symbols.insert(address, &name);
+        }
+        Ok(symbols)
+    }
+
+    pub fn from_nl_bank(text: &str, bank: usize) -> Result<Self> {
+        // fceux writes a .nl file for each 16KB prg bank (game.nes.0.nl, game.nes.1.nl...) with the addresses
+        // where the bank was mapped, and game.nes.ram.nl for the rest
+        let mut symbols = Self::new();
+        for (address, name) in parse_nl(text)? {
+            if address < PRG_ROM_START {
+                symbols.insert(address, &name);
+            } else {
+                let offset = bank * PRG_BANK_SIZE + address as usize % PRG_BANK_SIZE;
+                symbols.insert_rom(offset, Some(address), &name);
+            }
+        }
+        Ok(symbols)
+    }
+
+    pub fn from_mlb(text: &str) -> Result<Self> {
+        // mesen's format, a line for each label: P:1234:name:comment, where the letter is the memory and the
+        // number the offset in it (hex). mesen 2 writes the whole name of the memory, like NesPrgRom:1234:name
+        let mut symbols = Self::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(4, ':');
+            let (Some(memory), Some(offset), Some(name)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                bail!("line {}: {line:?} is not memory:offset:name", i + 1);
+            };
+            // a range (R:0200-02FF:buffer) is named by its first byte
+            let offset = offset.split('-').next().unwrap_or_default();
+            let offset = usize::from_str_radix(offset, 16)
+                .with_context(|| format!("line {}: bad offset in {line:?}", i + 1))?;
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            match memory {
+                "P" | "NesPrgRom" => symbols.insert_rom(offset, None, name),
+                "R" | "NesInternalRam" | "G" | "NesMemory" => symbols.insert(offset as u16, name),
+                "W" | "S" | "NesWorkRam" | "NesSaveRam" => {
+                    symbols.insert(PRG_RAM_START + offset as u16, name)
+                }
+                // the ppu memories and the rest aren't in the cpu's way
+                _ => {}
+            }
+        }
+        Ok(symbols)
+    }
+
+    pub fn from_dbg(text: &str) -> Result<Self> {
+        // the debug info of ld65 (ld65 --dbgfile), lines like
+        //   seg id=1,name="CODE",start=0x00C000,size=0x0100,addrsize=absolute,type=ro,oname="game.nes",ooffs=16
+        //   sym id=5,name="NMI_handler",addrsize=absolute,scope=0,def=12,val=0xC010,seg=1,type=lab
+        // the rom segments are placed by their offset in the output file (after the ines header). only the
+        // labels are taken, the constants (type=equ) aren't addresses
+        struct Segment {
+            start: usize,
+            rom_offset: Option<usize>,
+        }
+        let mut segments = BTreeMap::new();
+        let mut labels = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let Some((kind, fields)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            let fields = dbg_fields(fields);
+            let number = |key: &str| -> Result<Option<usize>> {
+                fields
+                    .get(key)
+                    .map(|value| parse_dbg_number(value))
+                    .transpose()
+                    .with_context(|| format!("line {}: bad {key}", i + 1))
+            };
+            match kind {
+                "seg" => {
+                    let Some(id) = number("id")? else {
+                        bail!("line {}: a segment without an id", i + 1);
+                    };
+                    let start = number("start")?.unwrap_or_default();
+                    let rom_offset = number("ooffs")?
+                        .filter(|offset| {
+                            *offset >= INES_HEADER_SIZE && start >= PRG_ROM_START as usize
+                        })
+                        .map(|offset| offset - INES_HEADER_SIZE);
+                    segments.insert(id, Segment { start, rom_offset });
+                }
+                "sym" if fields.get("type").map(String::as_str) == Some("lab") => {
+                    let (Some(name), Some(value)) = (fields.get("name"), number("val")?) else {
+                        bail!("line {}: a label without a name or a value", i + 1);
+                    };
+                    labels.push((name.clone(), value, number("seg")?));
+                }
+                _ => {}
+            }
+        }
+
+        let mut symbols = Self::new();
+        for (name, value, segment) in labels {
+            let address = value as u16;
+            let rom_offset = segment
+                .and_then(|id| segments.get(&id))
+                .and_then(|segment| Some(segment.rom_offset? + value.checked_sub(segment.start)?));
+            // a scope and a label on the same address, the cheap local (@loop) is the one to lose
+            let taken = match rom_offset {
+                Some(offset) => symbols.rom_label(offset),
+                None => symbols.labels.get(&address).map(String::as_str),
+            };
+            if taken.is_some_and(|taken| !taken.starts_with('@')) {
+                continue;
+            }
+            match rom_offset {
+                Some(offset) => symbols.insert_rom(offset, Some(address), &name),
+                None => symbols.insert(address, &name),
+            }
+        }
+        Ok(symbols)
+    }
+}
+
+impl Labels for Symbols {
+    // without the mapper, a label in the rom goes with the address it was assembled to (or the first one the
+    // offset could be at)
+    fn label(&self, address: u16) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str).or_else(|| {
+            self.rom_labels
+                .iter()
+                .find(|(offset, label)| label.address_or_guess(**offset) == address)
+                .map(|(_, label)| label.name.as_str())
+        })
+    }
+
+    fn address_of(&self, name: &str) -> Option<u16> {
+        self.labels
+            .iter()
+            .find(|(_, label)| *label == name)
+            .map(|(address, _)| *address)
+            .or_else(|| {
+                self.rom_labels
+                    .iter()
+                    .find(|(_, label)| label.name == name)
+                    .map(|(offset, label)| label.address_or_guess(*offset))
+            })
+    }
+}
+
+// the labels as the cpu sees them, with the prg banks that are mapped in
+pub struct Mapped<'a, F: Fn(u16) -> Option<usize>> {
+    symbols: &'a Symbols,
+    prg_rom_offset: F,
+}
+
+impl<F: Fn(u16) -> Option<usize>> Labels for Mapped<'_, F> {
+    fn label(&self, address: u16) -> Option<&str> {
+        // the labels by the cpu address (like a .nl file without a bank) go with any bank
+        (self.prg_rom_offset)(address)
+            .and_then(|offset| self.symbols.rom_label(offset))
+            .or_else(|| self.symbols.labels.get(&address).map(String::as_str))
+    }
+
+    fn address_of(&self, name: &str) -> Option<u16> {
+        // where the label is now, or where it was assembled to when its bank isn't mapped in. a bank can be seen
+        // at two addresses (a 16KB rom without a mapper), the one it was assembled to, or else the higher one
+        // (where the vectors are) is where the code runs
+        let Some((offset, label)) = self
+            .symbols
+            .rom_labels
+            .iter()
+            .find(|(_, label)| label.name == name)
+        else {
+            return self.symbols.address_of(name);
+        };
+        let is_there = |address: u16| (self.prg_rom_offset)(address) == Some(*offset);
+        if let Some(address) = label.address.filter(|address| is_there(*address)) {
+            return Some(address);
+        }
+        (PRG_ROM_START..=u16::MAX)
+            .rev()
+            .find(|address| is_there(*address))
+            .or(Some(label.address_or_guess(*offset)))
+    }
+}
+
+fn parse_nl(text: &str) -> Result<Vec<(u16, String)>> {
+    let mut labels = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('#');
+        let address = fields.next().unwrap_or_default();
+        let name = fields.next().unwrap_or_default().trim();
+        let Some(address) = address.strip_prefix('$') else {
+            bail!("line {}: {line:?} doesn't start with an address", i + 1);
+        };
+        // fceux also allows $0200/10 for arrays, the name goes on the first address
+        let address = address.split('/').next().unwrap_or_default();
+        let address = u16::from_str_radix(address, 16)
+            .with_context(|| format!("line {}: bad address in {line:?}", i + 1))?;
+        if !name.is_empty() {
+            labels.push((address, name.to_string()));
+        }
+    }
+    Ok(labels)
+}
+
+fn nl_bank(path: &Path) -> Option<usize> {
+    // game.nes.3.nl is the file of the prg bank 3
+    let stem = path.file_stem()?.to_str()?;
+    let (_, bank) = stem.rsplit_once('.')?;
+    bank.parse().ok()
+}
+
+fn dbg_fields(text: &str) -> BTreeMap<String, String> {
+    // key=value pairs split by commas, the strings are quoted (and can have commas in them)
+    let mut fields = BTreeMap::new();
+    let mut rest = text.trim();
+    while let Some((key, after_key)) = rest.split_once('=') {
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                let after = quoted.get(end + 1..).unwrap_or_default();
+                (&quoted[..end], after.strip_prefix(',').unwrap_or(after))
+            }
+            None => after_key.split_once(',').unwrap_or((after_key, "")),
+        };
+        fields.insert(key.trim().to_string(), value.to_string());
+        rest = after_value;
+    }
+    fields
+}
+
+fn parse_dbg_number(text: &str) -> Result<usize> {
+    match text.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => text.parse(),
+    }
+    .with_context(|| format!("bad number {text:?}"))
+}
@@ -0,0 +1,135 @@
+use crate::symbols::{Labels, Symbols};
+use std::path::Path;
+
+#[test]
+fn test_nl_file() {
+    let symbols = Symbols::from_nl(
+        "$C000#reset#the entry point\n\
+         $0200/10#player_x#\n\
+         \n\
+         $C05E#nmi\n\
+         $C100##only a comment\n",
+    )
+    .unwrap();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols.label(0xC000), Some("reset"));
+    assert_eq!(symbols.label(0x0200), Some("player_x"));
+    assert_eq!(symbols.label(0xC05E), Some("nmi"));
+    assert_eq!(symbols.label(0xC100), None);
+    assert_eq!(symbols.address_of("nmi"), Some(0xC05E));
+}
+
+#[test]
+fn test_bad_nl_file() {
+    assert!(Symbols::from_nl("C000#reset#").is_err());
+    assert!(Symbols::from_nl("$C0G0#reset#").is_err());
+}
+
+#[test]
+fn test_nl_bank_files() {
+    // two banks that the game switches in at 8000
+    let mut symbols = Symbols::from_nl_bank("$8000#title#\n$0010#temp#\n", 0).unwrap();
+    symbols.extend(Symbols::from_nl_bank("$8000#level#\n$BFFA#vectors#\n", 1).unwrap());
+    assert_eq!(symbols.rom_label(0x0000), Some("title"));
+    assert_eq!(symbols.rom_label(0x4000), Some("level"));
+    assert_eq!(symbols.rom_label(0x7FFA), Some("vectors"));
+    assert_eq!(symbols.rom_label(0x8000), None);
+    assert_eq!(symbols.label(0x0010), Some("temp"));
+
+    let bank_1 =
+        symbols.mapped(|address| (address >= 0x8000).then(|| 0x4000 + (address as usize & 0x3FFF)));
+    assert_eq!(bank_1.label(0x8000), Some("level"));
+    assert_eq!(bank_1.label(0x0010), Some("temp"));
+    assert_eq!(bank_1.address_of("level"), Some(0x8000));
+    // title isn't mapped in, so it is where the file says
+    assert_eq!(bank_1.address_of("title"), Some(0x8000));
+    let bank_0 = symbols.mapped(|address| (address >= 0x8000).then_some(address as usize & 0x3FFF));
+    assert_eq!(bank_0.label(0x8000), Some("title"));
+}
+
+#[test]
+fn test_mlb_file() {
+    let symbols = Symbols::from_mlb(
+        "P:0010:NMI_handler:the nmi: it does everything\n\
+         NesPrgRom:4000:level_data\n\
+         R:0200-02FF:oam_buffer\n\
+         NesInternalRam:0010:frame\n\
+         G:2000:PPUCTRL\n\
+         W:0000:save_slot\n\
+         P:0020::only a comment\n\
+         NesChrRom:0000:tiles\n",
+    )
+    .unwrap();
+    assert_eq!(symbols.len(), 6);
+    assert_eq!(symbols.rom_label(0x0010), Some("NMI_handler"));
+    assert_eq!(symbols.rom_label(0x4000), Some("level_data"));
+    assert_eq!(symbols.label(0x0200), Some("oam_buffer"));
+    assert_eq!(symbols.label(0x0010), Some("frame"));
+    assert_eq!(symbols.label(0x2000), Some("PPUCTRL"));
+    assert_eq!(symbols.label(0x6000), Some("save_slot"));
+
+    // a 16KB rom is at 8000 and at C000
+    let nrom = symbols.mapped(|address| (address >= 0x8000).then_some(address as usize & 0x3FFF));
+    assert_eq!(nrom.label(0x8010), Some("NMI_handler"));
+    assert_eq!(nrom.label(0xC010), Some("NMI_handler"));
+    assert_eq!(nrom.address_of("NMI_handler"), Some(0xC010));
+    // level_data isn't mapped in
+    assert_eq!(nrom.address_of("level_data"), Some(0xC000));
+
+    assert!(Symbols::from_mlb("P:0010").is_err());
+    assert!(Symbols::from_mlb("P:00G0:name").is_err());
+}
+
+#[test]
+fn test_dbg_file() {
+    let symbols = Symbols::from_dbg(
+        "version\tmajor=2,minor=0\n\
+         file\tid=0,name=\"game, the first.s\",size=1000,mtime=0x60000000,mod=0\n\
+         seg\tid=0,name=\"HEADER\",start=0x000000,size=0x0010,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=0\n\
+         seg\tid=1,name=\"CODE\",start=0x00C000,size=0x0100,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=16\n\
+         seg\tid=2,name=\"BANK1\",start=0x008000,size=0x4000,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=16400\n\
+         seg\tid=3,name=\"ZEROPAGE\",start=0x000000,size=0x0004,addrsize=zeropage,type=rw\n\
+         sym\tid=0,name=\"reset\",addrsize=absolute,scope=0,def=1,ref=2,val=0xC000,seg=1,type=lab\n\
+         sym\tid=1,name=\"@loop\",addrsize=absolute,scope=0,def=3,val=0xC010,seg=1,type=lab\n\
+         sym\tid=2,name=\"NMI_handler\",addrsize=absolute,scope=0,def=4,val=0xC010,seg=1,type=lab\n\
+         sym\tid=3,name=\"level\",addrsize=absolute,scope=0,def=5,val=0x8000,seg=2,type=lab\n\
+         sym\tid=4,name=\"frame\",addrsize=zeropage,size=1,scope=0,def=6,val=0x2,seg=3,type=lab\n\
+         sym\tid=5,name=\"BUTTON_A\",addrsize=zeropage,scope=0,def=7,val=0x80,type=equ\n",
+    )
+    .unwrap();
+    assert_eq!(symbols.len(), 4);
+    assert_eq!(symbols.rom_label(0x0000), Some("reset"));
+    assert_eq!(symbols.rom_label(0x0010), Some("NMI_handler"));
+    assert_eq!(symbols.rom_label(0x4000), Some("level"));
+    assert_eq!(symbols.label(0x0002), Some("frame"));
+    assert_eq!(symbols.label(0x0080), None);
+    // without the mapper they are where they were assembled to
+    assert_eq!(symbols.label(0xC010), Some("NMI_handler"));
+    assert_eq!(symbols.address_of("level"), Some(0x8000));
+
+    assert!(Symbols::from_dbg("sym\tid=0,name=\"a\",val=0xZZ,type=lab\n").is_err());
+}
+
+#[test]
+fn test_load() {
+    let dir = std::env::temp_dir().join(format!("nes_symbols_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |name: &str, text: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    };
+
+    let bank = Symbols::load(&write("game.nes.1.nl", "$8000#level#\n")).unwrap();
+    assert_eq!(bank.rom_label(0x4000), Some("level"));
+    let ram = Symbols::load(&write("game.nes.ram.nl", "$0010#frame#\n")).unwrap();
+    assert_eq!(ram.label(0x0010), Some("frame"));
+    let mlb = Symbols::load(&write("game.mlb", "R:0010:frame\n")).unwrap();
+    assert_eq!(mlb, ram);
+    let dbg = Symbols::load(&write("game.dbg", "")).unwrap();
+    assert!(dbg.is_empty());
+
+    assert!(Symbols::load(&write("game.sym", "")).is_err());
+    assert!(Symbols::load(Path::new("does/not/exist.nl")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
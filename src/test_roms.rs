@@ -0,0 +1,133 @@
+use crate::nes::Nes;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+// blargg's test roms (and many others since) report their result in the cartridge ram:
+// 0x6000 is the status, 0x6001-0x6003 hold DE B0 61 once the rest is valid, and 0x6004 starts a zero terminated message.
+// see https://github.com/christopherpow/nes-test-roms/blob/master/instr_test-v5/readme.txt
+const STATUS_OFFSET: usize = 0; // the offsets are from the start of the prg ram (0x6000)
+const SIGNATURE_OFFSET: usize = 1;
+const MESSAGE_OFFSET: usize = 4;
+const SIGNATURE: [u8; 3] = [0xDE, 0xB0, 0x61];
+const STATUS_RUNNING: u8 = 0x80;
+const STATUS_NEEDS_RESET: u8 = 0x81;
+// the rom asks to press reset, and wants at least 100ms before it happens
+const FRAMES_BEFORE_RESET: u64 = 6;
+pub const DEFAULT_TIMEOUT_FRAMES: u64 = 60 * 60;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum TestRomStatus {
+    Passed,
+    Failed(u8),      // the final status, every value but 0 is a failure
+    TimedOut,        // the status never became final
+    Crashed(String), // the emulator panicked (usually an unsupported mapper or opcode)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TestRomResult {
+    pub status: TestRomStatus,
+    pub message: String, // what the rom wrote at 0x6004, if it ever wrote the signature
+    pub frames: u64,
+}
+
+impl TestRomResult {
+    pub fn passed(&self) -> bool {
+        self.status == TestRomStatus::Passed
+    }
+}
+
+impl fmt::Display for TestRomResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            TestRomStatus::Passed => write!(f, "passed")?,
+            TestRomStatus::Failed(code) => write!(f, "failed with status {code}")?,
+            TestRomStatus::TimedOut => write!(f, "timed out after {} frames", self.frames)?,
+            TestRomStatus::Crashed(reason) => write!(f, "crashed: {reason}")?,
+        }
+        let message = self.message.trim();
+        if !message.is_empty() {
+            write!(f, "\n{message}")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_test_rom(rom: &[u8], timeout_frames: u64) -> TestRomResult {
+    // runs the rom until its status is final, pressing reset when it asks for it
+    let mut frames = 0;
+    let mut message = String::new();
+    let run = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut nes = Nes::from_rom(rom);
+        let mut reset_at = None;
+        while frames < timeout_frames {
+            nes.run_frame();
+            frames += 1;
+            let prg_ram = &nes.bus().prg_ram;
+            if prg_ram[SIGNATURE_OFFSET..MESSAGE_OFFSET] != SIGNATURE {
+                continue;
+            }
+            message = read_message(prg_ram);
+            match prg_ram[STATUS_OFFSET] {
+                STATUS_RUNNING => {}
+                STATUS_NEEDS_RESET => match reset_at {
+                    None => reset_at = Some(frames + FRAMES_BEFORE_RESET),
+                    Some(frame) if frame <= frames => {
+                        nes.reset();
+                        reset_at = None;
+                    }
+                    Some(_) => {}
+                },
+                0 => return TestRomStatus::Passed,
+                code => return TestRomStatus::Failed(code),
+            }
+        }
+        TestRomStatus::TimedOut
+    }));
+    let status = run.unwrap_or_else(|reason| {
+        let reason = reason
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| {
+                reason
+                    .downcast_ref::<&str>()
+                    .map(|reason| reason.to_string())
+            })
+            .unwrap_or_default();
+        TestRomStatus::Crashed(reason)
+    });
+    TestRomResult {
+        status,
+        message,
+        frames,
+    }
+}
+
+fn read_message(prg_ram: &[u8]) -> String {
+    let message = &prg_ram[MESSAGE_OFFSET..];
+    let end = message
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(message.len());
+    String::from_utf8_lossy(&message[..end]).into_owned()
+}
+
+pub fn find_test_roms(dir: &Path) -> Result<Vec<PathBuf>> {
+    // every .nes file under the directory (the suites keep their roms in sub directories), sorted
+    let mut roms = Vec::new();
+    let entries = std::fs::read_dir(dir).with_context(|| format!("failed to read {dir:?}"))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            roms.extend(find_test_roms(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("nes"))
+        {
+            roms.push(path);
+        }
+    }
+    roms.sort();
+    Ok(roms)
+}
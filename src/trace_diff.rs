@@ -0,0 +1,382 @@
+#[cfg(test)]
+mod trace_diff_tests;
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+// the b and the unused flag only exist on the stack, and every emulator shows them differently
+const STATUS_MASK: u8 = 0b1100_1111;
+const PRE_RENDER_SCANLINE: i64 = 261;
+
+static NESTEST_PC_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([0-9A-F]{4})  [0-9A-F]{2} ").unwrap());
+static MESEN_PC_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*\$?([0-9A-Fa-f]{4})[\s:]").unwrap());
+static FCEUX_PC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$([0-9A-Fa-f]{4}):").unwrap());
+static REGISTERS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bA:([0-9A-Fa-f]{2})\s+X:([0-9A-Fa-f]{2})\s+Y:([0-9A-Fa-f]{2})\b").unwrap()
+});
+static STATUS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bP:([0-9A-Fa-f]{2}\b|[nNvVuUbBdDiIzZcC-]{8})").unwrap());
+static STACK_POINTER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bSP?:([0-9A-Fa-f]{2})\b").unwrap());
+// (scanline, dot) in nestest and mesen 2, and (dot, scanline) in the older nestest and mesen logs
+static PPU_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:PPU:\s*(-?\d+),\s*(-?\d+)|V:\s*(-?\d+)\s+H:\s*(-?\d+))").unwrap()
+});
+static OLD_PPU_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\bCYC:\s*(\d+)\s+SL:\s*(-?\d+)").unwrap());
+static CYCLES_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:\bPPU:\s*-?\d+,\s*-?\d+\s+CYC:|\bCycle:\s*|^c)(\d+)\b").unwrap());
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum TraceFormat {
+    Nestest, // what our tracer writes too
+    Mesen,
+    Fceux,
+}
+
+impl TraceFormat {
+    pub fn detect(line: &str) -> Option<Self> {
+        if !REGISTERS_REGEX.is_match(line) {
+            return None;
+        }
+        if NESTEST_PC_REGEX.is_match(line) {
+            Some(Self::Nestest)
+        } else if FCEUX_PC_REGEX.is_match(line) {
+            Some(Self::Fceux)
+        } else if MESEN_PC_REGEX.is_match(line) {
+            Some(Self::Mesen)
+        } else {
+            None
+        }
+    }
+
+    pub fn parse_line(self, line: &str) -> Option<TraceEntry> {
+        let pc_regex = match self {
+            Self::Nestest => &NESTEST_PC_REGEX,
+            Self::Fceux => &FCEUX_PC_REGEX,
+            Self::Mesen => &MESEN_PC_REGEX,
+        };
+        let registers = REGISTERS_REGEX.captures(line)?;
+        Some(TraceEntry {
+            pc: u16::from_str_radix(&pc_regex.captures(line)?[1], 16).ok()?,
+            a: u8::from_str_radix(&registers[1], 16).ok()?,
+            x: u8::from_str_radix(&registers[2], 16).ok()?,
+            y: u8::from_str_radix(&registers[3], 16).ok()?,
+            p: parse_status(&STATUS_REGEX.captures(line)?[1])?,
+            sp: u8::from_str_radix(&STACK_POINTER_REGEX.captures(line)?[1], 16).ok()?,
+            cycles: CYCLES_REGEX
+                .captures(line)
+                .and_then(|captures| captures[1].parse().ok()),
+            ppu: parse_ppu(line),
+        })
+    }
+}
+
+impl fmt::Display for TraceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Nestest => "nestest",
+            Self::Mesen => "mesen",
+            Self::Fceux => "fceux",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn parse_status(text: &str) -> Option<u8> {
+    // either hex, or a letter for every flag (nvubdizc) that is upper case when the flag is set
+    if text.len() == 2 {
+        return u8::from_str_radix(text, 16).ok();
+    }
+    Some(
+        text.chars()
+            .fold(0, |p, flag| (p << 1) | flag.is_ascii_uppercase() as u8),
+    )
+}
+
+fn parse_ppu(line: &str) -> Option<(u16, u16)> {
+    let number = |text: &str| text.parse::<i64>().ok();
+    let (scanline, dot) = if let Some(captures) = PPU_REGEX.captures(line) {
+        match (captures.get(1), captures.get(2)) {
+            (Some(scanline), Some(dot)) => (number(scanline.as_str())?, number(dot.as_str())?),
+            _ => (number(&captures[3])?, number(&captures[4])?),
+        }
+    } else {
+        let captures = OLD_PPU_REGEX.captures(line)?;
+        (number(&captures[2])?, number(&captures[1])?)
+    };
+    // some emulators call the pre-render scanline -1
+    let scanline = if scanline < 0 {
+        PRE_RENDER_SCANLINE
+    } else {
+        scanline
+    };
+    Some((scanline as u16, dot as u16))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, PartialOrd, Ord)]
+pub enum TraceField {
+    Pc,
+    A,
+    X,
+    Y,
+    P,
+    Sp,
+    Cycles,
+    Ppu,
+}
+
+impl FromStr for TraceField {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Ok(match text.trim().to_ascii_lowercase().as_str() {
+            "pc" => Self::Pc,
+            "a" => Self::A,
+            "x" => Self::X,
+            "y" => Self::Y,
+            "p" | "status" => Self::P,
+            "sp" | "s" => Self::Sp,
+            "cycles" | "cycle" | "cyc" => Self::Cycles,
+            "ppu" => Self::Ppu,
+            _ => bail!("unknown field {text:?}, try pc, a, x, y, p, sp, cycles or ppu"),
+        })
+    }
+}
+
+impl fmt::Display for TraceField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Pc => "pc",
+            Self::A => "a",
+            Self::X => "x",
+            Self::Y => "y",
+            Self::P => "p",
+            Self::Sp => "sp",
+            Self::Cycles => "cycles",
+            Self::Ppu => "ppu",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// the cpu before an instruction. not every format has the cycles and the ppu position
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct TraceEntry {
+    pub pc: u16,
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub p: u8,
+    pub sp: u8,
+    pub cycles: Option<u64>,
+    pub ppu: Option<(u16, u16)>, // (scanline, dot)
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TraceLine {
+    pub number: usize, // 1 based, like in an editor
+    pub text: String,
+    pub entry: TraceEntry,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TraceLog {
+    pub format: TraceFormat,
+    pub lines: Vec<TraceLine>,
+}
+
+impl TraceLog {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {path:?}"))?;
+        Self::parse(&text).with_context(|| format!("failed to parse {path:?}"))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        // the first line we understand decides the format. the lines that don't have an instruction
+        // (headers, frame markers, empty lines) are skipped
+        let Some(format) = text.lines().find_map(TraceFormat::detect) else {
+            bail!("not a trace log we know");
+        };
+        let lines = text
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                format.parse_line(line).map(|entry| TraceLine {
+                    number: i + 1,
+                    text: line.to_string(),
+                    entry,
+                })
+            })
+            .collect();
+        Ok(Self { format, lines })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct DiffOptions {
+    pub context: usize,
+    pub ignore: BTreeSet<TraceField>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct FieldDifference {
+    pub field: TraceField,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Divergence<'a> {
+    pub context: Vec<(&'a TraceLine, &'a TraceLine)>,
+    pub ours: &'a TraceLine,
+    pub theirs: &'a TraceLine,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum DiffResult<'a> {
+    Same {
+        compared: usize,
+        ours_left: usize, // the lines after the end of the shorter log
+        theirs_left: usize,
+    },
+    Diverged(Divergence<'a>),
+}
+
+fn align(ours: &TraceLog, theirs: &TraceLog) -> Option<(usize, usize)> {
+    // one of the logs may start later (a trace range, or a log from the reset vector), so we skip the
+    // other one to the first instruction of the later one. the shorter skip wins, a loop may run the
+    // first instruction of the earlier log again
+    let our_first = ours.lines.first()?.entry.pc;
+    let their_first = theirs.lines.first()?.entry.pc;
+    let skip_theirs = theirs
+        .lines
+        .iter()
+        .position(|line| line.entry.pc == our_first);
+    let skip_ours = ours
+        .lines
+        .iter()
+        .position(|line| line.entry.pc == their_first);
+    match (skip_ours, skip_theirs) {
+        (Some(ours), Some(theirs)) if ours < theirs => Some((ours, 0)),
+        (_, Some(theirs)) => Some((0, theirs)),
+        (Some(ours), None) => Some((ours, 0)),
+        (None, None) => None,
+    }
+}
+
+pub fn diff<'a>(
+    ours: &'a TraceLog,
+    theirs: &'a TraceLog,
+    options: &DiffOptions,
+) -> Result<DiffResult<'a>> {
+    let Some((our_start, their_start)) = align(ours, theirs) else {
+        bail!("the logs don't run the same code, there is no pc to start comparing from");
+    };
+    let our_lines = &ours.lines[our_start..];
+    let their_lines = &theirs.lines[their_start..];
+    // the emulators count the cycles from different points, so only the cycles since the start count
+    let our_base = our_lines[0].entry.cycles;
+    let their_base = their_lines[0].entry.cycles;
+
+    for (i, (our, their)) in our_lines.iter().zip(their_lines).enumerate() {
+        let mut differences = Vec::new();
+        let mut compare = |field: TraceField, ours: Option<String>, theirs: Option<String>| {
+            if options.ignore.contains(&field) {
+                return;
+            }
+            if let (Some(ours), Some(theirs)) = (ours, theirs) {
+                if ours != theirs {
+                    differences.push(FieldDifference {
+                        field,
+                        ours,
+                        theirs,
+                    });
+                }
+            }
+        };
+        let (our_entry, their_entry) = (&our.entry, &their.entry);
+        let byte = |value: u8| Some(format!("{value:02X}"));
+        compare(
+            TraceField::Pc,
+            Some(format!("{:04X}", our_entry.pc)),
+            Some(format!("{:04X}", their_entry.pc)),
+        );
+        compare(TraceField::A, byte(our_entry.a), byte(their_entry.a));
+        compare(TraceField::X, byte(our_entry.x), byte(their_entry.x));
+        compare(TraceField::Y, byte(our_entry.y), byte(their_entry.y));
+        compare(
+            TraceField::P,
+            byte(our_entry.p & STATUS_MASK),
+            byte(their_entry.p & STATUS_MASK),
+        );
+        compare(TraceField::Sp, byte(our_entry.sp), byte(their_entry.sp));
+        let since_start = |cycles: Option<u64>, base: Option<u64>| {
+            Some(format!("+{}", cycles?.checked_sub(base?)?))
+        };
+        compare(
+            TraceField::Cycles,
+            since_start(our_entry.cycles, our_base),
+            since_start(their_entry.cycles, their_base),
+        );
+        let ppu = |ppu: Option<(u16, u16)>| ppu.map(|(scanline, dot)| format!("{scanline},{dot}"));
+        compare(TraceField::Ppu, ppu(our_entry.ppu), ppu(their_entry.ppu));
+
+        if !differences.is_empty() {
+            let first = i.saturating_sub(options.context);
+            return Ok(DiffResult::Diverged(Divergence {
+                context: our_lines[first..i]
+                    .iter()
+                    .zip(&their_lines[first..i])
+                    .collect(),
+                ours: our,
+                theirs: their,
+                differences,
+            }));
+        }
+    }
+
+    let compared = our_lines.len().min(their_lines.len());
+    Ok(DiffResult::Same {
+        compared,
+        ours_left: our_lines.len() - compared,
+        theirs_left: their_lines.len() - compared,
+    })
+}
+
+impl fmt::Display for Divergence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "the logs diverge at our line {} and their line {}:",
+            self.ours.number, self.theirs.number
+        )?;
+        for difference in &self.differences {
+            writeln!(
+                f,
+                "  {}: ours {}, theirs {}",
+                difference.field, difference.ours, difference.theirs
+            )?;
+        }
+        writeln!(f)?;
+        for (ours, theirs) in &self.context {
+            writeln!(f, "  ours   {:>7}: {}", ours.number, ours.text)?;
+            writeln!(f, "  theirs {:>7}: {}", theirs.number, theirs.text)?;
+        }
+        writeln!(f, "> ours   {:>7}: {}", self.ours.number, self.ours.text)?;
+        write!(
+            f,
+            "> theirs {:>7}: {}",
+            self.theirs.number, self.theirs.text
+        )
+    }
+}
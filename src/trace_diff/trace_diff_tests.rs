@@ -0,0 +1,175 @@
+use crate::trace_diff::{
+    diff, DiffOptions, DiffResult, TraceEntry, TraceField, TraceFormat, TraceLog,
+};
+
+const NESTEST_LOG: &str = include_str!("../../tests/nestest_result_good.log");
+
+fn nestest_lines(count: usize) -> Vec<&'static str> {
+    NESTEST_LOG.lines().take(count).collect()
+}
+
+fn to_fceux(line: &str) -> String {
+    // the same instruction the way fceux logs it, with the cycles
+    let entry = TraceFormat::Nestest.parse_line(line).unwrap();
+    let flags: String = "nvubdizc"
+        .chars()
+        .enumerate()
+        .map(|(i, flag)| {
+            if entry.p & (0x80 >> i) != 0 {
+                flag.to_ascii_uppercase()
+            } else {
+                flag
+            }
+        })
+        .collect();
+    format!(
+        "c{:<10}i0 A:{:02X} X:{:02X} Y:{:02X} S:{:02X} P:{flags}  ${:04X}:{}",
+        entry.cycles.unwrap() + 100,
+        entry.a,
+        entry.x,
+        entry.y,
+        entry.sp,
+        entry.pc,
+        &line[6..48],
+    )
+}
+
+#[test]
+fn test_detect_and_parse() {
+    let nestest = NESTEST_LOG.lines().next().unwrap();
+    assert_eq!(TraceFormat::detect(nestest), Some(TraceFormat::Nestest));
+    assert_eq!(
+        TraceFormat::Nestest.parse_line(nestest),
+        Some(TraceEntry {
+            pc: 0xC000,
+            a: 0,
+            x: 0,
+            y: 0,
+            p: 0x24,
+            sp: 0xFD,
+            cycles: Some(7),
+            ppu: Some((0, 21)),
+        })
+    );
+
+    // our tracer writes the nestest format, the lines compare_logs wrote before it aren't a format anymore
+    let ours = "C5F7  86 00     STX $00 = 12                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 36 CYC:12";
+    assert_eq!(TraceFormat::detect(ours), Some(TraceFormat::Nestest));
+    let entry = TraceFormat::Nestest.parse_line(ours).unwrap();
+    assert_eq!((entry.pc, entry.p, entry.sp), (0xC5F7, 0x26, 0xFD));
+    assert_eq!((entry.cycles, entry.ppu), (Some(12), Some((0, 36))));
+    let old = " pc: 0xc5f7 opcode: 0x86 A: 0x0, X: 0x0, Y: 0x0, SP: 0xfd, Status: 0x26, Cycles: 12";
+    assert_eq!(TraceFormat::detect(old), None);
+
+    let fceux = "A:10 X:20 Y:30 S:FB P:NvUbdIzC  $8004:8D 00 20  STA $2000 = #$00";
+    assert_eq!(TraceFormat::detect(fceux), Some(TraceFormat::Fceux));
+    let entry = TraceFormat::Fceux.parse_line(fceux).unwrap();
+    assert_eq!(
+        (entry.pc, entry.a, entry.x, entry.y),
+        (0x8004, 0x10, 0x20, 0x30)
+    );
+    assert_eq!((entry.p, entry.sp, entry.cycles), (0xA5, 0xFB, None));
+
+    let mesen = "8000 $78    SEI                    A:00 X:00 Y:00 S:FD P:nvUbdIzc V:-1  H:30  Fr:0 Cycle:8";
+    assert_eq!(TraceFormat::detect(mesen), Some(TraceFormat::Mesen));
+    let entry = TraceFormat::Mesen.parse_line(mesen).unwrap();
+    assert_eq!((entry.pc, entry.p, entry.sp), (0x8000, 0x24, 0xFD));
+    assert_eq!((entry.cycles, entry.ppu), (Some(8), Some((261, 30))));
+
+    assert_eq!(TraceFormat::detect("frame 12"), None);
+    assert!(TraceLog::parse("nothing to see\n").is_err());
+}
+
+#[test]
+fn test_same_logs() {
+    let log = TraceLog::parse(NESTEST_LOG).unwrap();
+    assert_eq!(
+        diff(&log, &log, &DiffOptions::default()).unwrap(),
+        DiffResult::Same {
+            compared: log.lines.len(),
+            ours_left: 0,
+            theirs_left: 0
+        }
+    );
+}
+
+#[test]
+fn test_first_divergence() {
+    let mut lines = nestest_lines(100);
+    let theirs = TraceLog::parse(&lines.join("\n")).unwrap();
+    // a wrong a register on line 51
+    let wrong = format!("{}A:EE{}", &lines[50][..48], &lines[50][52..]);
+    lines[50] = &wrong;
+    let ours = TraceLog::parse(&lines.join("\n")).unwrap();
+
+    let options = DiffOptions {
+        context: 3,
+        ..Default::default()
+    };
+    let DiffResult::Diverged(divergence) = diff(&ours, &theirs, &options).unwrap() else {
+        panic!("the logs should diverge");
+    };
+    assert_eq!((divergence.ours.number, divergence.theirs.number), (51, 51));
+    assert_eq!(divergence.differences.len(), 1);
+    assert_eq!(divergence.differences[0].field, TraceField::A);
+    assert_eq!(divergence.differences[0].ours, "EE");
+    let context: Vec<usize> = divergence
+        .context
+        .iter()
+        .map(|(our, _)| our.number)
+        .collect();
+    assert_eq!(context, [48, 49, 50]);
+    assert!(divergence.to_string().contains("a: ours EE"));
+
+    let options = DiffOptions {
+        ignore: [TraceField::A].into(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        diff(&ours, &theirs, &options).unwrap(),
+        DiffResult::Same { compared: 100, .. }
+    ));
+}
+
+#[test]
+fn test_align_and_other_formats() {
+    // fceux starts counting at another cycle, and its log starts 10 instructions later
+    let fceux: Vec<String> = nestest_lines(200)
+        .iter()
+        .skip(10)
+        .map(|line| to_fceux(line))
+        .collect();
+    let theirs = TraceLog::parse(&fceux.join("\n")).unwrap();
+    assert_eq!(theirs.format, TraceFormat::Fceux);
+    let ours = TraceLog::parse(&nestest_lines(150).join("\n")).unwrap();
+    assert_eq!(
+        diff(&ours, &theirs, &DiffOptions::default()).unwrap(),
+        DiffResult::Same {
+            compared: 140,
+            ours_left: 0,
+            theirs_left: 50
+        }
+    );
+
+    // a wrong cycle count shows up as the cycles since the start
+    let mut lines = nestest_lines(150);
+    let wrong = lines[20].replace(" CYC:", " CYC:1");
+    lines[20] = &wrong;
+    let ours = TraceLog::parse(&lines.join("\n")).unwrap();
+    let DiffResult::Diverged(divergence) = diff(&ours, &theirs, &DiffOptions::default()).unwrap()
+    else {
+        panic!("the logs should diverge");
+    };
+    assert_eq!((divergence.ours.number, divergence.theirs.number), (21, 11));
+    assert_eq!(divergence.differences[0].field, TraceField::Cycles);
+
+    let options = DiffOptions {
+        ignore: ["cyc".parse().unwrap()].into(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        diff(&ours, &theirs, &options).unwrap(),
+        DiffResult::Same { .. }
+    ));
+    assert!("flags".parse::<TraceField>().is_err());
+}
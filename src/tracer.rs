@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tracer_tests;
+
+use crate::bus::memory::Mem;
+use crate::cpu::interrupt_lines::InterruptLines;
+use crate::cpu::CPU;
+use crate::disasm::Instruction;
+use crate::symbols::{Labels, Symbols};
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+// the frames to trace, from first_frame up to (not including) end_frame
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct TraceRange {
+    pub first_frame: u64,
+    pub end_frame: Option<u64>, // None to trace until the end
+}
+
+impl TraceRange {
+    pub fn contains(&self, frame: u64) -> bool {
+        frame >= self.first_frame && self.end_frame.is_none_or(|end| frame < end)
+    }
+}
+
+impl FromStr for TraceRange {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        // "100..200", "100..", "..200", or a single frame "150"
+        let parse = |number: &str| {
+            number
+                .trim()
+                .parse::<u64>()
+                .with_context(|| format!("bad frame {number:?} in the trace range {text:?}"))
+        };
+        let range = match text.split_once("..") {
+            Some((first, end)) => Self {
+                first_frame: if first.is_empty() { 0 } else { parse(first)? },
+                end_frame: if end.is_empty() {
+                    None
+                } else {
+                    Some(parse(end)?)
+                },
+            },
+            None => {
+                let frame = parse(text)?;
+                Self {
+                    first_frame: frame,
+                    end_frame: Some(frame + 1),
+                }
+            }
+        };
+        if range.end_frame.is_some_and(|end| end <= range.first_frame) {
+            bail!("the trace range {text:?} is empty");
+        }
+        Ok(range)
+    }
+}
+
+// where the console is when an instruction starts
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct TracePosition {
+    pub scanline: usize,
+    pub dot: usize,
+    pub cycles: u64, // cpu cycles since the power on
+    pub frame: u64,
+}
+
+pub fn trace_line<B: Mem + InterruptLines>(
+    cpu: &CPU<'_, B>,
+    mut peek: impl FnMut(u16) -> u8,
+    symbols: Option<&dyn Labels>,
+    position: TracePosition,
+) -> String {
+    // one line of nestest.log (the format of nintendulator, that mesen and most emulators can write too):
+    // C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+    // with symbols the operands have their labels, like the debuggers do (and the other emulators won't)
+    let instruction = Instruction::decode(cpu.program_counter, &mut peek);
+    let text = instruction.annotated_text(cpu.register_x, cpu.register_y, &mut peek, symbols);
+    let bytes: Vec<String> = instruction
+        .bytes()
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect();
+    format!(
+        "{:04X}  {:<8}  {text:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        cpu.program_counter,
+        bytes.join(" "),
+        cpu.register_a,
+        cpu.register_x,
+        cpu.register_y,
+        cpu.status,
+        cpu.stack_pointer,
+        position.scanline,
+        position.dot,
+        position.cycles
+    )
+}
+
+// writes a line for every instruction. a failed write stops the tracing, and finish returns the error
+pub struct Tracer {
+    output: Box<dyn Write + Send>,
+    range: TraceRange,
+    symbols: Option<Symbols>,
+    lines: u64,
+    error: Option<io::Error>,
+}
+
+impl Tracer {
+    pub fn new(output: Box<dyn Write + Send>, range: TraceRange) -> Self {
+        Self {
+            output,
+            range,
+            symbols: None,
+            lines: 0,
+            error: None,
+        }
+    }
+
+    pub fn to_file(path: &Path, range: TraceRange) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("failed to create {path:?}"))?;
+        Ok(Self::new(Box::new(BufWriter::new(file)), range))
+    }
+
+    pub fn set_symbols(&mut self, symbols: Option<Symbols>) {
+        self.symbols = symbols;
+    }
+
+    pub fn trace<B: Mem + InterruptLines>(
+        &mut self,
+        cpu: &CPU<'_, B>,
+        peek: impl FnMut(u16) -> u8,
+        prg_rom_offset: impl Fn(u16) -> Option<usize>,
+        position: TracePosition,
+    ) {
+        // prg_rom_offset tells the banks of the labels apart, like Bus::prg_rom_offset
+        if self.error.is_some() || !self.range.contains(position.frame) {
+            return;
+        }
+        let symbols = self
+            .symbols
+            .as_ref()
+            .map(|symbols| symbols.mapped(prg_rom_offset));
+        let symbols = symbols.as_ref().map(|symbols| symbols as &dyn Labels);
+        match writeln!(self.output, "{}", trace_line(cpu, peek, symbols, position)) {
+            Ok(()) => self.lines += 1,
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn finish(mut self) -> Result<u64> {
+        // flushes the output, and returns how many lines were written
+        if let Some(e) = self.error.take() {
+            return Err(e).context("failed to write the trace");
+        }
+        self.output.flush().context("failed to write the trace")?;
+        Ok(self.lines)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::cpu::flat_bus::FlatBus;
+use crate::cpu::CPU;
+use crate::symbols::Symbols;
+use crate::tracer::{trace_line, TracePosition, TraceRange, Tracer};
+
+#[test]
+fn test_trace_range() {
+    let range: TraceRange = "100..200".parse().unwrap();
+    assert!(!range.contains(99));
+    assert!(range.contains(100));
+    assert!(range.contains(199));
+    assert!(!range.contains(200));
+
+    let range: TraceRange = "100..".parse().unwrap();
+    assert!(range.contains(1_000_000));
+    assert!(!range.contains(0));
+    assert!("..3".parse::<TraceRange>().unwrap().contains(0));
+    assert_eq!(
+        "7".parse::<TraceRange>().unwrap(),
+        TraceRange {
+            first_frame: 7,
+            end_frame: Some(8)
+        }
+    );
+
+    assert!("5..5".parse::<TraceRange>().is_err());
+    assert!("a..5".parse::<TraceRange>().is_err());
+}
+
+#[test]
+fn test_trace_line() {
+    let mut bus = FlatBus::new();
+    bus.load(0xC5F7, &[0x86, 0x00]); // STX $00
+    bus.load(0x0000, &[0x12]);
+    let mut cpu = CPU::new(&mut bus);
+    cpu.program_counter = 0xC5F7;
+    cpu.status = 0x26;
+    cpu.stack_pointer = 0xFD;
+    let position = TracePosition {
+        scanline: 0,
+        dot: 36,
+        cycles: 12,
+        frame: 0,
+    };
+    let peek = |address: u16| cpu.bus.as_deref().unwrap().memory[address as usize];
+    assert_eq!(
+        trace_line(&cpu, peek, None, position),
+        "C5F7  86 00     STX $00 = 12                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 36 CYC:12"
+    );
+
+    let symbols = Symbols::from_nl("$0000#temp#\n").unwrap();
+    assert_eq!(
+        trace_line(&cpu, peek, Some(&symbols), position),
+        "C5F7  86 00     STX temp = 12                   A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 36 CYC:12"
+    );
+}
+
+#[test]
+fn test_tracer_only_writes_the_range() {
+    let mut bus = FlatBus::new();
+    let cpu = CPU::new(&mut bus);
+    let mut tracer = Tracer::new(Box::new(Vec::new()), "2..3".parse().unwrap());
+    for frame in 0..5 {
+        let position = TracePosition {
+            frame,
+            ..Default::default()
+        };
+        tracer.trace(&cpu, |_| 0xEA, |_| None, position);
+    }
+    assert_eq!(tracer.finish().unwrap(), 1);
+}
@@ -0,0 +1,64 @@
+use crate::ppu::frame::Frame;
+use crate::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::video::VideoSink;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub fn write_ppm<W: Write>(frame: &Frame, writer: &mut W) -> std::io::Result<()> {
+    // binary ppm, the simplest image format there is (see https://netpbm.sourceforge.net/doc/ppm.html)
+    write!(writer, "P6\n{SCREEN_WIDTH} {SCREEN_HEIGHT}\n255\n")?;
+    writer.write_all(&frame.screen_state)
+}
+
+pub fn write_png<W: Write>(frame: &Frame, writer: &mut W) -> std::io::Result<()> {
+    let mut encoder = png::Encoder::new(writer, SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(&frame.screen_state)?;
+    png_writer.finish()?;
+    Ok(())
+}
+
+// writes every frame as a ppm image, one after the other. ffmpeg can read it as is:
+// ffmpeg -f image2pipe -framerate 60.0988 -i frames.ppm video.mp4
+#[derive(Debug)]
+pub struct FileSink<W: Write = BufWriter<File>> {
+    writer: W,
+    frames_written: u64,
+}
+
+impl FileSink {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("failed to create {path:?}"))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> FileSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            frames_written: 0,
+        }
+    }
+
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> VideoSink for FileSink<W> {
+    fn present(&mut self, frame: &Frame) -> Result<()> {
+        write_ppm(frame, &mut self.writer)?;
+        self.frames_written += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+mod file_sink;
+#[cfg(feature = "sdl")]
+mod sdl_sink;
+#[cfg(test)]
+mod video_tests;
+
+use crate::ppu::frame::Frame;
+use anyhow::Result;
+pub use file_sink::{write_png, write_ppm, FileSink};
+#[cfg(feature = "sdl")]
+pub use sdl_sink::SdlSink;
+
+// where the finished frames go. the ppu only draws into its frame, and whoever runs it decides what to do with it
+pub trait VideoSink {
+    fn present(&mut self, frame: &Frame) -> Result<()>;
+}
+
+// throws the frames away, for running without a screen
+#[derive(Debug, Default)]
+pub struct NullSink;
+
+impl VideoSink for NullSink {
+    fn present(&mut self, _frame: &Frame) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use crate::ppu::frame::Frame;
+use crate::ppu::SCREEN_WIDTH;
+use crate::video::VideoSink;
+use anyhow::Result;
+use sdl2::render::{Texture, WindowCanvas};
+
+// shows the frames in an sdl window, see generate_texture_canvas_event_pump! for creating the canvas and the texture
+pub struct SdlSink<'t> {
+    canvas: WindowCanvas,
+    texture: Texture<'t>,
+}
+
+impl<'t> SdlSink<'t> {
+    pub fn new(canvas: WindowCanvas, texture: Texture<'t>) -> Self {
+        Self { canvas, texture }
+    }
+
+    pub fn canvas_mut(&mut self) -> &mut WindowCanvas {
+        &mut self.canvas
+    }
+}
+
+impl VideoSink for SdlSink<'_> {
+    fn present(&mut self, frame: &Frame) -> Result<()> {
+        self.texture
+            .update(None, &frame.screen_state, SCREEN_WIDTH * 3)?;
+        self.canvas
+            .copy(&self.texture, None, None)
+            .map_err(anyhow::Error::msg)?;
+        self.canvas.present();
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::ppu::frame::Frame;
+use crate::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::video::{write_png, FileSink, NullSink, VideoSink};
+
+#[test]
+fn test_file_sink_writes_ppm_frames() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (1, 2, 3));
+    frame.set_pixel(SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1, (4, 5, 6));
+
+    let mut sink = FileSink::new(Vec::new());
+    sink.present(&frame).unwrap();
+    sink.present(&Frame::new()).unwrap();
+    assert_eq!(sink.frames_written(), 2);
+    let bytes = sink.into_inner().unwrap();
+
+    let header = b"P6\n256 240\n255\n";
+    let frame_size = header.len() + SCREEN_WIDTH * SCREEN_HEIGHT * 3;
+    assert_eq!(bytes.len(), 2 * frame_size);
+    assert!(bytes.starts_with(header));
+    assert_eq!(bytes[header.len()..header.len() + 3], [1, 2, 3]);
+    assert_eq!(bytes[frame_size - 3..frame_size], [4, 5, 6]);
+    assert!(bytes[frame_size..].starts_with(header));
+}
+
+#[test]
+fn test_null_sink() {
+    assert!(NullSink.present(&Frame::new()).is_ok());
+}
+
+#[test]
+fn test_png_round_trip() {
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, (1, 2, 3));
+    frame.set_pixel(SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1, (4, 5, 6));
+    let mut bytes = Vec::new();
+    write_png(&frame, &mut bytes).unwrap();
+
+    let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(
+        (info.width, info.height),
+        (SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32)
+    );
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    assert_eq!(pixels, frame.screen_state);
+}
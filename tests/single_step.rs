@@ -0,0 +1,79 @@
+use nes_emulator::cpu::single_step::{run_single_step_test, SingleStepTest};
+use std::path::{Path, PathBuf};
+
+// ten tests for every documented opcode in the suite's format, written by tests/single_step/generate.py.
+// point SINGLE_STEP_TESTS at the nes6502/v1 directory of the whole suite to run it instead
+static VENDORED_TESTS: &str = "./tests/single_step";
+
+// the undocumented opcodes, the cpu can't run them yet. their files are skipped
+const UNSUPPORTED_OPCODES: [u8; 105] = [
+    0x02, 0x03, 0x04, 0x07, 0x0B, 0x0C, 0x0F, 0x12, 0x13, 0x14, 0x17, 0x1A, 0x1B, 0x1C, 0x1F, 0x22,
+    0x23, 0x27, 0x2B, 0x2F, 0x32, 0x33, 0x34, 0x37, 0x3A, 0x3B, 0x3C, 0x3F, 0x42, 0x43, 0x44, 0x47,
+    0x4B, 0x4F, 0x52, 0x53, 0x54, 0x57, 0x5A, 0x5B, 0x5C, 0x5F, 0x62, 0x63, 0x64, 0x67, 0x6B, 0x6F,
+    0x72, 0x73, 0x74, 0x77, 0x7A, 0x7B, 0x7C, 0x7F, 0x80, 0x82, 0x83, 0x87, 0x89, 0x8B, 0x8F, 0x92,
+    0x93, 0x97, 0x9B, 0x9C, 0x9E, 0x9F, 0xA3, 0xA7, 0xAB, 0xAF, 0xB2, 0xB3, 0xB7, 0xBB, 0xBF, 0xC2,
+    0xC3, 0xC7, 0xCB, 0xCF, 0xD2, 0xD3, 0xD4, 0xD7, 0xDA, 0xDB, 0xDC, 0xDF, 0xE2, 0xE3, 0xE7, 0xEB,
+    0xEF, 0xF2, 0xF3, 0xF4, 0xF7, 0xFA, 0xFB, 0xFC, 0xFF,
+];
+
+fn test_files() -> Vec<(u8, PathBuf)> {
+    // the files are named after their opcode, like a9.json
+    let dir = std::env::var("SINGLE_STEP_TESTS").unwrap_or(VENDORED_TESTS.to_string());
+    let mut files: Vec<(u8, PathBuf)> = std::fs::read_dir(Path::new(&dir))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            let opcode = u8::from_str_radix(name, 16)
+                .unwrap_or_else(|_| panic!("{path:?} isn't named after an opcode"));
+            (opcode, path)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_single_step_opcodes() {
+    let files = test_files();
+    let missing: Vec<String> = (0..=u8::MAX)
+        .filter(|opcode| !UNSUPPORTED_OPCODES.contains(opcode))
+        .filter(|opcode| !files.iter().any(|(file_opcode, _)| file_opcode == opcode))
+        .map(|opcode| format!("{opcode:02x}"))
+        .collect();
+    assert!(missing.is_empty(), "no tests for {}", missing.join(", "));
+
+    let mut failures = Vec::new();
+    let mut number_of_tests = 0;
+    for (opcode, path) in files {
+        if UNSUPPORTED_OPCODES.contains(&opcode) {
+            continue;
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        let tests: Vec<SingleStepTest> = serde_json::from_str(&text).unwrap();
+        let failed_before = failures.len();
+        for test in &tests {
+            if let Err(e) = run_single_step_test(test) {
+                failures.push(format!("{e:#}"));
+            }
+        }
+        number_of_tests += tests.len();
+        println!(
+            "{:?}: {} of {} passed",
+            path.file_name().unwrap(),
+            tests.len() - (failures.len() - failed_before),
+            tests.len()
+        );
+    }
+    // the first few are enough to see what's wrong
+    assert!(
+        failures.is_empty(),
+        "{} of {number_of_tests} failed:\n{}",
+        failures.len(),
+        failures[..failures.len().min(20)].join("\n")
+    );
+}
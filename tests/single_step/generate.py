@@ -0,0 +1,357 @@
+# writes tests/single_step/<opcode>.json, TESTS_PER_OPCODE tests for every documented opcode, in the format of
+# https://github.com/SingleStepTests/65x02 (the nes6502 set: no decimal mode).
+# the tests come from the cycle by cycle model below, which follows the bus activity tables of 64doc (the
+# "documentation for the NMOS 65xx/85xx instruction set") and shares nothing with the emulator. the status register keeps
+# the unused bit set and the b flag clear, like in nestest.log.
+# the undocumented opcodes get no file, tests/single_step.rs lists them as unsupported.
+#
+#   python3 tests/single_step/generate.py
+import json
+import os
+import random
+
+TESTS_PER_OPCODE = 10
+SEED = 6502
+
+C, Z, I, D, B, U, V, N = (1 << bit for bit in range(8))
+
+# mnemonic, addressing mode
+OPCODES = {
+    0x69: ("ADC", "imm"), 0x65: ("ADC", "zp"), 0x75: ("ADC", "zpx"), 0x6D: ("ADC", "abs"),
+    0x7D: ("ADC", "absx"), 0x79: ("ADC", "absy"), 0x61: ("ADC", "indx"), 0x71: ("ADC", "indy"),
+    0x29: ("AND", "imm"), 0x25: ("AND", "zp"), 0x35: ("AND", "zpx"), 0x2D: ("AND", "abs"),
+    0x3D: ("AND", "absx"), 0x39: ("AND", "absy"), 0x21: ("AND", "indx"), 0x31: ("AND", "indy"),
+    0x0A: ("ASL", "acc"), 0x06: ("ASL", "zp"), 0x16: ("ASL", "zpx"), 0x0E: ("ASL", "abs"), 0x1E: ("ASL", "absx"),
+    0x90: ("BCC", "rel"), 0xB0: ("BCS", "rel"), 0xF0: ("BEQ", "rel"), 0x30: ("BMI", "rel"),
+    0xD0: ("BNE", "rel"), 0x10: ("BPL", "rel"), 0x50: ("BVC", "rel"), 0x70: ("BVS", "rel"),
+    0x24: ("BIT", "zp"), 0x2C: ("BIT", "abs"),
+    0x00: ("BRK", "imp"),
+    0x18: ("CLC", "imp"), 0xD8: ("CLD", "imp"), 0x58: ("CLI", "imp"), 0xB8: ("CLV", "imp"),
+    0xC9: ("CMP", "imm"), 0xC5: ("CMP", "zp"), 0xD5: ("CMP", "zpx"), 0xCD: ("CMP", "abs"),
+    0xDD: ("CMP", "absx"), 0xD9: ("CMP", "absy"), 0xC1: ("CMP", "indx"), 0xD1: ("CMP", "indy"),
+    0xE0: ("CPX", "imm"), 0xE4: ("CPX", "zp"), 0xEC: ("CPX", "abs"),
+    0xC0: ("CPY", "imm"), 0xC4: ("CPY", "zp"), 0xCC: ("CPY", "abs"),
+    0xC6: ("DEC", "zp"), 0xD6: ("DEC", "zpx"), 0xCE: ("DEC", "abs"), 0xDE: ("DEC", "absx"),
+    0xCA: ("DEX", "imp"), 0x88: ("DEY", "imp"),
+    0x49: ("EOR", "imm"), 0x45: ("EOR", "zp"), 0x55: ("EOR", "zpx"), 0x4D: ("EOR", "abs"),
+    0x5D: ("EOR", "absx"), 0x59: ("EOR", "absy"), 0x41: ("EOR", "indx"), 0x51: ("EOR", "indy"),
+    0xE6: ("INC", "zp"), 0xF6: ("INC", "zpx"), 0xEE: ("INC", "abs"), 0xFE: ("INC", "absx"),
+    0xE8: ("INX", "imp"), 0xC8: ("INY", "imp"),
+    0x4C: ("JMP", "abs"), 0x6C: ("JMP", "ind"),
+    0x20: ("JSR", "abs"),
+    0xA9: ("LDA", "imm"), 0xA5: ("LDA", "zp"), 0xB5: ("LDA", "zpx"), 0xAD: ("LDA", "abs"),
+    0xBD: ("LDA", "absx"), 0xB9: ("LDA", "absy"), 0xA1: ("LDA", "indx"), 0xB1: ("LDA", "indy"),
+    0xA2: ("LDX", "imm"), 0xA6: ("LDX", "zp"), 0xB6: ("LDX", "zpy"), 0xAE: ("LDX", "abs"), 0xBE: ("LDX", "absy"),
+    0xA0: ("LDY", "imm"), 0xA4: ("LDY", "zp"), 0xB4: ("LDY", "zpx"), 0xAC: ("LDY", "abs"), 0xBC: ("LDY", "absx"),
+    0x4A: ("LSR", "acc"), 0x46: ("LSR", "zp"), 0x56: ("LSR", "zpx"), 0x4E: ("LSR", "abs"), 0x5E: ("LSR", "absx"),
+    0xEA: ("NOP", "imp"),
+    0x09: ("ORA", "imm"), 0x05: ("ORA", "zp"), 0x15: ("ORA", "zpx"), 0x0D: ("ORA", "abs"),
+    0x1D: ("ORA", "absx"), 0x19: ("ORA", "absy"), 0x01: ("ORA", "indx"), 0x11: ("ORA", "indy"),
+    0x48: ("PHA", "imp"), 0x08: ("PHP", "imp"), 0x68: ("PLA", "imp"), 0x28: ("PLP", "imp"),
+    0x2A: ("ROL", "acc"), 0x26: ("ROL", "zp"), 0x36: ("ROL", "zpx"), 0x2E: ("ROL", "abs"), 0x3E: ("ROL", "absx"),
+    0x6A: ("ROR", "acc"), 0x66: ("ROR", "zp"), 0x76: ("ROR", "zpx"), 0x6E: ("ROR", "abs"), 0x7E: ("ROR", "absx"),
+    0x40: ("RTI", "imp"), 0x60: ("RTS", "imp"),
+    0xE9: ("SBC", "imm"), 0xE5: ("SBC", "zp"), 0xF5: ("SBC", "zpx"), 0xED: ("SBC", "abs"),
+    0xFD: ("SBC", "absx"), 0xF9: ("SBC", "absy"), 0xE1: ("SBC", "indx"), 0xF1: ("SBC", "indy"),
+    0x38: ("SEC", "imp"), 0xF8: ("SED", "imp"), 0x78: ("SEI", "imp"),
+    0x85: ("STA", "zp"), 0x95: ("STA", "zpx"), 0x8D: ("STA", "abs"), 0x9D: ("STA", "absx"),
+    0x99: ("STA", "absy"), 0x81: ("STA", "indx"), 0x91: ("STA", "indy"),
+    0x86: ("STX", "zp"), 0x96: ("STX", "zpy"), 0x8E: ("STX", "abs"),
+    0x84: ("STY", "zp"), 0x94: ("STY", "zpx"), 0x8C: ("STY", "abs"),
+    0xAA: ("TAX", "imp"), 0xA8: ("TAY", "imp"), 0xBA: ("TSX", "imp"),
+    0x8A: ("TXA", "imp"), 0x9A: ("TXS", "imp"), 0x98: ("TYA", "imp"),
+}
+
+READS = {"ADC", "AND", "BIT", "CMP", "CPX", "CPY", "EOR", "LDA", "LDX", "LDY", "ORA", "SBC"}
+WRITES = {"STA", "STX", "STY"}
+READ_MODIFY_WRITES = {"ASL", "DEC", "INC", "LSR", "ROL", "ROR"}
+BRANCHES = {"BCC": (C, False), "BCS": (C, True), "BEQ": (Z, True), "BMI": (N, True),
+            "BNE": (Z, False), "BPL": (N, False), "BVC": (V, False), "BVS": (V, True)}
+FLAG_SETS = {"CLC": (C, False), "CLD": (D, False), "CLI": (I, False), "CLV": (V, False),
+             "SEC": (C, True), "SED": (D, True), "SEI": (I, True)}
+
+
+class Machine:
+    def __init__(self, rng, pc, s, a, x, y, p):
+        self.rng = rng
+        self.pc, self.s, self.a, self.x, self.y, self.p = pc, s, a, x, y, p
+        self.initial = {}  # the memory before the instruction, every address it touches gets a random value
+        self.memory = {}
+        self.cycles = []
+
+    def touch(self, address):
+        if address not in self.initial:
+            self.initial[address] = self.rng.randrange(256)
+            self.memory[address] = self.initial[address]
+
+    def read(self, address):
+        address &= 0xFFFF
+        self.touch(address)
+        value = self.memory[address]
+        self.cycles.append([address, value, "read"])
+        return value
+
+    def write(self, address, value):
+        address &= 0xFFFF
+        self.touch(address)
+        self.memory[address] = value
+        self.cycles.append([address, value, "write"])
+
+    def fetch(self):
+        value = self.read(self.pc)
+        self.pc = (self.pc + 1) & 0xFFFF
+        return value
+
+    def push(self, value):
+        self.write(0x100 | self.s, value)
+        self.s = (self.s - 1) & 0xFF
+
+    def pull(self):
+        self.s = (self.s + 1) & 0xFF
+        return self.read(0x100 | self.s)
+
+    def flag(self, flag, on):
+        self.p = (self.p | flag) if on else (self.p & ~flag)
+
+    def nz(self, value):
+        self.flag(Z, value == 0)
+        self.flag(N, value & 0x80)
+        return value
+
+    def state(self, memory):
+        return {"pc": self.pc, "s": self.s, "a": self.a, "x": self.x, "y": self.y, "p": self.p,
+                "ram": [[address, memory[address]] for address in sorted(memory)]}
+
+
+def effective_address(m, mode, kind):
+    # the cycles between the opcode and the access to the operand. kind is read, write or rmw, the indexed modes
+    # read the address before the page is fixed when the page changes, and always when they write
+    if mode == "zp":
+        return m.fetch()
+    if mode in ("zpx", "zpy"):
+        base = m.fetch()
+        m.read(base)
+        return (base + (m.x if mode == "zpx" else m.y)) & 0xFF
+    if mode == "abs":
+        low = m.fetch()
+        return low | (m.fetch() << 8)
+    if mode in ("absx", "absy", "indy"):
+        if mode == "indy":
+            pointer = m.fetch()
+            low = m.read(pointer)
+            high = m.read((pointer + 1) & 0xFF)
+        else:
+            low = m.fetch()
+            high = m.fetch()
+        index = m.x if mode == "absx" else m.y
+        unfixed = (high << 8) | ((low + index) & 0xFF)
+        address = ((high << 8) + low + index) & 0xFFFF
+        if kind != "read" or unfixed != address:
+            m.read(unfixed)
+        return address
+    if mode == "indx":
+        pointer = m.fetch()
+        m.read(pointer)
+        low = m.read((pointer + m.x) & 0xFF)
+        high = m.read((pointer + m.x + 1) & 0xFF)
+        return low | (high << 8)
+    raise ValueError(mode)
+
+
+def add(m, value):
+    result = m.a + value + (m.p & C)
+    m.flag(C, result > 0xFF)
+    m.flag(V, ~(m.a ^ value) & (m.a ^ result) & 0x80)
+    m.a = m.nz(result & 0xFF)
+
+
+def compare(m, register, value):
+    m.flag(C, register >= value)
+    m.nz((register - value) & 0xFF)
+
+
+def shift(m, mnemonic, value):
+    carry = m.p & C
+    if mnemonic == "ASL":
+        m.flag(C, value & 0x80)
+        return m.nz((value << 1) & 0xFF)
+    if mnemonic == "LSR":
+        m.flag(C, value & 1)
+        return m.nz(value >> 1)
+    if mnemonic == "ROL":
+        m.flag(C, value & 0x80)
+        return m.nz(((value << 1) | carry) & 0xFF)
+    m.flag(C, value & 1)
+    return m.nz((value >> 1) | (carry << 7))
+
+
+def run(m):
+    opcode = m.fetch()
+    mnemonic, mode = OPCODES[opcode]
+
+    if mnemonic in READS:
+        value = m.fetch() if mode == "imm" else m.read(effective_address(m, mode, "read"))
+        if mnemonic == "ADC":
+            add(m, value)
+        elif mnemonic == "SBC":
+            add(m, value ^ 0xFF)
+        elif mnemonic == "AND":
+            m.a = m.nz(m.a & value)
+        elif mnemonic == "ORA":
+            m.a = m.nz(m.a | value)
+        elif mnemonic == "EOR":
+            m.a = m.nz(m.a ^ value)
+        elif mnemonic == "LDA":
+            m.a = m.nz(value)
+        elif mnemonic == "LDX":
+            m.x = m.nz(value)
+        elif mnemonic == "LDY":
+            m.y = m.nz(value)
+        elif mnemonic == "CMP":
+            compare(m, m.a, value)
+        elif mnemonic == "CPX":
+            compare(m, m.x, value)
+        elif mnemonic == "CPY":
+            compare(m, m.y, value)
+        elif mnemonic == "BIT":
+            m.flag(Z, m.a & value == 0)
+            m.flag(V, value & 0x40)
+            m.flag(N, value & 0x80)
+    elif mnemonic in WRITES:
+        register = {"STA": m.a, "STX": m.x, "STY": m.y}[mnemonic]
+        m.write(effective_address(m, mode, "write"), register)
+    elif mnemonic in READ_MODIFY_WRITES and mode != "acc":
+        address = effective_address(m, mode, "rmw")
+        value = m.read(address)
+        m.write(address, value)
+        if mnemonic == "INC":
+            result = m.nz((value + 1) & 0xFF)
+        elif mnemonic == "DEC":
+            result = m.nz((value - 1) & 0xFF)
+        else:
+            result = shift(m, mnemonic, value)
+        m.write(address, result)
+    elif mnemonic in BRANCHES:
+        offset = m.fetch()
+        flag, taken_when = BRANCHES[mnemonic]
+        if bool(m.p & flag) == taken_when:
+            m.read(m.pc)
+            target = (m.pc + offset - (0x100 if offset & 0x80 else 0)) & 0xFFFF
+            if target & 0xFF00 != m.pc & 0xFF00:
+                m.read((m.pc & 0xFF00) | (target & 0xFF))
+            m.pc = target
+    elif mnemonic == "JMP":
+        low = m.fetch()
+        high = m.fetch()
+        address = (high << 8) | low
+        if mode == "ind":
+            # the high byte comes from the same page
+            low = m.read(address)
+            high = m.read((address & 0xFF00) | ((address + 1) & 0xFF))
+            address = (high << 8) | low
+        m.pc = address
+    elif mnemonic == "JSR":
+        low = m.fetch()
+        m.read(0x100 | m.s)
+        m.push(m.pc >> 8)
+        m.push(m.pc & 0xFF)
+        high = m.read(m.pc)
+        m.pc = (high << 8) | low
+    elif mnemonic == "BRK":
+        m.fetch()
+        m.push(m.pc >> 8)
+        m.push(m.pc & 0xFF)
+        m.push(m.p | B | U)
+        m.flag(I, True)
+        low = m.read(0xFFFE)
+        m.pc = low | (m.read(0xFFFF) << 8)
+    else:
+        # the rest are one byte long, and read the next byte anyway
+        m.read(m.pc)
+        if mnemonic in ("PLA", "PLP", "RTS", "RTI"):
+            m.read(0x100 | m.s)
+        if mode == "acc":
+            m.a = shift(m, mnemonic, m.a)
+        elif mnemonic in FLAG_SETS:
+            flag, on = FLAG_SETS[mnemonic]
+            m.flag(flag, on)
+        elif mnemonic == "PHA":
+            m.push(m.a)
+        elif mnemonic == "PHP":
+            m.push(m.p | B | U)
+        elif mnemonic == "PLA":
+            m.a = m.nz(m.pull())
+        elif mnemonic == "PLP":
+            m.p = (m.pull() & ~B) | U
+        elif mnemonic == "RTS":
+            low = m.pull()
+            m.pc = low | (m.pull() << 8)
+            m.read(m.pc)
+            m.pc = (m.pc + 1) & 0xFFFF
+        elif mnemonic == "RTI":
+            m.p = (m.pull() & ~B) | U
+            low = m.pull()
+            m.pc = low | (m.pull() << 8)
+        elif mnemonic == "INX":
+            m.x = m.nz((m.x + 1) & 0xFF)
+        elif mnemonic == "INY":
+            m.y = m.nz((m.y + 1) & 0xFF)
+        elif mnemonic == "DEX":
+            m.x = m.nz((m.x - 1) & 0xFF)
+        elif mnemonic == "DEY":
+            m.y = m.nz((m.y - 1) & 0xFF)
+        elif mnemonic == "TAX":
+            m.x = m.nz(m.a)
+        elif mnemonic == "TAY":
+            m.y = m.nz(m.a)
+        elif mnemonic == "TXA":
+            m.a = m.nz(m.x)
+        elif mnemonic == "TYA":
+            m.a = m.nz(m.y)
+        elif mnemonic == "TSX":
+            m.x = m.nz(m.s)
+        elif mnemonic == "TXS":
+            m.s = m.x
+        elif mnemonic != "NOP":
+            raise ValueError(mnemonic)
+
+
+def generate(rng, opcode):
+    p = (rng.randrange(256) | U) & ~B
+    m = Machine(rng, rng.randrange(0x10000), rng.randrange(256), rng.randrange(256), rng.randrange(256),
+                rng.randrange(256), p)
+    m.initial[m.pc] = opcode
+    m.memory[m.pc] = opcode
+    before = m.state({})
+    start = m.pc
+    run(m)
+    # the bytes of the instruction
+    name = " ".join(f"{m.initial[(start + i) & 0xFFFF]:02x}" for i in range(instruction_length(opcode)))
+    before["ram"] = [[address, m.initial[address]] for address in sorted(m.initial)]
+    return {"name": name, "initial": before, "final": m.state(m.memory), "cycles": m.cycles}
+
+
+def instruction_length(opcode):
+    mnemonic, mode = OPCODES[opcode]
+    if mnemonic in ("JSR", "BRK"):
+        return 3 if mnemonic == "JSR" else 1
+    if mode in ("imp", "acc"):
+        return 1
+    if mode in ("abs", "absx", "absy", "ind"):
+        return 3
+    return 2
+
+
+def main():
+    rng = random.Random(SEED)
+    directory = os.path.dirname(os.path.abspath(__file__))
+    for opcode in sorted(OPCODES):
+        tests = [generate(rng, opcode) for _ in range(TESTS_PER_OPCODE)]
+        with open(os.path.join(directory, f"{opcode:02x}.json"), "w") as file:
+            file.write("[\n")
+            file.write(",\n".join(json.dumps(test, separators=(",", ":")) for test in tests))
+            file.write("\n]\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,112 @@
+use nes_emulator::test_roms::{find_test_roms, run_test_rom, TestRomStatus};
+use std::path::Path;
+
+// the suites (instr_test-v5, ppu_vbl_nmi, apu_test, ...) go here, each in its own directory
+static VENDORED_SUITES: &str = "./tests/test_roms";
+
+const PRG_START: u16 = 0xC000;
+
+// a tiny nrom cartridge that speaks the 0x6000 protocol. if reset_first, it asks for a reset before reporting
+fn protocol_rom(final_status: u8, message: &str, reset_first: bool) -> Vec<u8> {
+    let mut code: Vec<u8> = vec![
+        0x78, // SEI
+        0xA2, 0xFF, 0x9A, // LDX #$FF; TXS
+        0xA9, 0x80, 0x8D, 0x00, 0x60, // LDA #$80; STA $6000 - running
+        0xA9, 0xDE, 0x8D, 0x01, 0x60, // the signature
+        0xA9, 0xB0, 0x8D, 0x02, 0x60, //
+        0xA9, 0x61, 0x8D, 0x03, 0x60, //
+    ];
+    if reset_first {
+        // the ram survives the reset, so it counts the runs in 0x6100
+        code.extend_from_slice(&[0xEE, 0x00, 0x61, 0xAD, 0x00, 0x61, 0xC9, 0x01]); // INC $6100; LDA $6100; CMP #1
+        code.extend_from_slice(&[0xD0, 0x08]); // BNE report
+        code.extend_from_slice(&[0xA9, 0x81, 0x8D, 0x00, 0x60]); // LDA #$81; STA $6000 - needs reset
+        let wait = PRG_START + code.len() as u16;
+        code.extend_from_slice(&[0x4C, wait as u8, (wait >> 8) as u8]); // JMP wait
+    }
+    // copy the message (with its terminator) to 0x6004
+    let message_address = PRG_START + 0x1000;
+    code.extend_from_slice(&[0xA2, 0x00]); // LDX #0
+    let copy = PRG_START + code.len() as u16;
+    code.extend_from_slice(&[0xBD, message_address as u8, (message_address >> 8) as u8]); // LDA message,X
+    code.extend_from_slice(&[0x9D, 0x04, 0x60, 0xF0, 0x04, 0xE8]); // STA $6004,X; BEQ +4; INX
+    code.extend_from_slice(&[0x4C, copy as u8, (copy >> 8) as u8]); // JMP copy
+    code.extend_from_slice(&[0xA9, final_status, 0x8D, 0x00, 0x60]); // LDA #status; STA $6000
+    let done = PRG_START + code.len() as u16;
+    code.extend_from_slice(&[0x4C, done as u8, (done >> 8) as u8]); // JMP done
+    let rti = PRG_START + code.len() as u16;
+    code.push(0x40);
+
+    let mut prg = vec![0xEA; 0x4000];
+    prg[..code.len()].copy_from_slice(&code);
+    prg[0x1000..0x1000 + message.len()].copy_from_slice(message.as_bytes());
+    prg[0x1000 + message.len()] = 0;
+    // nmi, reset and irq vectors
+    let vectors = [rti, PRG_START, rti];
+    for (i, vector) in vectors.into_iter().enumerate() {
+        prg[0x3FFA + 2 * i..0x3FFC + 2 * i].copy_from_slice(&vector.to_le_bytes());
+    }
+
+    let mut rom = b"NES\x1A\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+    rom.extend_from_slice(&prg);
+    rom.extend_from_slice(&[0; 0x2000]);
+    rom
+}
+
+#[test]
+fn test_passing_rom() {
+    let result = run_test_rom(&protocol_rom(0, "\nAll tests passed\n", false), 600);
+    assert_eq!(result.status, TestRomStatus::Passed);
+    assert_eq!(result.message, "\nAll tests passed\n");
+    assert!(result.frames < 10);
+}
+
+#[test]
+fn test_failing_rom() {
+    let result = run_test_rom(&protocol_rom(3, "BRK\nFailed #3", false), 600);
+    assert_eq!(result.status, TestRomStatus::Failed(3));
+    assert_eq!(result.to_string(), "failed with status 3\nBRK\nFailed #3");
+}
+
+#[test]
+fn test_rom_that_asks_for_a_reset() {
+    let result = run_test_rom(&protocol_rom(0, "Passed", true), 600);
+    assert_eq!(result.status, TestRomStatus::Passed);
+    // it waited for the reset
+    assert!(result.frames > 6);
+}
+
+#[test]
+fn test_timeout_and_crash() {
+    // the rom never writes the signature
+    let mut silent = protocol_rom(0, "", false);
+    silent[16..19].copy_from_slice(&[0x4C, 0x00, 0xC0]); // JMP $C000
+    assert_eq!(run_test_rom(&silent, 30).status, TestRomStatus::TimedOut);
+
+    // mmc1 with 64KB of prg rom, too big to run as nrom
+    let mut unsupported_mapper = protocol_rom(0, "", false);
+    unsupported_mapper[4] = 4;
+    unsupported_mapper[6] = 0x10;
+    unsupported_mapper.resize(16 + 4 * 0x4000 + 0x2000, 0);
+    assert!(matches!(
+        run_test_rom(&unsupported_mapper, 30).status,
+        TestRomStatus::Crashed(_)
+    ));
+}
+
+#[test]
+#[ignore = "the blargg test roms aren't in the repo, put them in tests/test_roms and run with --ignored"]
+fn test_vendored_suites() {
+    assert!(
+        Path::new(VENDORED_SUITES).exists(),
+        "no test roms in {VENDORED_SUITES}"
+    );
+    let mut failures = Vec::new();
+    for path in find_test_roms(Path::new(VENDORED_SUITES)).unwrap() {
+        let result = run_test_rom(&std::fs::read(&path).unwrap(), 60 * 60);
+        if !result.passed() {
+            failures.push(format!("{path:?}: {result}"));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}